@@ -1,40 +1,79 @@
+use std::borrow::Cow;
+
+use advent_2021::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, Solution,
+};
+use anyhow::Context;
 use itertools::Itertools;
 
-const DATA: &str = include_str!("data.txt");
+struct Day;
 
-fn main() {
-    println!("part a: {}", a(DATA));
-    println!("part b: {}", b(DATA));
-}
+impl BasicSolution for Day {
+    const INPUT: Option<&'static str> = embedded_input!(day1);
+    const ANSWERS: Answers = embedded_answers!(day1);
+
+    type Shared = Vec<u32>;
+    type Answer = usize;
+
+    fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
+        input
+            .lines()
+            .map(|line| line.parse().with_context(|| format!("bad depth {line:?}")))
+            .collect()
+    }
+
+    fn part_a(depths: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
+        Ok(depths.iter().tuple_windows().filter(|(a, b)| b > a).count())
+    }
 
-fn a(data: &str) -> usize {
-    data.lines()
-        .map(|line| line.parse::<u32>().unwrap())
-        .tuple_windows()
-        .filter(|(a, b)| b > a)
-        .count()
+    fn part_b(depths: Self::Shared) -> anyhow::Result<Self::Answer> {
+        Ok(depths
+            .iter()
+            .tuple_windows()
+            .filter(|(a, _, _, b)| b > a)
+            .count())
+    }
+
+    /// `size` times 10 depths, mostly increasing.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let mut depth = rng.u32(100..200);
+        let depths = (0..size * 10)
+            .map(|_| {
+                depth = depth.saturating_add_signed(rng.i32(-5..20));
+                depth
+            })
+            .collect_vec();
+        let input = depths.iter().map(|depth| format!("{depth}\n")).collect();
+        Some(Generated::round_trip(input, depths))
+    }
 }
 
-fn b(data: &str) -> usize {
-    data.lines()
-        .map(|line| line.parse::<u32>().unwrap())
-        .tuple_windows()
-        .filter(|(a, _, _, b)| b > a)
-        .count()
+fn main() -> anyhow::Result<()> {
+    Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    const SAMPLE_DATA: &str = include_str!("sample.txt");
 
     #[test]
-    fn test_a() {
-        assert_eq!(a(SAMPLE_DATA), 7);
+    fn a() -> anyhow::Result<()> {
+        Day::test_part_a()
     }
 
     #[test]
-    fn test_b() {
-        assert_eq!(b(SAMPLE_DATA), 5);
+    fn b() -> anyhow::Result<()> {
+        Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2021::sample_tests!(Day, day1);
 }
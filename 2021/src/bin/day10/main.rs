@@ -1,13 +1,14 @@
-use std::collections::HashMap;
+use std::{borrow::Cow, collections::HashMap};
 
+use advent_2021::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, Solution,
+};
+use anyhow::bail;
 use itertools::Itertools;
 
-const DATA: &str = include_str!("data.txt");
-
-fn main() {
-    println!("part a: {}", part_a(DATA));
-    println!("part b: {}", part_b(DATA));
-}
+struct Day;
 
 lazy_static::lazy_static! {
     static ref PAIRS: HashMap<char, char> = HashMap::from([
@@ -18,6 +19,94 @@ lazy_static::lazy_static! {
     ]);
 }
 
+impl BasicSolution for Day {
+    const INPUT: Option<&'static str> = embedded_input!(day10);
+    const ANSWERS: Answers = embedded_answers!(day10);
+
+    type Shared = Vec<&'static str>;
+    type Answer = usize;
+
+    fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
+        input
+            .lines()
+            .map(
+                |line| match line.chars().find(|c| !"()[]{}<>".contains(*c)) {
+                    Some(c) => bail!("unexpected character {c:?} in {line:?}"),
+                    None => Ok(line),
+                },
+            )
+            .collect()
+    }
+
+    fn part_a(lines: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
+        let char_to_score = HashMap::from([(')', 3), (']', 57), ('}', 1197), ('>', 25137)]);
+        Ok(lines
+            .iter()
+            .filter_map(|line| {
+                let mut stack = Vec::new();
+                line.chars().find(|&c| is_corrupt(&mut stack, c))
+            })
+            .map(|illegal_char| char_to_score[&illegal_char])
+            .sum())
+    }
+
+    fn part_b(lines: Self::Shared) -> anyhow::Result<Self::Answer> {
+        let char_to_score = HashMap::from([('(', 1), ('[', 2), ('{', 3), ('<', 4)]);
+        let mut scores = lines
+            .iter()
+            .filter_map(|line| {
+                let mut stack = Vec::new();
+                for c in line.chars() {
+                    if is_corrupt(&mut stack, c) {
+                        return None;
+                    }
+                }
+                Some(stack)
+            })
+            .map(|stack| {
+                stack
+                    .into_iter()
+                    .rev()
+                    .fold(0, |score, char| score * 5 + char_to_score[&char])
+            })
+            .collect_vec();
+        if scores.is_empty() {
+            bail!("every line is corrupted");
+        }
+        scores.sort_unstable();
+        Ok(scores[scores.len() / 2])
+    }
+
+    /// 10 lines of up to `size` times 10 characters, some corrupted and the
+    /// others incomplete, nesting at most 12 deep so the scores fit.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let mut input = String::new();
+        for _ in 0..10 {
+            let mut stack = Vec::new();
+            for _ in 0..rng.usize(1..=size * 10) {
+                let open = ['(', '[', '{', '<'][rng.usize(0..4)];
+                match stack.last() {
+                    Some(&last) if stack.len() == 12 || rng.bool() => {
+                        stack.pop();
+                        if rng.u8(0..50) == 0 {
+                            input.push(PAIRS[&open]);
+                        } else {
+                            input.push(PAIRS[&last]);
+                        }
+                    }
+                    _ => {
+                        stack.push(open);
+                        input.push(open);
+                    }
+                }
+            }
+            input.push('\n');
+        }
+        let lines = input.clone().leak().lines().collect();
+        Some(Generated::round_trip(input, lines))
+    }
+}
+
 fn is_corrupt(stack: &mut Vec<char>, c: char) -> bool {
     if PAIRS.contains_key(&c) {
         stack.push(c);
@@ -31,53 +120,30 @@ fn is_corrupt(stack: &mut Vec<char>, c: char) -> bool {
     true
 }
 
-fn part_a(data: &'static str) -> usize {
-    let char_to_score = HashMap::from([(')', 3), (']', 57), ('}', 1197), ('>', 25137)]);
-    data.lines()
-        .filter_map(|line| {
-            let mut stack = Vec::new();
-            line.chars().find(|&c| is_corrupt(&mut stack, c))
-        })
-        .map(|illegal_char| char_to_score[&illegal_char])
-        .sum()
+fn main() -> anyhow::Result<()> {
+    Day::main()
 }
 
-fn part_b(data: &'static str) -> usize {
-    let char_to_score = HashMap::from([('(', 1), ('[', 2), ('{', 3), ('<', 4)]);
-    let mut scores = data
-        .lines()
-        .filter_map(|line| {
-            let mut stack = Vec::new();
-            for c in line.chars() {
-                if is_corrupt(&mut stack, c) {
-                    return None;
-                }
-            }
-            Some(stack)
-        })
-        .map(|stack| {
-            stack
-                .into_iter()
-                .rev()
-                .fold(0, |score, char| score * 5 + char_to_score[&char])
-        })
-        .collect_vec();
-    scores.sort_unstable();
-    scores[scores.len() / 2]
-}
+register!(Day);
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const SAMPLE_DATA: &str = include_str!("sample.txt");
 
     #[test]
-    fn test_a() {
-        assert_eq!(part_a(SAMPLE_DATA), 26397);
+    fn a() -> anyhow::Result<()> {
+        Day::test_part_a()
     }
 
     #[test]
-    fn test_b() {
-        assert_eq!(part_b(SAMPLE_DATA), 288957);
+    fn b() -> anyhow::Result<()> {
+        Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2021::sample_tests!(Day, day10);
 }
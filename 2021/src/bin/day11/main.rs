@@ -1,77 +1,115 @@
-use std::{collections::HashMap, iter::repeat_with};
+use std::{borrow::Cow, iter::repeat_with};
 
-use itertools::Itertools;
+use advent_2021::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    grid::{Grid, Pos, ADJACENT},
+    register, Answers, BasicSolution, Solution,
+};
+use anyhow::{ensure, Context};
 
-const DATA: &str = include_str!("data.txt");
+struct Day;
 
-fn main() {
-    println!("part a: {}", part_a(DATA));
-    println!("part b: {}", part_b(DATA));
-}
+type Octopuses = Grid<u8>;
+
+const SIZE: usize = 10;
+
+impl BasicSolution for Day {
+    const INPUT: Option<&'static str> = embedded_input!(day11);
+    const ANSWERS: Answers = embedded_answers!(day11);
+
+    type Shared = Octopuses;
+    type Answer = usize;
+
+    fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
+        let grid = Grid::parse_with(input, |c| c.is_ascii_digit().then(|| c - b'0'))?;
+        ensure!(
+            grid.width() == SIZE && grid.height() == SIZE,
+            "expected a {SIZE}x{SIZE} grid"
+        );
+        Ok(grid)
+    }
 
-fn parse(data: &'static str) -> HashMap<(i32, i32), u32> {
-    data.lines()
-        .enumerate()
-        .flat_map(|(row, line)| {
-            line.chars()
-                .map(|c| c.to_digit(10).unwrap())
-                .enumerate()
-                .map(move |(col, energy)| ((row as i32, col as i32), energy))
-        })
-        .collect()
+    fn part_a(grid: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
+        let mut grid = grid.into_owned();
+        Ok(repeat_with(|| step(&mut grid)).take(100).sum())
+    }
+
+    fn part_b(mut grid: Self::Shared) -> anyhow::Result<Self::Answer> {
+        let all = grid.width() * grid.height();
+        // Some grids never synchronize, real inputs do within a few hundred
+        // steps
+        repeat_with(|| step(&mut grid))
+            .take(10_000)
+            .position(|flashes| flashes == all)
+            .map(|steps| steps + 1)
+            .context("the octopuses don't all flash at once within 10000 steps")
+    }
+
+    /// The grid is always 10 by 10, whatever the `size`.
+    fn generate(rng: &mut Rng, _size: usize) -> Option<Generated<Self::Shared>> {
+        let energies = (0..SIZE * SIZE).map(|_| rng.u8(0..10)).collect();
+        let octopuses = Grid::from_vec(SIZE, energies)?;
+        let input = octopuses.map(|&energy| b'0' + energy).to_string();
+        Some(Generated::round_trip(input, octopuses))
+    }
 }
 
-fn flash_if_gt_9(grid: &mut HashMap<(i32, i32), u32>, (y, x): (i32, i32)) {
-    match grid.get_mut(&(y, x)) {
-        Some(energy) if *energy > 9 => *energy = 0,
-        _ => return,
-    };
-    (-1..=1)
-        .cartesian_product(-1..=1)
-        .map(|(dy, dx)| (y + dy, x + dx))
-        .for_each(|adj| {
-            if let Some(energy) = grid.get_mut(&adj) {
-                if *energy > 0 {
-                    *energy += 1;
-                    flash_if_gt_9(grid, adj)
-                }
+fn flash_if_gt_9(grid: &mut Octopuses, pos: Pos) {
+    if grid[pos] <= 9 {
+        return;
+    }
+    grid[pos] = 0;
+    for direction in ADJACENT {
+        if let Some(adj) = grid.offset(pos, direction) {
+            if grid[adj] > 0 {
+                grid[adj] += 1;
+                flash_if_gt_9(grid, adj)
             }
-        })
+        }
+    }
 }
 
-fn step(grid: &mut HashMap<(i32, i32), u32>) -> usize {
-    grid.values_mut().for_each(|e| *e += 1);
-    for p in (0..10).cartesian_product(0..10) {
+fn step(grid: &mut Octopuses) -> usize {
+    for p in grid.positions() {
+        grid[p] += 1;
+    }
+    for p in grid.positions() {
         flash_if_gt_9(grid, p);
     }
-    grid.values().filter(|&&e| e == 0).count()
+    grid.iter().filter(|&(_, &e)| e == 0).count()
 }
 
-fn part_a(data: &'static str) -> usize {
-    let mut grid = parse(data);
-    repeat_with(|| step(&mut grid)).take(100).sum()
+fn main() -> anyhow::Result<()> {
+    Day::main()
 }
 
-fn part_b(data: &'static str) -> usize {
-    let mut grid = parse(data);
-    repeat_with(|| step(&mut grid))
-        .position(|flashes| flashes == 100)
-        .unwrap()
-        + 1
-}
+register!(Day);
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const SAMPLE_DATA: &str = include_str!("sample.txt");
 
     #[test]
-    fn test_a() {
-        assert_eq!(part_a(SAMPLE_DATA), 1656);
+    fn a() -> anyhow::Result<()> {
+        Day::test_part_a()
     }
 
     #[test]
-    fn test_b() {
-        assert_eq!(part_b(SAMPLE_DATA), 195);
+    fn b() -> anyhow::Result<()> {
+        Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    #[test]
+    fn non_digit() {
+        let error = <Day as Solution>::shared("12\n3#\n").unwrap_err();
+        assert!(error.to_string().contains("unexpected '#'"), "{error}");
+    }
+
+    advent_2021::sample_tests!(Day, day11);
 }
@@ -1,15 +1,18 @@
-use std::{collections::HashMap, iter};
+use std::{borrow::Cow, collections::HashMap, iter};
 
+use advent_2021::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, Solution,
+};
+use anyhow::{ensure, Context};
 use itertools::Itertools;
 
-const DATA: &str = include_str!("data.txt");
-const MAX_CAVES: usize = 11;
+struct Day;
 
-fn main() {
-    println!("part a: {}", part_a(DATA));
-    println!("part b: {}", part_b(DATA));
-}
+const MAX_CAVES: usize = 11;
 
+#[derive(Debug, Clone)]
 struct Graph {
     start: usize,
     end: usize,
@@ -17,30 +20,90 @@ struct Graph {
     connected_to: [Vec<usize>; MAX_CAVES],
 }
 
-fn parse(data: &'static str) -> Graph {
-    let mut is_small = [false; MAX_CAVES];
-    let mut connected_to: [Vec<usize>; MAX_CAVES] = Default::default();
-    let str_graph = data
-        .lines()
-        .flat_map(|line| {
-            let (a, b) = line.split('-').collect_tuple().unwrap();
-            [(a, b), (b, a)]
+impl BasicSolution for Day {
+    const INPUT: Option<&'static str> = embedded_input!(day12);
+    const ANSWERS: Answers = embedded_answers!(day12);
+
+    type Shared = Graph;
+    type Answer = usize;
+
+    fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
+        let mut is_small = [false; MAX_CAVES];
+        let mut connected_to: [Vec<usize>; MAX_CAVES] = Default::default();
+        let edges: Vec<_> = input
+            .lines()
+            .map(|line| {
+                line.split_once('-')
+                    .with_context(|| format!("expected 'a-b', got {line:?}"))
+            })
+            .try_collect()?;
+        let str_graph = edges
+            .into_iter()
+            .flat_map(|(a, b)| [(a, b), (b, a)])
+            .filter(|&(a, b)| a != "end" && b != "start")
+            .into_group_map();
+        ensure!(str_graph.contains_key("start"), "no path leaves start");
+        // Caves that only lead back to start have no paths leaving them
+        let names = str_graph
+            .keys()
+            .chain(str_graph.values().flatten())
+            .chain(iter::once(&"end"))
+            .unique()
+            .collect_vec();
+        ensure!(
+            names.len() <= MAX_CAVES,
+            "at most {MAX_CAVES} caves are supported"
+        );
+        let mut name_to_id = HashMap::new();
+        for (id, &name) in names.into_iter().enumerate() {
+            is_small[id] = name.starts_with(|c: char| c.is_ascii_lowercase());
+            name_to_id.insert(name, id);
+        }
+        for (from, to) in str_graph {
+            connected_to[name_to_id[from]] = to.into_iter().map(|name| name_to_id[name]).collect()
+        }
+        Ok(Graph {
+            start: name_to_id["start"],
+            end: name_to_id["end"],
+            is_small,
+            connected_to,
         })
-        .filter(|&(a, b)| a != "end" && b != "start")
-        .into_group_map();
-    let mut name_to_id = HashMap::new();
-    for (id, &name) in str_graph.keys().chain(iter::once(&"end")).enumerate() {
-        is_small[id] = name.chars().next().unwrap().is_ascii_lowercase();
-        name_to_id.insert(name, id);
     }
-    for (from, to) in str_graph {
-        connected_to[name_to_id[from]] = to.into_iter().map(|name| name_to_id[name]).collect()
+
+    fn part_a(graph: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
+        Ok(count_paths(&graph, graph.start, Default::default(), false))
+    }
+
+    fn part_b(graph: Self::Shared) -> anyhow::Result<Self::Answer> {
+        Ok(count_paths(&graph, graph.start, Default::default(), true))
     }
-    Graph {
-        start: name_to_id["start"],
-        end: name_to_id["end"],
-        is_small,
-        connected_to,
+
+    /// Up to `size` + 1 small and `size` / 2 big caves, without two big ones
+    /// next to each other so the paths don't loop forever. Only checked to
+    /// parse, since the caves are numbered in the order of a `HashMap`.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let small = (0..(size + 1).min(5))
+            .map(|i| format!("{}{i}", rng.lowercase()))
+            .collect_vec();
+        let big = (0..(size / 2).min(3))
+            .map(|i| format!("{}{i}", rng.uppercase()))
+            .collect_vec();
+        let caves = ["start".to_owned(), "end".to_owned()]
+            .into_iter()
+            .chain(small)
+            .chain(big)
+            .collect_vec();
+        let is_big = |cave: &str| cave.starts_with(|c: char| c.is_ascii_uppercase());
+        let mut edges = caves
+            .iter()
+            .tuple_combinations()
+            .filter(|(a, b)| !(is_big(a) && is_big(b)))
+            .filter(|_| rng.u8(0..3) == 0)
+            .map(|(a, b)| format!("{a}-{b}\n"))
+            .collect_vec();
+        edges.push(format!("start-{}\n", caves[rng.usize(1..caves.len())]));
+        rng.shuffle(&mut edges);
+        Some(Generated::new(edges.concat()))
     }
 }
 
@@ -68,28 +131,30 @@ fn count_paths(
         .sum()
 }
 
-fn part_a(data: &'static str) -> usize {
-    let graph = parse(data);
-    count_paths(&graph, graph.start, Default::default(), false)
+fn main() -> anyhow::Result<()> {
+    Day::main()
 }
 
-fn part_b(data: &'static str) -> usize {
-    let graph = parse(data);
-    count_paths(&graph, graph.start, Default::default(), true)
-}
+register!(Day);
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const SAMPLE_DATA: &str = include_str!("sample.txt");
 
     #[test]
-    fn test_a() {
-        assert_eq!(part_a(SAMPLE_DATA), 226);
+    fn a() -> anyhow::Result<()> {
+        Day::test_part_a()
     }
 
     #[test]
-    fn test_b() {
-        assert_eq!(part_b(SAMPLE_DATA), 3509);
+    fn b() -> anyhow::Result<()> {
+        Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2021::sample_tests!(Day, day12);
 }
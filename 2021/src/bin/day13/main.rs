@@ -1,91 +1,203 @@
-use std::collections::HashSet;
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    fmt::{self, Display},
+};
 
-const DATA: &str = include_str!("data.txt");
+use advent_2021::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, Solution,
+};
+use anyhow::{bail, Context};
+use itertools::Itertools;
 
-fn main() {
-    println!("part a: {}", part_a(DATA));
-    println!("{}", part_b(DATA));
-}
+struct Day;
 
+#[derive(Debug, Clone, Copy)]
 enum Fold {
-    X(i64),
-    Y(i64),
+    X(usize),
+    Y(usize),
+}
+
+impl Display for Fold {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Fold::X(x) => write!(f, "x={x}"),
+            Fold::Y(y) => write!(f, "y={y}"),
+        }
+    }
 }
 
 type Point = (usize, usize);
 
-fn parse(data: &'static str) -> (HashSet<Point>, Vec<Fold>) {
-    let mut lines = data.lines();
-    let points = lines
-        .by_ref()
-        .take_while(|line| !line.is_empty())
-        .map(|line| {
-            let (x, y) = line.split_once(',').unwrap();
-            (x.parse().unwrap(), y.parse().unwrap())
-        })
-        .collect();
-    let folds = lines
-        .map(|line| {
-            let (axis, value) = line.split_once('=').unwrap();
-            let value = value.parse().unwrap();
-            match axis {
-                "fold along x" => Fold::X(value),
-                "fold along y" => Fold::Y(value),
-                _ => panic!(),
+#[derive(Debug, Clone)]
+struct Manual {
+    points: HashSet<Point>,
+    folds: Vec<Fold>,
+}
+
+impl BasicSolution for Day {
+    const INPUT: Option<&'static str> = embedded_input!(day13);
+    const ANSWERS: Answers = embedded_answers!(day13);
+
+    type Shared = Manual;
+    type Answer = String;
+
+    fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
+        let mut lines = input.lines();
+        let points = lines
+            .by_ref()
+            .take_while(|line| !line.is_empty())
+            .map(point)
+            .collect::<anyhow::Result<_>>()?;
+        let folds = lines.map(fold).collect::<anyhow::Result<_>>()?;
+        Ok(Manual { points, folds })
+    }
+
+    fn part_a(manual: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
+        let Manual { points, folds } = manual.into_owned();
+        let first_fold = folds.first().context("no folds")?;
+        Ok(apply_folds(points, std::slice::from_ref(first_fold))?
+            .len()
+            .to_string())
+    }
+
+    fn part_b(Manual { points, folds }: Self::Shared) -> anyhow::Result<Self::Answer> {
+        let points = apply_folds(points, &folds)?;
+        let (max_x, max_y) = points
+            .iter()
+            .fold((0, 0), |(x1, y1), &(x2, y2)| (x1.max(x2), y1.max(y2)));
+        let mut grid = vec![vec!['.'; max_x + 1]; max_y + 1];
+        grid.iter_mut().for_each(|line| line.push('\n'));
+        points.into_iter().for_each(|(x, y)| grid[y][x] = '█');
+        Ok(grid.into_iter().flatten().collect())
+    }
+
+    /// `size` times 10 dots on a sheet folded up to 8 times, made by
+    /// unfolding dots on the folded sheet so none are on a fold. Only
+    /// checked to parse, since the dots are kept in a `HashSet`.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let (mut width, mut height) = (rng.usize(5..40), rng.usize(3..8));
+        let mut dots = (0..size * 10)
+            .map(|_| (rng.usize(0..width), rng.usize(0..height)))
+            .collect_vec();
+        let mut folds = Vec::new();
+        for _ in 0..rng.usize(1..=8) {
+            let fold = if rng.bool() {
+                let fold = Fold::X(width);
+                width = width * 2 + 1;
+                fold
+            } else {
+                let fold = Fold::Y(height);
+                height = height * 2 + 1;
+                fold
+            };
+            for (x, y) in &mut dots {
+                match fold {
+                    Fold::X(line) if rng.bool() => *x = 2 * line - *x,
+                    Fold::Y(line) if rng.bool() => *y = 2 * line - *y,
+                    _ => {}
+                }
             }
-        })
-        .collect();
-    (points, folds)
+            folds.push(fold);
+        }
+        let mut input = dots.iter().map(|(x, y)| format!("{x},{y}\n")).join("");
+        input.push('\n');
+        for fold in folds.iter().rev() {
+            match fold {
+                Fold::X(x) => input.push_str(&format!("fold along x={x}\n")),
+                Fold::Y(y) => input.push_str(&format!("fold along y={y}\n")),
+            }
+        }
+        Some(Generated::new(input))
+    }
+}
+
+fn point(line: &str) -> anyhow::Result<Point> {
+    let (x, y) = line
+        .split_once(',')
+        .with_context(|| format!("expected 'x,y', got {line:?}"))?;
+    Ok((
+        x.parse().with_context(|| format!("bad x in {line:?}"))?,
+        y.parse().with_context(|| format!("bad y in {line:?}"))?,
+    ))
 }
 
-fn part_a(data: &'static str) -> usize {
-    let (points, folds) = parse(data);
-    apply_folds(points, &folds[0..=0]).len()
+fn fold(line: &str) -> anyhow::Result<Fold> {
+    let (axis, value) = line
+        .split_once('=')
+        .with_context(|| format!("expected 'fold along <axis>=<value>', got {line:?}"))?;
+    let value = value
+        .parse()
+        .with_context(|| format!("bad value in {line:?}"))?;
+    Ok(match axis {
+        "fold along x" => Fold::X(value),
+        "fold along y" => Fold::Y(value),
+        _ => bail!("unknown fold {axis:?}"),
+    })
 }
 
-fn apply_folds(points: HashSet<Point>, folds: &[Fold]) -> HashSet<Point> {
+fn apply_folds(points: HashSet<Point>, folds: &[Fold]) -> anyhow::Result<HashSet<Point>> {
     points
         .into_iter()
         .map(|p| {
-            folds.iter().fold(p, |(x, y), fold| match *fold {
-                Fold::X(v) => ((v - (v - x as i64).abs()) as usize, y),
-                Fold::Y(v) => (x, (v - (v - y as i64).abs()) as usize),
+            folds.iter().try_fold(p, |(x, y), fold| {
+                let folded = match *fold {
+                    Fold::X(v) => mirror(x, v).map(|x| (x, y)),
+                    Fold::Y(v) => mirror(y, v).map(|y| (x, y)),
+                };
+                folded.with_context(|| format!("folding along {fold} moves {x},{y} off the paper"))
             })
         })
         .collect()
 }
 
-fn part_b(data: &'static str) -> String {
-    let (mut points, folds) = parse(data);
-    points = apply_folds(points, &folds);
-    let (max_x, max_y) = points
-        .iter()
-        .fold((0, 0), |(x1, y1), &(x2, y2)| (x1.max(x2), y1.max(y2)));
-    let mut grid = vec![vec!['.'; max_x + 1]; max_y + 1];
-    grid.iter_mut().for_each(|line| line.push('\n'));
-    points.into_iter().for_each(|(x, y)| grid[y][x] = '█');
-    grid.into_iter().flatten().collect()
+/// Where `coord` ends up after folding along `line`, if it's still on the
+/// paper.
+fn mirror(coord: usize, line: usize) -> Option<usize> {
+    if coord <= line {
+        Some(coord)
+    } else {
+        line.checked_mul(2)?.checked_sub(coord)
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    const SAMPLE_DATA: &str = include_str!("sample.txt");
 
     #[test]
-    fn test_a() {
-        assert_eq!(part_a(SAMPLE_DATA), 17);
+    fn a() -> anyhow::Result<()> {
+        Day::test_part_a()
     }
 
     #[test]
-    fn test_b() {
+    fn b() -> anyhow::Result<()> {
+        Day::test_part_b()
+    }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    #[test]
+    fn folded_off_the_paper() -> anyhow::Result<()> {
+        let manual = <Day as Solution>::shared("0,14\n\nfold along y=5\n")?;
+        let error = <Day as Solution>::part_a(Cow::Owned(manual)).unwrap_err();
         assert_eq!(
-            part_b(SAMPLE_DATA),
-            "█████\n\
-             █...█\n\
-             █...█\n\
-             █...█\n\
-             █████\n"
+            error.to_string(),
+            "folding along y=5 moves 0,14 off the paper"
         );
+        Ok(())
     }
+
+    advent_2021::sample_tests!(Day, day13);
 }
@@ -1,25 +1,87 @@
-#![feature(array_windows)]
-use std::{collections::HashMap, iter};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    iter,
+};
 
+use advent_2021::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, Solution,
+};
+use anyhow::{bail, ensure, Context};
 use itertools::{iterate, Itertools};
 
-const DATA: &str = include_str!("data.txt");
+struct Day;
 
-fn main() {
-    println!("part a: {}", part_a(DATA));
-    println!("part b: {}", part_b(DATA));
+#[derive(Debug, Clone)]
+struct Polymer {
+    template: &'static [u8],
+    rules: HashMap<[u8; 2], u8>,
 }
 
-fn solution(data: &'static str, num_steps: usize) -> usize {
-    let lines = data.lines().map(|line| line.as_bytes()).collect_vec();
-    let rules: HashMap<[u8; 2], u8> = lines[2..]
-        .iter()
-        .map(|&line| match *line {
-            [a, b, .., c] => ([a, b], c),
-            _ => panic!(),
-        })
-        .collect();
-    let pair_counts = iterate(lines[0].array_windows().copied().counts(), |prev| {
+impl BasicSolution for Day {
+    const INPUT: Option<&'static str> = embedded_input!(day14);
+    const ANSWERS: Answers = embedded_answers!(day14);
+
+    type Shared = Polymer;
+    type Answer = usize;
+
+    fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
+        let (template, rules) = input
+            .split_once("\n\n")
+            .context("expected a template and rules separated by a blank line")?;
+        let template = template.as_bytes();
+        ensure!(!template.is_empty(), "empty template");
+        let rules: HashMap<[u8; 2], u8> = rules
+            .lines()
+            .map(|line| match *line.as_bytes() {
+                [a, b, b' ', b'-', b'>', b' ', c] => Ok(([a, b], c)),
+                _ => bail!("expected 'AB -> C', got {line:?}"),
+            })
+            .try_collect()?;
+        let elements: HashSet<u8> = template.iter().chain(rules.values()).copied().collect();
+        for pair in elements.iter().cartesian_product(&elements) {
+            let pair = [*pair.0, *pair.1];
+            ensure!(
+                rules.contains_key(&pair),
+                "no rule for {:?}",
+                String::from_utf8_lossy(&pair)
+            );
+        }
+        Ok(Polymer { template, rules })
+    }
+
+    fn part_a(polymer: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
+        Ok(solution(&polymer, 10))
+    }
+
+    fn part_b(polymer: Self::Shared) -> anyhow::Result<Self::Answer> {
+        Ok(solution(&polymer, 40))
+    }
+
+    /// A template of `size` times 5 elements, with a rule for every pair of
+    /// up to 10 of them. Only checked to parse, since the rules are kept in
+    /// a `HashMap`.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let elements = &b"BCFHKNOPSV"[..rng.usize(2..=10)];
+        let template = (0..size * 5)
+            .map(|_| char::from(elements[rng.usize(0..elements.len())]))
+            .collect::<String>();
+        let rules = elements
+            .iter()
+            .cartesian_product(elements)
+            .map(|(&a, &b)| {
+                let c = elements[rng.usize(0..elements.len())];
+                format!("{}{} -> {}\n", char::from(a), char::from(b), char::from(c))
+            })
+            .join("");
+        Some(Generated::new(format!("{template}\n\n{rules}")))
+    }
+}
+
+fn solution(Polymer { template, rules }: &Polymer, num_steps: usize) -> usize {
+    let pair_counts = iterate(template.array_windows().copied().counts(), |prev| {
         prev.iter()
             .flat_map(|(&[a, c], &count)| {
                 let b = rules[&[a, c]];
@@ -29,37 +91,46 @@ fn solution(data: &'static str, num_steps: usize) -> usize {
             .sum()
     })
     .nth(num_steps)
-    .unwrap();
+    .expect("iterate is infinite");
+    let last = *template.last().expect("the template isn't empty");
     let elem_counts = pair_counts
         .into_iter()
         .map(|([c0, _], count)| (c0, count))
-        .chain(iter::once((*lines[0].last().unwrap(), 1)))
+        .chain(iter::once((last, 1)))
         .into_grouping_map()
         .sum();
-    let (min, max) = elem_counts.into_values().minmax().into_option().unwrap();
+    let (min, max) = elem_counts
+        .into_values()
+        .minmax()
+        .into_option()
+        .expect("there's at least one element");
     max - min
 }
 
-fn part_a(data: &'static str) -> usize {
-    solution(data, 10)
+fn main() -> anyhow::Result<()> {
+    Day::main()
 }
 
-fn part_b(data: &'static str) -> usize {
-    solution(data, 40)
-}
+register!(Day);
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const SAMPLE_DATA: &str = include_str!("sample.txt");
 
     #[test]
-    fn test_a() {
-        assert_eq!(part_a(SAMPLE_DATA), 1588);
+    fn a() -> anyhow::Result<()> {
+        Day::test_part_a()
     }
 
     #[test]
-    fn test_b() {
-        assert_eq!(part_b(SAMPLE_DATA), 2188189693529);
+    fn b() -> anyhow::Result<()> {
+        Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2021::sample_tests!(Day, day14);
 }
@@ -1,26 +1,63 @@
-use std::{cmp::Reverse, collections::BinaryHeap};
+use std::borrow::Cow;
 
+use advent_2021::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, search, Answers, BasicSolution, Solution,
+};
+use anyhow::Context;
 use ndarray::{Array, Array2, Axis};
 
-const DATA: &str = include_str!("data.txt");
+struct Day;
 
-fn main() {
-    println!("part a: {}", part_a(DATA));
-    println!("part b: {}", part_b(DATA));
-}
+impl BasicSolution for Day {
+    const INPUT: Option<&'static str> = embedded_input!(day15);
+    const ANSWERS: Answers = embedded_answers!(day15);
 
-fn parse(data: &'static str) -> Array2<u32> {
-    let lines: Vec<_> = data.lines().collect();
-    Array::from_shape_vec(
-        [lines.len(), lines[0].len()],
-        data.chars().filter_map(|c| c.to_digit(10)).collect(),
-    )
-    .unwrap()
-}
+    type Shared = Array2<u32>;
+    type Answer = u32;
+
+    fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
+        let lines: Vec<_> = input.lines().collect();
+        let width = lines.first().context("empty grid")?.len();
+        let risks = input
+            .chars()
+            .filter(|&c| c != '\n')
+            .map(|c| {
+                c.to_digit(10)
+                    .with_context(|| format!("bad risk level {c:?}"))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Array::from_shape_vec([lines.len(), width], risks).context("the grid should be rectangular")
+    }
+
+    fn part_a(grid: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
+        best_total_risk(&grid)
+    }
+
+    fn part_b(grid: Self::Shared) -> anyhow::Result<Self::Answer> {
+        let mut five_grids = grid.clone();
+        for i in 1..=4 {
+            five_grids.append(Axis(0), grid.mapv(|r| cycle(r + i)).view())?;
+        }
+        let mut twent_five_grids = five_grids.clone();
+        for i in 1..=4 {
+            twent_five_grids.append(Axis(1), five_grids.mapv(|r| cycle(r + i)).view())?;
+        }
+        best_total_risk(&twent_five_grids)
+    }
 
-fn part_a(data: &'static str) -> u32 {
-    let grid = parse(data);
-    best_total_risk(&grid)
+    /// A square cave `size` times 5 positions across.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let side = size * 5;
+        let grid = Array::from_shape_simple_fn([side, side], || rng.u32(1..=9));
+        let input = grid
+            .rows()
+            .into_iter()
+            .map(|row| row.iter().map(u32::to_string).collect::<String>() + "\n")
+            .collect();
+        Some(Generated::round_trip(input, grid))
+    }
 }
 
 fn adjacent([x, y]: [usize; 2]) -> impl Iterator<Item = [usize; 2]> {
@@ -34,56 +71,45 @@ fn adjacent([x, y]: [usize; 2]) -> impl Iterator<Item = [usize; 2]> {
     .flatten()
 }
 
-fn best_total_risk(grid: &Array2<u32>) -> u32 {
-    let mut best_known = Array::from_elem(grid.shape(), u32::MAX);
-    let mut queue = BinaryHeap::from([(Reverse(0), [0, 0])]);
-    while let Some((Reverse(total_risk), idx)) = queue.pop() {
-        let best_known_risk = best_known.get_mut(idx).unwrap();
-        if total_risk < *best_known_risk {
-            *best_known_risk = total_risk;
-            for adj in adjacent(idx) {
-                if let Some(risk) = grid.get(adj) {
-                    queue.push((Reverse(total_risk + risk), adj));
-                }
-            }
-        }
-    }
-    *best_known.last().unwrap()
+fn best_total_risk(grid: &Array2<u32>) -> anyhow::Result<u32> {
+    let &[rows, columns] = grid.shape() else {
+        unreachable!("the grid is 2 dimensional")
+    };
+    let successors =
+        |&idx: &[usize; 2]| adjacent(idx).filter_map(|adj| Some((adj, *grid.get(adj)?)));
+    let path = search::dijkstra([0, 0], successors, |&idx| idx == [rows - 1, columns - 1])
+        .context("the bottom right corner can't be reached")?;
+    Ok(path.cost)
 }
 
 fn cycle(risk: u32) -> u32 {
     (risk - 1) % 9 + 1
 }
 
-fn part_b(data: &'static str) -> u32 {
-    let grid = parse(data);
-    let mut five_grids = grid.clone();
-    for i in 1..=4 {
-        five_grids
-            .append(Axis(0), grid.mapv(|r| cycle(r + i)).view())
-            .unwrap()
-    }
-    let mut twent_five_grids = five_grids.clone();
-    for i in 1..=4 {
-        twent_five_grids
-            .append(Axis(1), five_grids.mapv(|r| cycle(r + i)).view())
-            .unwrap()
-    }
-    best_total_risk(&twent_five_grids)
+fn main() -> anyhow::Result<()> {
+    Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    const SAMPLE_DATA: &str = include_str!("sample.txt");
 
     #[test]
-    fn test_a() {
-        assert_eq!(part_a(SAMPLE_DATA), 40);
+    fn a() -> anyhow::Result<()> {
+        Day::test_part_a()
     }
 
     #[test]
-    fn test_b() {
-        assert_eq!(part_b(SAMPLE_DATA), 315);
+    fn b() -> anyhow::Result<()> {
+        Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2021::sample_tests!(Day, day15);
 }
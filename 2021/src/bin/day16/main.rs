@@ -1,22 +1,121 @@
-use std::vec::IntoIter;
-
+use std::{borrow::Cow, vec::IntoIter};
+
+use advent_2021::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, Solution,
+};
+use anyhow::{bail, ensure, Context};
 use itertools::Itertools;
 
-const DATA: &str = include_str!("data.txt");
+struct Day;
 
-fn main() {
-    println!("part a: {}", part_a(DATA));
-    println!("part b: {}", part_b(DATA));
+#[derive(Debug, Clone, Copy)]
+struct ParseOutcome {
+    version_sum: usize,
+    value: usize,
 }
 
-fn parse(data: &'static str) -> Vec<bool> {
-    data.trim()
-        .chars()
-        .flat_map(|c| {
-            let n = c.to_digit(16).unwrap();
-            (0..4).rev().map(move |i| (n & (1 << i)) != 0)
-        })
-        .collect()
+impl BasicSolution for Day {
+    const INPUT: Option<&'static str> = embedded_input!(day16);
+    const ANSWERS: Answers = embedded_answers!(day16);
+
+    type Shared = ParseOutcome;
+    type Answer = usize;
+
+    fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
+        let bits: Vec<bool> = input
+            .trim()
+            .chars()
+            .map(|c| {
+                c.to_digit(16)
+                    .with_context(|| format!("bad hex digit {c:?}"))
+            })
+            .map_ok(|n| (0..4).rev().map(move |i| (n & (1 << i)) != 0))
+            .flatten_ok()
+            .try_collect()?;
+        parse_bits(bits.into_iter().by_ref())
+    }
+
+    fn part_a(outcome: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
+        Ok(outcome.version_sum)
+    }
+
+    fn part_b(outcome: Self::Shared) -> anyhow::Result<Self::Answer> {
+        Ok(outcome.value)
+    }
+
+    /// A transmission of about `size` times 3 packets, where products and
+    /// sums stay small enough not to overflow.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        /// Appends a packet to `bits`, returning what it parses to.
+        fn packet(rng: &mut Rng, bits: &mut Vec<bool>, budget: &mut usize) -> ParseOutcome {
+            let push = |bits: &mut Vec<bool>, n: usize, len: usize| {
+                bits.extend((0..len).rev().map(|i| n & (1 << i) != 0));
+            };
+            let version = rng.usize(0..8);
+            push(bits, version, 3);
+            if *budget == 0 || rng.u8(0..3) == 0 {
+                let value = rng.usize(0..1 << 12);
+                push(bits, 4, 3);
+                let groups = (0..3).rev().map(|i| (value >> (4 * i)) & 0xf);
+                for (i, group) in groups.enumerate() {
+                    bits.push(i < 2);
+                    push(bits, group, 4);
+                }
+                return ParseOutcome {
+                    version_sum: version,
+                    value,
+                };
+            }
+            *budget -= 1;
+            let mut sub_bits = Vec::new();
+            let mut sub_packets = Vec::new();
+            let comparison = rng.u8(0..4) == 0;
+            let count = if comparison { 2 } else { rng.usize(1..=4) };
+            for _ in 0..count {
+                sub_packets.push(packet(rng, &mut sub_bits, budget));
+            }
+            let values = sub_packets.iter().map(|p| p.value).collect_vec();
+            let product = values
+                .iter()
+                .try_fold(1usize, |product, &value| product.checked_mul(value))
+                .filter(|&product| product < 1 << 40);
+            let (type_id, value) = match (comparison, rng.u8(0..4)) {
+                (true, 0) => (5, (values[0] > values[1]) as usize),
+                (true, 1) => (6, (values[0] < values[1]) as usize),
+                (true, _) => (7, (values[0] == values[1]) as usize),
+                (false, 0) if product.is_some() => (1, product.unwrap_or_default()),
+                (false, 1) => (2, *values.iter().min().unwrap_or(&0)),
+                (false, 2) => (3, *values.iter().max().unwrap_or(&0)),
+                (false, _) => (0, values.iter().sum()),
+            };
+            push(bits, type_id, 3);
+            if rng.bool() {
+                bits.push(true);
+                push(bits, count, 11);
+            } else {
+                bits.push(false);
+                push(bits, sub_bits.len(), 15);
+            }
+            bits.extend(sub_bits);
+            ParseOutcome {
+                version_sum: version + sub_packets.iter().map(|p| p.version_sum).sum::<usize>(),
+                value,
+            }
+        }
+        let mut bits = Vec::new();
+        let outcome = packet(rng, &mut bits, &mut (size * 3));
+        bits.resize(bits.len().next_multiple_of(4), false);
+        let input = bits
+            .chunks(4)
+            .map(|nibble| {
+                let n = nibble.iter().fold(0, |n, &bit| n * 2 + u32::from(bit));
+                char::from_digit(n, 16).map(|c| c.to_ascii_uppercase())
+            })
+            .collect::<Option<String>>()?;
+        Some(Generated::round_trip(input + "\n", outcome))
+    }
 }
 
 fn bits_to_number(bits: &[bool]) -> usize {
@@ -27,89 +126,84 @@ fn bits_to_number(bits: &[bool]) -> usize {
         .sum()
 }
 
-fn bits_iter_to_number(bits: impl Iterator<Item = bool>) -> usize {
-    bits_to_number(&bits.collect_vec())
+fn take_number(bits: &mut IntoIter<bool>, len: usize) -> anyhow::Result<usize> {
+    let taken = bits.take(len).collect_vec();
+    ensure!(taken.len() == len, "transmission ended mid-packet");
+    Ok(bits_to_number(&taken))
 }
 
-struct ParseOutcome {
-    version_sum: usize,
-    value: usize,
-}
-
-fn parse_bits(bits: &mut IntoIter<bool>) -> ParseOutcome {
-    let mut version_sum = bits_iter_to_number(bits.take(3));
-    let type_id = bits_iter_to_number(bits.take(3));
+fn parse_bits(bits: &mut IntoIter<bool>) -> anyhow::Result<ParseOutcome> {
+    let mut version_sum = take_number(bits, 3)?;
+    let type_id = take_number(bits, 3)?;
 
     if type_id == 4 {
         let mut literal_bin = Vec::new();
         let mut keep_reading = true;
         while keep_reading {
-            keep_reading = bits.next().unwrap();
+            keep_reading = bits.next().context("transmission ended mid-literal")?;
             literal_bin.extend(bits.take(4))
         }
         let value = bits_to_number(&literal_bin);
-        return ParseOutcome { version_sum, value };
+        return Ok(ParseOutcome { version_sum, value });
     }
 
-    let len_type_is_subpackets = bits.next().unwrap();
+    let len_type_is_subpackets = bits.next().context("missing length type")?;
     let (num_subpackets, num_bits) = if len_type_is_subpackets {
-        (bits_iter_to_number(bits.take(11)), usize::MAX)
+        (take_number(bits, 11)?, usize::MAX)
     } else {
-        (usize::MAX, bits_iter_to_number(bits.take(15)))
+        (usize::MAX, take_number(bits, 15)?)
     };
 
     let bits_left = bits.len();
     let mut sub_values = Vec::new();
     while (bits_left - bits.len()) < num_bits && sub_values.len() < num_subpackets {
-        let parsed = parse_bits(bits);
+        let parsed = parse_bits(bits)?;
         version_sum += parsed.version_sum;
         sub_values.push(parsed.value);
     }
 
+    let comparison = |compare: fn(&usize, &usize) -> bool| match sub_values[..] {
+        [a, b] => Ok(compare(&a, &b) as usize),
+        _ => bail!("comparison packets should have exactly 2 sub-packets"),
+    };
     let value = match type_id {
-        0 => sub_values.into_iter().sum(),
-        1 => sub_values.into_iter().product(),
-        2 => sub_values.into_iter().min().unwrap(),
-        3 => sub_values.into_iter().max().unwrap(),
-        5 => (sub_values[0] > sub_values[1]) as usize,
-        6 => (sub_values[0] < sub_values[1]) as usize,
-        7 => (sub_values[0] == sub_values[1]) as usize,
-        _ => panic!("{type_id} is not a valid type id"),
+        0 => sub_values.iter().sum(),
+        1 => sub_values.iter().product(),
+        2 => *sub_values.iter().min().context("empty minimum packet")?,
+        3 => *sub_values.iter().max().context("empty maximum packet")?,
+        5 => comparison(usize::gt)?,
+        6 => comparison(usize::lt)?,
+        7 => comparison(usize::eq)?,
+        _ => bail!("{type_id} is not a valid type id"),
     };
 
-    ParseOutcome { version_sum, value }
+    Ok(ParseOutcome { version_sum, value })
 }
 
-fn part_a(data: &'static str) -> usize {
-    let bits = parse(data);
-    parse_bits(bits.into_iter().by_ref()).version_sum
+fn main() -> anyhow::Result<()> {
+    Day::main()
 }
 
-fn part_b(data: &'static str) -> usize {
-    let bits = parse(data);
-    parse_bits(bits.into_iter().by_ref()).value
-}
+register!(Day);
 
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    #[test]
+    fn a() -> anyhow::Result<()> {
+        Day::test_part_a()
+    }
+
     #[test]
-    fn test_a() {
-        assert_eq!(part_a("8A004A801A8002F478"), 16);
-        assert_eq!(part_a("620080001611562C8802118E34"), 12);
-        assert_eq!(part_a("C0015000016115A2E0802F182340"), 23);
-        assert_eq!(part_a("A0016C880162017C3686B18A3D4780"), 31);
+    fn b() -> anyhow::Result<()> {
+        Day::test_part_b()
     }
 
     #[test]
-    fn test_b() {
-        assert_eq!(part_b("C200B40A82"), 3);
-        assert_eq!(part_b("04005AC33890"), 54);
-        assert_eq!(part_b("880086C3E88112"), 7);
-        assert_eq!(part_b("CE00C43D881120"), 9);
-        assert_eq!(part_b("D8005AC2A8F0"), 1);
-        assert_eq!(part_b("F600BC2D8F"), 0);
-        assert_eq!(part_b("9C005AC2F8F0"), 0);
-        assert_eq!(part_b("9C0141080250320F1802104A08"), 1);
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
     }
+
+    advent_2021::sample_tests!(Day, day16);
 }
@@ -1,53 +1,123 @@
+use std::borrow::Cow;
+
+use advent_2021::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, Solution,
+};
+use anyhow::{ensure, Context};
 use itertools::{iterate, Itertools};
 use regex::Regex;
 
-const DATA: &str = include_str!("data.txt");
+struct Day;
 
-fn main() {
-    println!("part a: {}", part_a(DATA));
-    println!("part b: {}", part_b(DATA));
-}
+impl BasicSolution for Day {
+    const INPUT: Option<&'static str> = embedded_input!(day17);
+    const ANSWERS: Answers = embedded_answers!(day17);
 
-fn parse(data: &'static str) -> [isize; 4] {
-    let pat = Regex::new(r"target area: x=(-?\d+)\.\.(-?\d+), y=(-?\d+)\.\.(-?\d+)").unwrap();
-    let caps = pat.captures(data).unwrap();
-    [&caps[1], &caps[2], &caps[3], &caps[4]].map(|s| s.parse().unwrap())
-}
+    type Shared = [isize; 4];
+    type Answer = usize;
 
-fn part_a(data: &'static str) -> usize {
-    let [_min_x, _max_x, min_y, _max_y] = parse(data);
-    let vel_y = -1 - min_y;
-    (vel_y * (1 + vel_y) / 2) as usize
-}
+    fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
+        let pat = Regex::new(r"^target area: x=(-?\d+)\.\.(-?\d+), y=(-?\d+)\.\.(-?\d+)$")?;
+        let caps = pat
+            .captures(input.trim_end())
+            .context("expected 'target area: x=<min>..<max>, y=<min>..<max>'")?;
+        // Small enough that the heights and velocities can't overflow
+        let [min_x, max_x, min_y, max_y] = [1, 2, 3, 4].map(|i| {
+            caps[i]
+                .parse::<i32>()
+                .map(|coord| coord as isize)
+                .context("coordinate out of range")
+        });
+        let [min_x, max_x, min_y, max_y] = [min_x?, max_x?, min_y?, max_y?];
+        ensure!(
+            min_x <= max_x && min_y <= max_y,
+            "the target's ranges should go from low to high"
+        );
+        ensure!(
+            min_x > 0 && max_y < 0,
+            "the target should be to the right of and below the launcher"
+        );
+        Ok([min_x, max_x, min_y, max_y])
+    }
 
-fn part_b(data: &'static str) -> usize {
-    let [min_x, max_x, min_y, max_y] = parse(data);
-    let possible_vels_x = 1..=max_x;
-    let possible_vels_y = min_y..-min_y;
-    (possible_vels_x)
-        .cartesian_product(possible_vels_y)
-        .filter(|&(vel_x, vel_y)| {
-            iterate((0, 0, vel_x, vel_y), |&(x, y, vel_x, vel_y)| {
-                (x + vel_x, y + vel_y, vel_x - vel_x.signum(), vel_y - 1)
+    fn part_a(target: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
+        let [_min_x, _max_x, min_y, _max_y] = *target;
+        let vel_y = -1 - min_y;
+        Ok((vel_y * (1 + vel_y) / 2) as usize)
+    }
+
+    fn part_b([min_x, max_x, min_y, max_y]: Self::Shared) -> anyhow::Result<Self::Answer> {
+        let possible_vels_x = 1..=max_x;
+        let possible_vels_y = min_y..-min_y;
+        Ok((possible_vels_x)
+            .cartesian_product(possible_vels_y)
+            .filter(|&(vel_x, vel_y)| {
+                iterate((0, 0, vel_x, vel_y), |&(x, y, vel_x, vel_y)| {
+                    (x + vel_x, y + vel_y, vel_x - vel_x.signum(), vel_y - 1)
+                })
+                .take_while(|&(x, y, _, _)| x <= max_x && y >= min_y)
+                .any(|(x, y, _, _)| min_x <= x && x <= max_x && min_y <= y && y <= max_y)
             })
-            .take_while(|&(x, y, _, _)| x <= max_x && y >= min_y)
-            .any(|(x, y, _, _)| min_x <= x && x <= max_x && min_y <= y && y <= max_y)
-        })
-        .count()
+            .count())
+    }
+
+    /// A target area up to `size` times 10 steps away in each direction.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let reach = size as isize * 10;
+        let (min_x, min_y) = (rng.isize(1..=reach), rng.isize(-reach..=-1));
+        let target = [
+            min_x,
+            min_x + rng.isize(0..=reach / 2),
+            min_y,
+            (min_y + rng.isize(0..=reach / 2)).min(-1),
+        ];
+        let [min_x, max_x, min_y, max_y] = target;
+        let input = format!("target area: x={min_x}..{max_x}, y={min_y}..{max_y}\n");
+        Some(Generated::round_trip(input, target))
+    }
 }
 
+fn main() -> anyhow::Result<()> {
+    Day::main()
+}
+
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    const SAMPLE_DATA: &str = include_str!("sample.txt");
 
     #[test]
-    fn test_a() {
-        assert_eq!(part_a(SAMPLE_DATA), 45);
+    fn a() -> anyhow::Result<()> {
+        Day::test_part_a()
+    }
+
+    #[test]
+    fn b() -> anyhow::Result<()> {
+        Day::test_part_b()
     }
 
     #[test]
-    fn test_b() {
-        assert_eq!(part_b(SAMPLE_DATA), 112);
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
     }
+
+    #[test]
+    fn malformed_targets() {
+        for (input, message) in [
+            ("target area: x=20..30, y=10..-5\n", "from low to high"),
+            (
+                "target area: x=20..30, y=-10..-5, z=1..2\n",
+                "expected 'target area",
+            ),
+            ("target area: x=20..30, y=-9999999999..-5\n", "out of range"),
+        ] {
+            let error = <Day as Solution>::shared(input).unwrap_err();
+            assert!(format!("{error:#}").contains(message), "{input}: {error:#}");
+        }
+    }
+
+    advent_2021::sample_tests!(Day, day17);
 }
@@ -1,11 +1,84 @@
-const DATA: &str = include_str!("data.txt");
+use std::{borrow::Cow, fmt::Display, mem};
 
+use advent_2021::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, ParseError, Solution,
+};
+use anyhow::Context;
 use derive_new::new;
 use itertools::Itertools;
 
-fn main() {
-    println!("part a: {}", part_a(DATA));
-    println!("part b: {}", part_b(DATA));
+struct Day;
+
+impl BasicSolution for Day {
+    const INPUT: Option<&'static str> = embedded_input!(day18);
+    const ANSWERS: Answers = embedded_answers!(day18);
+
+    type Shared = Vec<Vec<Element>>;
+    type Answer = u32;
+
+    fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
+        Ok(input.lines().map(parse_number).collect::<Result<_, _>>()?)
+    }
+
+    fn part_a(numbers: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
+        let final_number = numbers
+            .into_owned()
+            .into_iter()
+            .reduce(sum_snail_numbers)
+            .context("no numbers to add")?;
+        Ok(get_magnitude(&final_number))
+    }
+
+    fn part_b(numbers: Self::Shared) -> anyhow::Result<Self::Answer> {
+        numbers
+            .iter()
+            .tuple_combinations()
+            .flat_map(|(a, b)| [(a, b), (b, a)])
+            .map(|(a, b)| get_magnitude(&sum_snail_numbers(a.clone(), b.clone())))
+            .max()
+            .context("need at least two numbers")
+    }
+
+    /// `size` + 1 reduced numbers, nested up to 4 deep.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        /// Writes a pair to `input` and its regular numbers to `number`,
+        /// with `open` brackets before the first of them.
+        fn pair(
+            rng: &mut Rng,
+            depth: u8,
+            input: &mut String,
+            number: &mut Vec<Element>,
+            open: &mut u8,
+        ) {
+            input.push('[');
+            *open += 1;
+            for i in 0..2 {
+                if i == 1 {
+                    input.push(',');
+                }
+                if depth < 4 && rng.u8(0..3) != 0 {
+                    pair(rng, depth + 1, input, number, open);
+                } else {
+                    let val = rng.u32(0..10);
+                    input.push_str(&val.to_string());
+                    number.push(Element::new(mem::take(open), val));
+                }
+            }
+            input.push(']');
+        }
+        let mut input = String::new();
+        let numbers = (0..=size)
+            .map(|_| {
+                let mut number = Vec::new();
+                pair(rng, 1, &mut input, &mut number, &mut 0);
+                input.push('\n');
+                number
+            })
+            .collect();
+        Some(Generated::round_trip(input, numbers))
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, new)]
@@ -14,20 +87,61 @@ struct Element {
     val: u32,
 }
 
-fn parse(data: &'static str) -> Vec<Vec<Element>> {
-    data.lines().map(parse_number).collect()
+/// Parses a reduced snailfish number, whose pairs nest at most 4 deep and
+/// whose regular numbers are single digits.
+fn parse_number(line: &'static str) -> Result<Vec<Element>, ParseError> {
+    if !line.starts_with('[') {
+        return Err(ParseError::new(
+            line,
+            0,
+            "a snailfish number should be a pair",
+        ));
+    }
+    let mut number = Vec::new();
+    let rest = parse_element(line, line, 0, &mut number, &mut 0)?;
+    if !rest.is_empty() {
+        return Err(error_at(line, rest, "expected the end of the line"));
+    }
+    Ok(number)
+}
+
+/// Parses a pair or a regular number `depth` pairs deep into `number`, with
+/// `open` brackets before it, and returns the rest of `line`.
+fn parse_element<'a>(
+    line: &'static str,
+    input: &'a str,
+    depth: u8,
+    number: &mut Vec<Element>,
+    open: &mut u8,
+) -> Result<&'a str, ParseError> {
+    let expect = |input: &'a str, c| {
+        input
+            .strip_prefix(c)
+            .ok_or_else(|| error_at(line, input, format!("expected {c:?}")))
+    };
+    match input.bytes().next() {
+        Some(b'[') if depth == 4 => Err(error_at(
+            line,
+            input,
+            "pairs nest more than 4 deep, so the number isn't reduced",
+        )),
+        Some(b'[') => {
+            *open += 1;
+            let rest = parse_element(line, &input[1..], depth + 1, number, open)?;
+            let rest = parse_element(line, expect(rest, ',')?, depth + 1, number, open)?;
+            expect(rest, ']')
+        }
+        Some(c @ b'0'..=b'9') => {
+            number.push(Element::new(mem::take(open), (c - b'0').into()));
+            Ok(&input[1..])
+        }
+        _ => Err(error_at(line, input, "expected a digit or '['")),
+    }
 }
 
-fn parse_number(number: &'static str) -> Vec<Element> {
-    number
-        .split_inclusive(|c: char| c.is_ascii_digit())
-        .flat_map(|elem| {
-            elem.chars()
-                .last()
-                .and_then(|c| c.to_digit(10))
-                .map(|val| Element::new(elem.chars().filter(|&c| c == '[').count() as u8, val))
-        })
-        .collect()
+/// An error in `line` where `rest` of it starts.
+fn error_at(line: &'static str, rest: &str, message: impl Display) -> ParseError {
+    ParseError::new(line, line.len() - rest.len(), message)
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -108,39 +222,47 @@ fn sum_snail_numbers(mut a: Vec<Element>, mut b: Vec<Element>) -> Vec<Element> {
     a
 }
 
-fn part_a(data: &'static str) -> u32 {
-    let numbers = parse(data);
-    let final_number = numbers.into_iter().reduce(sum_snail_numbers).unwrap();
-    get_magnitude(&final_number)
+fn main() -> anyhow::Result<()> {
+    Day::main()
 }
 
-fn part_b(data: &'static str) -> u32 {
-    let nums = parse(data);
-    nums.iter()
-        .tuple_combinations()
-        .flat_map(|(a, b)| [(a, b), (b, a)])
-        .map(|(a, b)| get_magnitude(&sum_snail_numbers(a.clone(), b.clone())))
-        .max()
-        .unwrap()
-}
+register!(Day);
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const SAMPLE_DATA: &str = include_str!("sample.txt");
 
     #[test]
     fn test_parse_number() {
         assert_eq!(
-            parse_number("[[1,2],3]"),
+            parse_number("[[1,2],3]").unwrap(),
             [Element::new(2, 1), Element::new(0, 2), Element::new(0, 3),]
         )
     }
 
+    #[test]
+    fn malformed_numbers() {
+        for (line, column, message) in [
+            ("7", 1, "should be a pair"),
+            ("[1,2", 5, "expected ']'"),
+            ("[1;2]", 3, "expected ','"),
+            ("[1,2]]", 6, "expected the end"),
+            ("[12,3]", 3, "expected ','"),
+            ("[[1,x],3]", 5, "expected a digit"),
+            ("[[[[[1,2],3],4],5],6]", 5, "more than 4 deep"),
+        ] {
+            let error = parse_number(line).unwrap_err();
+            assert_eq!(error.column(), column, "{line}: {error}");
+            assert!(error.to_string().contains(message), "{line}: {error}");
+        }
+    }
+
     #[test]
     fn test_reduce_snail_number() {
-        let mut number = parse_number("[[[[[4,3],4],4],[7,[[8,4],9]]],[1,1]]");
-        reduce_snail_number(&mut number);
+        let number = sum_snail_numbers(
+            parse_number("[[[[4,3],4],4],[7,[[8,4],9]]]").unwrap(),
+            parse_number("[1,1]").unwrap(),
+        );
         assert_eq!(
             number.into_iter().map(|e| e.val).collect_vec(),
             [0, 7, 4, 7, 8, 6, 0, 8, 1]
@@ -149,7 +271,7 @@ mod tests {
 
     #[test]
     fn test_get_magnitue() {
-        let test = |num, mag| assert_eq!(get_magnitude(&parse_number(num)), mag);
+        let test = |num, mag| assert_eq!(get_magnitude(&parse_number(num).unwrap()), mag);
         test("[[1,2],[[3,4],5]]", 143);
         test("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]", 1384);
         test("[[[[1,1],[2,2]],[3,3]],[4,4]]", 445);
@@ -162,12 +284,19 @@ mod tests {
     }
 
     #[test]
-    fn test_a() {
-        assert_eq!(part_a(SAMPLE_DATA), 4140);
+    fn a() -> anyhow::Result<()> {
+        Day::test_part_a()
     }
 
     #[test]
-    fn test_b() {
-        assert_eq!(part_b(SAMPLE_DATA), 3993);
+    fn b() -> anyhow::Result<()> {
+        Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2021::sample_tests!(Day, day18);
 }
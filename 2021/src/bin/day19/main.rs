@@ -1,29 +1,106 @@
-use std::collections::HashSet;
+use std::{borrow::Cow, collections::HashSet};
 
+use advent_2021::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, Solution,
+};
+use anyhow::{bail, Context};
 use glam::IVec3;
 use itertools::Itertools;
 
-const DATA: &str = include_str!("data.txt");
+struct Day;
 
-fn main() {
-    let unalligned = parse(DATA);
-    let (alligned, allignements) = allign_all_scanners(unalligned);
-    println!("part a: {}", num_beacons(alligned));
-    println!("part b: {}", greatest_manhatten_distance(allignements));
+#[derive(Debug, Clone)]
+struct Allignment {
+    alligned: Vec<Vec<IVec3>>,
+    allignements: Vec<IVec3>,
 }
 
-fn parse(data: &'static str) -> Vec<Vec<IVec3>> {
-    data.split("\n\n")
+impl BasicSolution for Day {
+    const INPUT: Option<&'static str> = embedded_input!(day19);
+    const ANSWERS: Answers = embedded_answers!(day19);
+
+    type Shared = Allignment;
+    type Answer = usize;
+
+    fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
+        allign_all_scanners(parse(input)?)
+    }
+
+    fn part_a(allignment: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
+        Ok(allignment.alligned.iter().flatten().unique().count())
+    }
+
+    fn part_b(allignment: Self::Shared) -> anyhow::Result<Self::Answer> {
+        allignment
+            .allignements
+            .into_iter()
+            .tuple_combinations()
+            .map(|(p1, p2)| (p1 - p2).abs())
+            .map(|dist| (dist.x + dist.y + dist.z) as usize)
+            .max()
+            .context("need at least two scanners")
+    }
+
+    /// `size` + 1 scanners in a chain, each turned a random way and sharing
+    /// 13 beacons with the next, so a repeated distance between them still
+    /// leaves the 66 needed to match. Only checked to parse, since parsing
+    /// aligns the scanners too, and in whatever order it finds matches.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let point = |rng: &mut Rng, reach: i32| {
+            IVec3::new(
+                rng.i32(-reach..=reach),
+                rng.i32(-reach..=reach),
+                rng.i32(-reach..=reach),
+            )
+        };
+        let mut positions = vec![IVec3::ZERO];
+        let mut beacons = vec![vec![]];
+        for i in 0..size {
+            let position = positions[i] + point(rng, 1000);
+            let middle = (positions[i] + position) / 2;
+            let shared = (0..13).map(|_| middle + point(rng, 500)).collect_vec();
+            beacons[i].extend(&shared);
+            beacons.push(shared);
+            positions.push(position);
+        }
+        for (position, beacons) in positions.iter().zip(&mut beacons) {
+            for _ in 0..rng.usize(0..10) {
+                beacons.push(*position + point(rng, 800));
+            }
+        }
+        let input = positions
+            .iter()
+            .zip(&beacons)
+            .enumerate()
+            .map(|(i, (&position, beacons))| {
+                let turn = rng.usize(0..24);
+                let lines = beacons
+                    .iter()
+                    .map(|&beacon| rotation(beacon - position, turn))
+                    .map(|p| format!("{},{},{}\n", p.x, p.y, p.z))
+                    .join("");
+                format!("--- scanner {i} ---\n{lines}")
+            })
+            .join("\n");
+        Some(Generated::new(input))
+    }
+}
+
+fn parse(input: &'static str) -> anyhow::Result<Vec<Vec<IVec3>>> {
+    input
+        .split("\n\n")
         .map(|scan| {
             scan.lines()
                 .skip(1)
                 .map(|line| {
                     let (x, y, z) = line
                         .split(',')
-                        .map(|n| n.parse().unwrap())
+                        .map(str::parse)
                         .collect_tuple()
-                        .unwrap();
-                    IVec3::new(x, y, z)
+                        .with_context(|| format!("expected 'x,y,z', found {line:?}"))?;
+                    Ok(IVec3::new(x?, y?, z?))
                 })
                 .collect()
         })
@@ -77,7 +154,7 @@ fn distances_squared(scan: &[IVec3]) -> HashSet<i32> {
     scan.iter().tuple_combinations().map(dist_squared).collect()
 }
 
-fn allign_all_scanners(unalligned: Vec<Vec<IVec3>>) -> (Vec<Vec<IVec3>>, Vec<IVec3>) {
+fn allign_all_scanners(unalligned: Vec<Vec<IVec3>>) -> anyhow::Result<Allignment> {
     let distances = unalligned
         .iter()
         .map(|s| distances_squared(s))
@@ -91,38 +168,17 @@ fn allign_all_scanners(unalligned: Vec<Vec<IVec3>>) -> (Vec<Vec<IVec3>>, Vec<IVe
         .collect_vec();
     let mut alligned: Vec<Vec<IVec3>> = vec![vec![]; unalligned.len()];
     let mut allignements = vec![IVec3::new(0, 0, 0)];
-    alligned[0] = unalligned[0].clone();
+    alligned[0] = unalligned.first().context("no scanners")?.clone();
     while let Some(&(cur, next)) = matches
         .iter()
         .find(|&&(cur, next)| !alligned[cur].is_empty() && alligned[next].is_empty())
     {
-        let common_distance = *distances[cur]
+        let (n, allignement) = distances[cur]
             .intersection(&distances[next])
-            .next()
-            .unwrap();
-        let (&p1_alligned, &p2_alligned) = alligned[cur]
-            .iter()
-            .tuple_combinations()
-            .find(|&pair| dist_squared(pair) == common_distance)
-            .unwrap();
-        let (&p1_unalligned, &p2_unalligned) = unalligned[next]
-            .iter()
-            .tuple_combinations()
-            .find(|&pair| dist_squared(pair) == common_distance)
-            .unwrap();
-        let (n, (p1_corrected, p2_corrected)) = all_rotations(p1_unalligned)
-            .zip(all_rotations(p2_unalligned))
-            .find_position(|&(candidate1, candidate2)| {
-                (candidate1 - p1_alligned == candidate2 - p2_alligned)
-                    || (candidate2 - p1_alligned == candidate1 - p2_alligned)
+            .find_map(|&common_distance| {
+                try_allign(&alligned[cur], &unalligned[next], common_distance)
             })
-            .unwrap();
-        let allignement = if p1_alligned - p1_corrected == p2_alligned - p2_corrected {
-            p1_alligned - p1_corrected
-        } else {
-            assert_eq!(p2_alligned - p1_corrected, p1_alligned - p2_corrected);
-            p2_alligned - p1_corrected
-        };
+            .context("no shared distance lines up the matched scanners")?;
         allignements.push(allignement);
         let new_alligned = unalligned[next]
             .iter()
@@ -130,29 +186,59 @@ fn allign_all_scanners(unalligned: Vec<Vec<IVec3>>) -> (Vec<Vec<IVec3>>, Vec<IVe
             .collect();
         alligned[next] = new_alligned;
     }
-    (alligned, allignements)
+    if alligned.iter().any(Vec::is_empty) {
+        bail!("some scanners don't overlap with any others");
+    }
+    Ok(Allignment {
+        alligned,
+        allignements,
+    })
 }
 
-fn num_beacons(alligned: Vec<Vec<IVec3>>) -> usize {
-    alligned.into_iter().flatten().unique().count()
+/// Finds the rotation and offset that line `unalligned` up with `alligned`,
+/// guessing from the pairs of beacons `common_distance` apart. Distances
+/// can repeat by coincidence, so the guess only counts if 12 beacons overlap.
+fn try_allign(
+    alligned: &[IVec3],
+    unalligned: &[IVec3],
+    common_distance: i32,
+) -> Option<(usize, IVec3)> {
+    let (&p1_alligned, &p2_alligned) = alligned
+        .iter()
+        .tuple_combinations()
+        .find(|&pair| dist_squared(pair) == common_distance)?;
+    let (&p1_unalligned, &p2_unalligned) = unalligned
+        .iter()
+        .tuple_combinations()
+        .find(|&pair| dist_squared(pair) == common_distance)?;
+    let (n, (p1_corrected, p2_corrected)) = all_rotations(p1_unalligned)
+        .zip(all_rotations(p2_unalligned))
+        .find_position(|&(candidate1, candidate2)| {
+            (candidate1 - p1_alligned == candidate2 - p2_alligned)
+                || (candidate2 - p1_alligned == candidate1 - p2_alligned)
+        })?;
+    let allignement = if p1_alligned - p1_corrected == p2_alligned - p2_corrected {
+        p1_alligned - p1_corrected
+    } else {
+        p2_alligned - p1_corrected
+    };
+    let alligned: HashSet<_> = alligned.iter().collect();
+    let overlap = unalligned
+        .iter()
+        .filter(|&&p| alligned.contains(&(rotation(p, n) + allignement)))
+        .count();
+    (overlap >= 12).then_some((n, allignement))
 }
 
-fn greatest_manhatten_distance(allignements: Vec<IVec3>) -> usize {
-    allignements
-        .into_iter()
-        .tuple_combinations()
-        .map(|(p1, p2)| (p1 - p2).abs())
-        .map(|dist| dist.x + dist.y + dist.z)
-        .max()
-        .unwrap() as usize
+fn main() -> anyhow::Result<()> {
+    Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
-    use itertools::Itertools;
-
     use super::*;
-    const SAMPLE_DATA: &str = include_str!("sample.txt");
 
     #[test]
     fn test_all_rotations() {
@@ -160,16 +246,19 @@ mod tests {
     }
 
     #[test]
-    fn test_a() {
-        let unalligned = parse(SAMPLE_DATA);
-        let (alligned, _) = allign_all_scanners(unalligned);
-        assert_eq!(num_beacons(alligned), 79);
+    fn a() -> anyhow::Result<()> {
+        Day::test_part_a()
     }
 
     #[test]
-    fn test_b() {
-        let unalligned = parse(SAMPLE_DATA);
-        let (_, allignements) = allign_all_scanners(unalligned);
-        assert_eq!(greatest_manhatten_distance(allignements), 3621);
+    fn b() -> anyhow::Result<()> {
+        Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2021::sample_tests!(Day, day19);
 }
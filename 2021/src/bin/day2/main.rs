@@ -1,74 +1,123 @@
-use itertools::Itertools;
-use Command::{Down, Forward, Up};
+use std::borrow::Cow;
 
-const DATA: &str = include_str!("data.txt");
+use advent_2021::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, Solution,
+};
+use anyhow::{bail, Context};
+use Command::{Down, Forward, Up};
 
-fn main() {
-    println!("part a: {}", part_a(DATA));
-    println!("part b: {}", part_b(DATA));
-}
+struct Day;
 
+#[derive(Debug, Clone, Copy)]
 enum Command {
     Forward(i32),
     Down(i32),
     Up(i32),
 }
 
-impl From<&str> for Command {
-    fn from(s: &str) -> Self {
-        let (direction, amount) = s.split_whitespace().collect_tuple().unwrap();
-        let n = amount.parse().unwrap();
-        match direction {
-            "forward" => Forward(n),
-            "down" => Down(n),
-            "up" => Up(n),
-            _ => panic!(),
-        }
+impl BasicSolution for Day {
+    const INPUT: Option<&'static str> = embedded_input!(day2);
+    const ANSWERS: Answers = embedded_answers!(day2);
+
+    type Shared = Vec<Command>;
+    type Answer = i32;
+
+    fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
+        input.lines().map(command).collect()
     }
-}
 
-fn part_a(data: &str) -> i32 {
-    let mut dist = 0;
-    let mut depth = 0;
-    for command in data.lines().map(Command::from) {
-        match command {
-            Forward(n) => dist += n,
-            Down(n) => depth += n,
-            Up(n) => depth -= n,
+    fn part_a(commands: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
+        let mut dist = 0;
+        let mut depth = 0;
+        for &command in commands.iter() {
+            match command {
+                Forward(n) => dist += n,
+                Down(n) => depth += n,
+                Up(n) => depth -= n,
+            }
         }
+        Ok(dist * depth)
     }
-    dist * depth
-}
 
-fn part_b(data: &str) -> i32 {
-    let mut dist = 0;
-    let mut depth = 0;
-    let mut aim = 0;
-    for command in data.lines().map(Command::from) {
-        match command {
-            Forward(n) => {
-                dist += n;
-                depth += aim * n
+    fn part_b(commands: Self::Shared) -> anyhow::Result<Self::Answer> {
+        let mut dist = 0;
+        let mut depth = 0;
+        let mut aim = 0;
+        for command in commands {
+            match command {
+                Forward(n) => {
+                    dist += n;
+                    depth += aim * n
+                }
+                Down(n) => aim += n,
+                Up(n) => aim -= n,
             }
-            Down(n) => aim += n,
-            Up(n) => aim -= n,
         }
+        Ok(dist * depth)
+    }
+
+    /// `size` times 10 commands.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let commands = (0..size * 10)
+            .map(|_| match rng.u8(0..3) {
+                0 => Forward(rng.i32(1..10)),
+                1 => Down(rng.i32(1..10)),
+                _ => Up(rng.i32(1..10)),
+            })
+            .collect::<Vec<_>>();
+        let input = commands
+            .iter()
+            .map(|command| match command {
+                Forward(n) => format!("forward {n}\n"),
+                Down(n) => format!("down {n}\n"),
+                Up(n) => format!("up {n}\n"),
+            })
+            .collect();
+        Some(Generated::round_trip(input, commands))
     }
-    dist * depth
 }
 
+fn command(line: &str) -> anyhow::Result<Command> {
+    let (direction, amount) = line
+        .split_once(' ')
+        .with_context(|| format!("expected '<direction> <amount>', got {line:?}"))?;
+    let n = amount
+        .parse()
+        .with_context(|| format!("bad amount {amount:?}"))?;
+    Ok(match direction {
+        "forward" => Forward(n),
+        "down" => Down(n),
+        "up" => Up(n),
+        _ => bail!("unknown direction {direction:?}"),
+    })
+}
+
+fn main() -> anyhow::Result<()> {
+    Day::main()
+}
+
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    const SAMPLE_DATA: &str = include_str!("sample.txt");
 
     #[test]
-    fn test_a() {
-        assert_eq!(part_a(SAMPLE_DATA), 150);
+    fn a() -> anyhow::Result<()> {
+        Day::test_part_a()
     }
 
     #[test]
-    fn test_b() {
-        assert_eq!(part_b(SAMPLE_DATA), 900);
+    fn b() -> anyhow::Result<()> {
+        Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2021::sample_tests!(Day, day2);
 }
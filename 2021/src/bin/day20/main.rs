@@ -1,35 +1,84 @@
 #![allow(clippy::reversed_empty_ranges)]
+use std::borrow::Cow;
+
+use advent_2021::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, Solution,
+};
+use anyhow::{ensure, Context};
 use derive_new::new;
 use ndarray::{s, Array, Array2, Zip};
 
-const DATA: &str = include_str!("data.txt");
+struct Day;
+
+impl BasicSolution for Day {
+    const INPUT: Option<&'static str> = embedded_input!(day20);
+    const ANSWERS: Answers = embedded_answers!(day20);
+
+    type Shared = (Vec<bool>, Image);
+    type Answer = usize;
+
+    fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
+        let (image_enhancement, image) = input
+            .split_once("\n\n")
+            .context("expected the algorithm and the image separated by a blank line")?;
+        let image_enhancement: Vec<bool> = image_enhancement.chars().map(|c| c == '#').collect();
+        ensure!(
+            image_enhancement.len() == 512,
+            "the enhancement algorithm should have 512 entries"
+        );
+        let lines: Vec<_> = image.lines().collect();
+        let (rows, cols) = (lines.len(), lines.first().context("empty image")?.len());
+        let default = false;
+        let mut data = Array2::from_elem([rows + 4, cols + 4], default);
+        let data_vec = lines
+            .iter()
+            .flat_map(|line| line.chars().map(|c| c == '#'))
+            .collect();
+        data.slice_mut(s![2..-2, 2..-2]).assign(
+            &Array::from_shape_vec([rows, cols], data_vec)
+                .context("the image should be rectangular")?,
+        );
+        Ok((image_enhancement, Image::new(data, default)))
+    }
+
+    fn part_a(shared: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
+        let (image_enhancement, image) = shared.as_ref();
+        Ok(solve(image_enhancement, image, 2))
+    }
+
+    fn part_b((image_enhancement, image): Self::Shared) -> anyhow::Result<Self::Answer> {
+        Ok(solve(&image_enhancement, &image, 50))
+    }
 
-fn main() {
-    println!("part a: {}", part_a(DATA));
-    println!("part b: {}", part_b(DATA));
+    /// A random algorithm and a square image `size` times 5 pixels across.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let side = size * 5;
+        let image_enhancement = (0..512).map(|_| rng.bool()).collect::<Vec<_>>();
+        let pixels = Array2::from_shape_simple_fn([side, side], || rng.bool());
+        let pixel = |&lit: &bool| if lit { '#' } else { '.' };
+        let mut input: String = image_enhancement.iter().map(pixel).collect();
+        input.push_str("\n\n");
+        for row in pixels.rows() {
+            input.extend(row.iter().map(pixel));
+            input.push('\n');
+        }
+        let mut data = Array2::from_elem([side + 4, side + 4], false);
+        data.slice_mut(s![2..-2, 2..-2]).assign(&pixels);
+        Some(Generated::round_trip(
+            input,
+            (image_enhancement, Image::new(data, false)),
+        ))
+    }
 }
 
-#[derive(new)]
+#[derive(Debug, Clone, new)]
 struct Image {
     data: Array2<bool>,
     default: bool,
 }
 
-fn parse(data: &'static str) -> (Vec<bool>, Image) {
-    let lines: Vec<_> = data.lines().collect();
-    let image_enhancement = lines[0].chars().map(|c| c == '#').collect();
-    let (rows, cols) = (lines[2..].len(), lines[2].len());
-    let default = false;
-    let mut data = Array2::from_elem([rows + 4, cols + 4], default);
-    let data_vec = lines[2..]
-        .iter()
-        .flat_map(|line| line.chars().map(|c| c == '#'))
-        .collect();
-    data.slice_mut(s![2..-2, 2..-2])
-        .assign(&Array::from_shape_vec([rows, cols], data_vec).unwrap());
-    (image_enhancement, Image::new(data, default))
-}
-
 fn enhance_image(image_enhancement: &[bool], image: &Image) -> Image {
     let shape = image.data.shape();
     let default = image_enhancement[if image.default { 0b111111111 } else { 0 }];
@@ -52,34 +101,38 @@ fn enhance_image(image_enhancement: &[bool], image: &Image) -> Image {
     Image::new(data, default)
 }
 
-fn solve(data: &'static str, iterations: usize) -> usize {
-    let (image_enhancement, mut image) = parse(data);
-    for _ in 0..iterations {
-        image = enhance_image(&image_enhancement, &image)
+fn solve(image_enhancement: &[bool], image: &Image, iterations: usize) -> usize {
+    let mut image = enhance_image(image_enhancement, image);
+    for _ in 1..iterations {
+        image = enhance_image(image_enhancement, &image)
     }
     image.data.map(|&b| b as usize).sum()
 }
 
-fn part_a(data: &'static str) -> usize {
-    solve(data, 2)
+fn main() -> anyhow::Result<()> {
+    Day::main()
 }
 
-fn part_b(data: &'static str) -> usize {
-    solve(data, 50)
-}
+register!(Day);
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const SAMPLE_DATA: &str = include_str!("sample.txt");
 
     #[test]
-    fn test_a() {
-        assert_eq!(part_a(SAMPLE_DATA), 35);
+    fn a() -> anyhow::Result<()> {
+        Day::test_part_a()
     }
 
     #[test]
-    fn test_b() {
-        assert_eq!(part_b(SAMPLE_DATA), 3351);
+    fn b() -> anyhow::Result<()> {
+        Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2021::sample_tests!(Day, day20);
 }
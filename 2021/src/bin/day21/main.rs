@@ -1,37 +1,84 @@
+use std::borrow::Cow;
+
+use advent_2021::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, Solution,
+};
+use anyhow::{ensure, Context};
 use derive_new::new;
+use itertools::Itertools;
 use num::Integer;
 
-const DATA: &str = include_str!("data.txt");
+struct Day;
 
-fn main() {
-    println!("part a: {}", part_a(DATA));
-    println!("part b: {}", part_b(DATA));
-}
+impl BasicSolution for Day {
+    const INPUT: Option<&'static str> = embedded_input!(day21);
+    const ANSWERS: Answers = embedded_answers!(day21);
 
-fn parse(data: &'static str) -> [usize; 2] {
-    let mut positions = data
-        .lines()
-        .map(|line| line.chars().last().unwrap().to_digit(10).unwrap() as usize);
-    [positions.next().unwrap(), positions.next().unwrap()]
-}
+    type Shared = [usize; 2];
+    type Answer = usize;
 
-fn cycle<T: Integer>(val: T, quot: T) -> T {
-    (val - T::one()) % quot + T::one()
-}
+    fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
+        let (p1, p2) = input
+            .lines()
+            .map(|line| {
+                let (_, position) = line
+                    .split_once("starting position: ")
+                    .with_context(|| format!("expected a starting position, found {line:?}"))?;
+                let position: usize = position.parse()?;
+                ensure!(
+                    (1..=10).contains(&position),
+                    "position {position} is off the board"
+                );
+                Ok(position)
+            })
+            .collect_tuple()
+            .context("expected exactly two players")?;
+        Ok([p1?, p2?])
+    }
 
-fn part_a(data: &'static str) -> usize {
-    let mut positions = parse(data);
-    let mut scores = [0, 0];
-    for turn in 0.. {
-        let player_idx = turn % 2;
-        let roll_sum = (1..=3).map(|i| cycle(turn * 3 + i, 100)).sum::<usize>();
-        positions[player_idx] = cycle(positions[player_idx] + roll_sum, 10);
-        scores[player_idx] += positions[player_idx];
-        if scores[player_idx] >= 1000 {
-            return scores[(player_idx + 1) % 2] * (turn + 1) * 3;
+    fn part_a(positions: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
+        let mut positions = *positions;
+        let mut scores = [0, 0];
+        for turn in 0.. {
+            let player_idx = turn % 2;
+            let roll_sum = (1..=3).map(|i| cycle(turn * 3 + i, 100)).sum::<usize>();
+            positions[player_idx] = cycle(positions[player_idx] + roll_sum, 10);
+            scores[player_idx] += positions[player_idx];
+            if scores[player_idx] >= 1000 {
+                return Ok(scores[(player_idx + 1) % 2] * (turn + 1) * 3);
+            }
         }
+        unreachable!()
+    }
+
+    fn part_b(player_positions: Self::Shared) -> anyhow::Result<Self::Answer> {
+        let player_win_lose_per_turn: [Vec<[usize; 2]>; 2] = player_positions
+            .map(QuantumIterate::from)
+            .map(|it| it.collect());
+        let [p1, p2] = player_win_lose_per_turn;
+        let total_wins = [(&p1[1..], &p2[..]), (&p2[..], &p1[..])].map(|(winner, loser)| {
+            winner
+                .iter()
+                .zip(loser.iter())
+                .map(|([wins, _], [_, losses])| wins * losses)
+                .sum::<usize>()
+        });
+        Ok(total_wins[0].max(total_wins[1]))
+    }
+
+    /// Two random starting positions, the game is the same size regardless.
+    fn generate(rng: &mut Rng, _size: usize) -> Option<Generated<Self::Shared>> {
+        let positions = [rng.usize(1..=10), rng.usize(1..=10)];
+        let [p1, p2] = positions;
+        let input = format!("Player 1 starting position: {p1}\nPlayer 2 starting position: {p2}\n");
+        Some(Generated::round_trip(input, positions))
     }
-    unreachable!()
+}
+
+fn cycle<T: Integer>(val: T, quot: T) -> T {
+    (val - T::one()) % quot + T::one()
 }
 
 #[derive(new, Clone, Copy)]
@@ -77,34 +124,30 @@ impl Iterator for QuantumIterate {
     }
 }
 
-fn part_b(data: &'static str) -> usize {
-    let player_positions = parse(data);
-    let player_win_lose_per_turn: [Vec<[usize; 2]>; 2] = player_positions
-        .map(QuantumIterate::from)
-        .map(|it| it.collect());
-    let [p1, p2] = player_win_lose_per_turn;
-    let total_wins = [(&p1[1..], &p2[..]), (&p2[..], &p1[..])].map(|(winner, loser)| {
-        winner
-            .iter()
-            .zip(loser.iter())
-            .map(|([wins, _], [_, losses])| wins * losses)
-            .sum::<usize>()
-    });
-    total_wins[0].max(total_wins[1])
+fn main() -> anyhow::Result<()> {
+    Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    const SAMPLE_DATA: &str = include_str!("sample.txt");
 
     #[test]
-    fn test_a() {
-        assert_eq!(part_a(SAMPLE_DATA), 739785);
+    fn a() -> anyhow::Result<()> {
+        Day::test_part_a()
     }
 
     #[test]
-    fn test_b() {
-        assert_eq!(part_b(SAMPLE_DATA), 444_356_092_776_315);
+    fn b() -> anyhow::Result<()> {
+        Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2021::sample_tests!(Day, day21);
 }
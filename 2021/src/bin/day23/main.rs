@@ -1,12 +1,88 @@
-const DATA: &str = include_str!("data.txt");
-use std::collections::HashMap;
+use std::borrow::Cow;
 
+use advent_2021::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, search, Answers, BasicSolution, Solution,
+};
+use anyhow::{bail, ensure, Context};
 use arrayvec::ArrayVec;
 use itertools::Itertools;
 use Amphipod::*;
 
-fn main() {
-    println!("part b: {}", part_b(DATA));
+struct Day;
+
+impl BasicSolution for Day {
+    const INPUT: Option<&'static str> = embedded_input!(day23);
+    const ANSWERS: Answers = embedded_answers!(day23);
+
+    /// The rows of amphipods in the side rooms, from the top down
+    type Shared = Vec<[Amphipod; 4]>;
+    type Answer = usize;
+
+    fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
+        let mut lines = input.lines().map(str::trim_end);
+        for expected in ["#############", "#...........#"] {
+            let line = lines.next().unwrap_or_default();
+            ensure!(line == expected, "expected {expected:?}, found {line:?}");
+        }
+        let mut rows = Vec::new();
+        for line in lines.by_ref() {
+            if line.trim_start() == "#########" {
+                break;
+            }
+            rows.push(row(line)?);
+        }
+        if let Some(line) = lines.next() {
+            bail!("expected the end of the diagram, found {line:?}");
+        }
+        if !matches!(rows.len(), 2 | 4) {
+            bail!("the side rooms should be 2 or 4 amphipods deep");
+        }
+        for amphipod in [A, B, C, D] {
+            let count = rows.iter().flatten().filter(|&&a| a == amphipod).count();
+            ensure!(
+                count == rows.len(),
+                "there are {count} {amphipod:?}s, rather than one per row"
+            );
+        }
+        Ok(rows)
+    }
+
+    fn part_a(rows: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
+        let [top, bottom] = rows[..] else {
+            bail!("part a needs the folded diagram, with rooms 2 amphipods deep");
+        };
+        least_energy::<2>([bottom, top])
+    }
+
+    fn part_b(rows: Self::Shared) -> anyhow::Result<Self::Answer> {
+        match rows[..] {
+            [top, bottom] => least_energy::<4>([bottom, [D, B, A, C], [D, C, B, A], top]),
+            [top, upper_middle, lower_middle, bottom] => {
+                least_energy::<4>([bottom, lower_middle, upper_middle, top])
+            }
+            _ => unreachable!("validated in shared"),
+        }
+    }
+
+    /// The folded diagram, `size` random swaps away from organized. It's the
+    /// same size regardless, but takes more moves to organize.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let mut amphipods = [A, B, C, D, A, B, C, D];
+        for _ in 0..size {
+            amphipods.swap(rng.usize(0..8), rng.usize(0..8));
+        }
+        let rows = vec![
+            [amphipods[0], amphipods[1], amphipods[2], amphipods[3]],
+            [amphipods[4], amphipods[5], amphipods[6], amphipods[7]],
+        ];
+        let [top, bottom] =
+            [&rows[0], &rows[1]].map(|row| row.iter().map(|a| format!("{a:?}")).join("#"));
+        let input =
+            format!("#############\n#...........#\n###{top}###\n  #{bottom}#\n  #########\n");
+        Some(Generated::round_trip(input, rows))
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -37,20 +113,20 @@ impl Amphipod {
     }
 }
 
-type Room = ArrayVec<Amphipod, 4>;
+type Room<const N: usize> = ArrayVec<Amphipod, N>;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-struct State {
+struct State<const N: usize> {
     hallway: [Option<Amphipod>; 7],
-    rooms: [Room; 4],
+    rooms: [Room<N>; 4],
 }
 
-impl State {
-    fn room(&self, room_type: Amphipod) -> &Room {
+impl<const N: usize> State<N> {
+    fn room(&self, room_type: Amphipod) -> &Room<N> {
         &self.rooms[room_type.room_id()]
     }
 
-    fn room_mut(&mut self, room_type: Amphipod) -> &mut Room {
+    fn room_mut(&mut self, room_type: Amphipod) -> &mut Room<N> {
         &mut self.rooms[room_type.room_id()]
     }
 
@@ -92,7 +168,7 @@ impl State {
                 } else {
                     let steps_taken = self.steps_between(hall_id, room_type)?;
                     let mut new_state = self.clone();
-                    let move_amphipod = new_state.room_mut(room_type).pop().unwrap();
+                    let move_amphipod = new_state.room_mut(room_type).pop()?;
                     new_state.hallway[hall_id] = Some(move_amphipod);
                     Some((new_state, steps_taken * move_amphipod.cost_multiplier()))
                 }
@@ -101,71 +177,123 @@ impl State {
     }
 }
 
-fn total_cost(state: State, memo: &mut HashMap<State, usize>) -> usize {
-    if let Some(cost) = memo.get(&state) {
-        return *cost;
-    }
-    if [A, B, C, D]
-        .into_iter()
-        .all(|room_type| state.room(room_type).as_slice() == [room_type; 4])
-    {
-        return 0;
-    }
-    let cost = (0..7)
-        .cartesian_product([A, B, C, D])
-        .flat_map(|(hall_id, room_type)| state.move_between(hall_id, room_type))
-        .map(|(new_state, move_cost)| move_cost.saturating_add(total_cost(new_state, memo)))
-        .min()
-        .unwrap_or(usize::MAX);
-    memo.insert(state, cost);
-    cost
+/// A row of the side rooms, like `###B#C#B#D###` at the top and `  #A#D#C#A#`
+/// below it.
+fn row(line: &str) -> anyhow::Result<[Amphipod; 4]> {
+    let rooms = line
+        .trim_start()
+        .strip_prefix("###")
+        .and_then(|rooms| rooms.strip_suffix("###"))
+        .or_else(|| {
+            let rooms = line.strip_prefix("  #")?.strip_suffix('#')?;
+            (!rooms.starts_with('#')).then_some(rooms)
+        })
+        .with_context(|| format!("expected a row of side rooms, found {line:?}"))?;
+    let amphipods: Vec<_> = rooms
+        .split('#')
+        .map(|amphipod| match amphipod {
+            "A" => Ok(A),
+            "B" => Ok(B),
+            "C" => Ok(C),
+            "D" => Ok(D),
+            _ => bail!("{amphipod:?} in {line:?} is not an amphipod"),
+        })
+        .try_collect()?;
+    amphipods
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("expected 4 side rooms, found {line:?}"))
 }
 
-fn parse(data: &'static str) -> State {
-    let lns = data.lines().map(|l| l.as_bytes()).collect_vec();
-    let hallway = [None; 7];
-    let parse_amphipod = |c| match c {
-        b'A' => A,
-        b'B' => B,
-        b'C' => C,
-        b'D' => D,
-        _ => panic!(),
+/// `rows` lists the side rooms from the bottom up.
+fn least_energy<const N: usize>(rows: [[Amphipod; 4]; N]) -> anyhow::Result<usize> {
+    let rooms = [0, 1, 2, 3].map(|room_id| rows.map(|row| row[room_id]).into());
+    let state = State {
+        hallway: [None; 7],
+        rooms,
+    };
+    let successors = |state: &State<N>| {
+        (0..7)
+            .cartesian_product([A, B, C, D])
+            .flat_map(|(hall_id, room_type)| state.move_between(hall_id, room_type))
+            .collect::<Vec<_>>()
+    };
+    let organized = |state: &State<N>| {
+        [A, B, C, D]
+            .into_iter()
+            .all(|room_type| state.room(room_type).as_slice() == [room_type; N])
     };
-    let rooms = [
-        [lns[5][3], lns[4][3], lns[3][3], lns[2][3]],
-        [lns[5][5], lns[4][5], lns[3][5], lns[2][5]],
-        [lns[5][7], lns[4][7], lns[3][7], lns[2][7]],
-        [lns[5][9], lns[4][9], lns[3][9], lns[2][9]],
-    ]
-    .map(|byte_room| byte_room.map(parse_amphipod).into());
-    State { hallway, rooms }
+    let path = search::dijkstra(state, successors, organized)
+        .context("the amphipods can't be organized")?;
+    Ok(path.cost)
 }
 
-fn part_b(data: &'static str) -> usize {
-    let state = parse(data);
-    total_cost(state, &mut HashMap::new())
+fn main() -> anyhow::Result<()> {
+    Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    const SAMPLE_DATA: &str = include_str!("sample.txt");
 
     #[test]
-    fn test_steps_between() {
-        let state = parse(SAMPLE_DATA);
-        let (state, cost) = state.move_between(0, A).unwrap();
+    fn test_steps_between() -> anyhow::Result<()> {
+        let [top, bottom] = Day::shared_test(include_str!("sample.txt"))?[..] else {
+            unreachable!()
+        };
+        let state = State::<4> {
+            hallway: [None; 7],
+            rooms: [0, 1, 2, 3]
+                .map(|i| [bottom[i], [D, B, A, C][i], [D, C, B, A][i], top[i]].into()),
+        };
+        let (state, cost) = state.move_between(0, A).context("A to 0")?;
         assert_eq!(cost, 30);
         assert!(state.move_between(0, A).is_none());
-        let (state, steps) = state.move_between(4, A).unwrap();
+        let (state, steps) = state.move_between(4, A).context("A to 4")?;
         assert_eq!(steps, 7000);
-        let (state, steps) = state.move_between(1, A).unwrap();
+        let (state, steps) = state.move_between(1, A).context("A to 1")?;
         assert_eq!(steps, 4000);
         assert!(state.move_between(2, A).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_diagrams() {
+        let sample = include_str!("sample.txt");
+        for (input, message) in [
+            (sample.replace("#A#D#C#A#", "#A#DB#C#A#"), "\"DB\""),
+            (
+                sample.replace("#A#D#C#A#", "#A#D#C#A#A#"),
+                "expected 4 side rooms",
+            ),
+            (sample.replace("#A#D#C#A#", "#A#D#C#D#"), "there are 1 As"),
+            (
+                sample.replace("#...........#\n", ""),
+                "expected \"#...........#\"",
+            ),
+            (sample.to_owned() + "#\n", "expected the end of the diagram"),
+        ] {
+            let error = <Day as Solution>::shared(input.leak()).unwrap_err();
+            assert!(error.to_string().contains(message), "{error}");
+        }
+    }
+
+    #[test]
+    fn a() -> anyhow::Result<()> {
+        Day::test_part_a()
     }
 
     #[test]
-    fn test_b() {
-        assert_eq!(part_b(SAMPLE_DATA), 44169);
+    fn b() -> anyhow::Result<()> {
+        Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        // Part b takes seconds on each input in a debug build
+        advent_2021::generate::check_with::<Day>(&[1, 20], 2)
+    }
+
+    advent_2021::sample_tests!(Day, day23);
 }
@@ -1,10 +1,64 @@
+use std::borrow::Cow;
+
+use advent_2021::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, Solution,
+};
+use anyhow::{bail, ensure, Context};
 use derive_new::new;
+use itertools::Itertools;
+
+struct Day;
+
+impl BasicSolution for Day {
+    const INPUT: Option<&'static str> = embedded_input!(day24);
+    const ANSWERS: Answers = embedded_answers!(day24);
 
-const DATA: &str = include_str!("data.txt");
+    type Shared = [Rule; 14];
+    type Answer = String;
 
-fn main() {
-    println!("part a: {}", part_a(DATA));
-    println!("part b: {}", part_b(DATA));
+    fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
+        get_rules(parse(input)?)
+    }
+
+    fn part_a(rules: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
+        Ok(rules.map(|r| 9.min(9 + r.val).to_string()).concat())
+    }
+
+    fn part_b(rules: Self::Shared) -> anyhow::Result<Self::Answer> {
+        Ok(rules.map(|r| 1.max(1 + r.val).to_string()).concat())
+    }
+
+    /// A MONAD of 7 blocks that push a digit and 7 that compare with one,
+    /// in a random order, where every comparison can hold. It's always 14
+    /// digits long.
+    fn generate(rng: &mut Rng, _size: usize) -> Option<Generated<Self::Shared>> {
+        let mut pushed = Vec::new();
+        let mut pushes_left = 7;
+        let mut rules = [Rule::new(0, 0); 14];
+        let mut input = String::new();
+        for i in 0..14 {
+            let cmp_later = rng.i8(1..=16);
+            let (divisor, cmp_now) = if pushes_left > 0 && (pushed.is_empty() || rng.bool()) {
+                pushes_left -= 1;
+                pushed.push((i, cmp_later));
+                (1, rng.i8(10..=15))
+            } else {
+                let (j, val) = pushed.pop()?;
+                let diff = rng.i8(-8..=8);
+                rules[i] = Rule::new(j, diff);
+                rules[j] = Rule::new(i, -diff);
+                (26, diff - val)
+            };
+            input.push_str(&format!(
+                "inp w\nmul x 0\nadd x z\nmod x 26\ndiv z {divisor}\nadd x {cmp_now}\n\
+                 eql x w\neql x 0\nmul y 0\nadd y 25\nmul y x\nadd y 1\nmul z y\n\
+                 mul y 0\nadd y w\nadd y {cmp_later}\nmul y x\nadd z y\n"
+            ));
+        }
+        Some(Generated::round_trip(input, rules))
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -14,19 +68,31 @@ struct StepParams {
     cmp_later: i8,
 }
 
-#[allow(clippy::needless_range_loop)]
-fn parse(data: &'static str) -> [StepParams; 14] {
-    let lines: Vec<_> = data.lines().collect();
-    let mut params = [None; 14];
-    let get_param = |line_id: usize| lines[line_id].rsplit_once(' ').unwrap().1.parse().unwrap();
-    for i in 0..14 {
-        params[i] = Some(StepParams {
-            do_cmp: get_param(i * 18 + 4) == 26,
-            cmp_now: get_param(i * 18 + 5),
-            cmp_later: get_param(i * 18 + 15),
+fn parse(input: &'static str) -> anyhow::Result<[StepParams; 14]> {
+    let lines: Vec<_> = input.lines().collect();
+    ensure!(
+        lines.len() == 14 * 18,
+        "expected 14 blocks of 18 instructions"
+    );
+    let get_param = |line_id: usize| -> anyhow::Result<i8> {
+        let line = lines[line_id];
+        let (_, param) = line
+            .rsplit_once(' ')
+            .with_context(|| format!("expected a parameter on line {}", line_id + 1))?;
+        param
+            .parse()
+            .with_context(|| format!("bad parameter on line {}", line_id + 1))
+    };
+    let params: Vec<_> = (0..14)
+        .map(|i| {
+            anyhow::Ok(StepParams {
+                do_cmp: get_param(i * 18 + 4)? == 26,
+                cmp_now: get_param(i * 18 + 5)?,
+                cmp_later: get_param(i * 18 + 15)?,
+            })
         })
-    }
-    params.map(Option::unwrap)
+        .try_collect()?;
+    Ok(params.try_into().expect("collected 14 blocks"))
 }
 
 #[derive(Debug, new, Clone, Copy)]
@@ -35,29 +101,50 @@ struct Rule {
     val: i8,
 }
 
-fn get_rules(params: [StepParams; 14]) -> [Rule; 14] {
+fn get_rules(params: [StepParams; 14]) -> anyhow::Result<[Rule; 14]> {
     let mut cmp_stack = Vec::new();
     let mut rules = [None; 14];
     for (i, step_params) in params.iter().enumerate() {
         if step_params.do_cmp {
-            let Rule { cmp_to, val } = cmp_stack.pop().unwrap();
+            let Rule { cmp_to, val } = cmp_stack
+                .pop()
+                .context("a block pops more digits than were pushed")?;
             rules[i] = Some(Rule::new(cmp_to, val + step_params.cmp_now));
             rules[cmp_to] = Some(Rule::new(i, -val - step_params.cmp_now));
         } else {
             cmp_stack.push(Rule::new(i, step_params.cmp_later))
         }
     }
-    rules.map(Option::unwrap)
+    if !cmp_stack.is_empty() {
+        bail!("some pushed digits are never compared");
+    }
+    Ok(rules.map(|rule| rule.expect("every digit is either pushed or popped")))
 }
 
-fn part_a(data: &'static str) -> String {
-    let params = parse(data);
-    let rules = get_rules(params);
-    rules.map(|r| 9.min(9 + r.val).to_string()).concat()
+fn main() -> anyhow::Result<()> {
+    Day::main()
 }
 
-fn part_b(data: &'static str) -> String {
-    let params = parse(data);
-    let rules = get_rules(params);
-    rules.map(|r| 1.max(1 + r.val).to_string()).concat()
+register!(Day);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a() -> anyhow::Result<()> {
+        Day::test_part_a()
+    }
+
+    #[test]
+    fn b() -> anyhow::Result<()> {
+        Day::test_part_b()
+    }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2021::sample_tests!(Day, day24);
 }
@@ -1,11 +1,86 @@
+use std::borrow::Cow;
+
+use advent_2021::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, Solution,
+};
+use anyhow::Context;
 use itertools::Itertools;
 use ndarray::{Array, Array2};
 use SeaCucumber::*;
 
-const DATA: &str = include_str!("data.txt");
+struct Day;
+
+impl BasicSolution for Day {
+    const INPUT: Option<&'static str> = embedded_input!(day25);
+    const ANSWERS: Answers = embedded_answers!(day25);
+
+    type Shared = Array2<Option<SeaCucumber>>;
+    type Answer = String;
+
+    fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
+        let lines: Vec<_> = input.lines().collect();
+        let cells = input
+            .chars()
+            .filter(|&c| c != '\n')
+            .map(|c| match c {
+                '>' => Ok(Some(East)),
+                'v' => Ok(Some(South)),
+                '.' => Ok(None),
+                _ => anyhow::bail!("{c:?} is not a sea cucumber"),
+            })
+            .try_collect()?;
+        Array::from_shape_vec(
+            [lines.len(), lines.first().context("empty map")?.len()],
+            cells,
+        )
+        .context("the map should be rectangular")
+    }
 
-fn main() {
-    println!("part a: {}", part_a(DATA));
+    fn part_a(grid: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
+        let mut grid = grid.into_owned();
+        for i in 1.. {
+            if !step_both_directions(&mut grid) {
+                return Ok(i.to_string());
+            }
+        }
+        unreachable!()
+    }
+
+    fn part_b(_grid: Self::Shared) -> anyhow::Result<Self::Answer> {
+        Ok(String::new())
+    }
+
+    /// A square map `size` times 5 cells across, with a full row of east
+    /// facing cucumbers and a full column of south facing ones that never
+    /// move, so the rest can't circle around forever.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let side = size * 5;
+        let (wall_row, wall_column) = (rng.usize(0..side), rng.usize(0..side));
+        let grid = Array::from_shape_fn([side, side], |(row, column)| {
+            if column == wall_column {
+                Some(South)
+            } else if row == wall_row {
+                Some(East)
+            } else {
+                [Some(East), Some(South), None][rng.usize(0..3)]
+            }
+        });
+        let input = grid
+            .rows()
+            .into_iter()
+            .map(|row| {
+                let row = row.iter().map(|cucumber| match cucumber {
+                    Some(East) => '>',
+                    Some(South) => 'v',
+                    None => '.',
+                });
+                row.collect::<String>() + "\n"
+            })
+            .collect();
+        Some(Generated::round_trip(input, grid))
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -14,22 +89,6 @@ enum SeaCucumber {
     South,
 }
 
-fn parse(data: &'static str) -> Array2<Option<SeaCucumber>> {
-    let lines: Vec<_> = data.lines().collect();
-    Array::from_shape_vec(
-        [lines.len(), lines[0].len()],
-        data.chars()
-            .filter_map(|c| match c {
-                '>' => Some(Some(East)),
-                'v' => Some(Some(South)),
-                '.' => Some(None),
-                _ => None,
-            })
-            .collect(),
-    )
-    .unwrap()
-}
-
 // For debugging
 #[allow(dead_code)]
 fn print_grid(grid: &Array2<Option<SeaCucumber>>) {
@@ -85,23 +144,30 @@ fn step_both_directions(grid: &mut Array2<Option<SeaCucumber>>) -> bool {
     moved
 }
 
-fn part_a(data: &'static str) -> usize {
-    let mut grid = parse(data);
-    for i in 1.. {
-        if !step_both_directions(&mut grid) {
-            return i;
-        }
-    }
-    unreachable!()
+fn main() -> anyhow::Result<()> {
+    Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    const SAMPLE_DATA: &str = include_str!("sample.txt");
 
     #[test]
-    fn test_a() {
-        assert_eq!(part_a(SAMPLE_DATA), 58);
+    fn a() -> anyhow::Result<()> {
+        Day::test_part_a()
     }
+
+    #[test]
+    fn b() -> anyhow::Result<()> {
+        Day::test_part_b()
+    }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2021::sample_tests!(Day, day25);
 }
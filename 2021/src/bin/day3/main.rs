@@ -1,64 +1,111 @@
+use std::borrow::Cow;
+
+use advent_2021::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, Solution,
+};
+use anyhow::{bail, Context};
 use itertools::Itertools;
 
-const DATA: &str = include_str!("data.txt");
+struct Day;
 
-fn main() {
-    println!("part a: {}", part_a(DATA));
-    println!("part b: {}", part_b(DATA));
+#[derive(Debug, Clone)]
+struct Report {
+    numbers: Vec<u16>,
+    num_bits: usize,
 }
 
-fn parse(data: &'static str) -> (Vec<u16>, usize) {
-    (
-        data.lines()
-            .map(|line| u16::from_str_radix(line, 2).unwrap())
-            .collect(),
-        data.chars().take_while(|&c| c != '\n').count(),
-    )
-}
+impl BasicSolution for Day {
+    const INPUT: Option<&'static str> = embedded_input!(day3);
+    const ANSWERS: Answers = embedded_answers!(day3);
 
-fn part_a(data: &'static str) -> usize {
-    let (data, num_bits) = parse(data);
-    let gamma = (0..num_bits)
-        .map(|bit_pos| 1 << bit_pos)
-        .filter(|mask| data.iter().filter(|&num| num & mask != 0).count() > data.len() / 2)
-        .fold(0, |gamma, mask| gamma | mask) as usize;
-    let interesting_bits = (1 << num_bits) - 1;
-    gamma * (!gamma & interesting_bits)
-}
+    type Shared = Report;
+    type Answer = usize;
+
+    fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
+        let numbers = input
+            .lines()
+            .map(|line| {
+                u16::from_str_radix(line, 2).with_context(|| format!("bad binary number {line:?}"))
+            })
+            .try_collect()?;
+        let num_bits = input.lines().next().context("empty report")?.len();
+        Ok(Report { numbers, num_bits })
+    }
+
+    fn part_a(report: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
+        let Report { numbers, num_bits } = report.as_ref();
+        let gamma = (0..*num_bits)
+            .map(|bit_pos| 1 << bit_pos)
+            .filter(|mask| {
+                numbers.iter().filter(|&num| num & mask != 0).count() > numbers.len() / 2
+            })
+            .fold(0, |gamma, mask| gamma | mask) as usize;
+        let interesting_bits = (1 << num_bits) - 1;
+        Ok(gamma * (!gamma & interesting_bits))
+    }
+
+    fn part_b(report: Self::Shared) -> anyhow::Result<Self::Answer> {
+        Ok(rating(&report, true)? * rating(&report, false)?)
+    }
 
-fn part_b(data: &'static str) -> usize {
-    let (data, num_bits) = parse(data);
-    rating(&data, num_bits, true) * rating(&data, num_bits, false)
+    /// `size` times 10 numbers of up to 12 bits.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let num_bits = rng.usize(1..=12);
+        let numbers = (0..size * 10)
+            .map(|_| rng.u16(0..1 << num_bits))
+            .collect_vec();
+        let input = numbers
+            .iter()
+            .map(|n| format!("{n:0num_bits$b}\n"))
+            .collect();
+        Some(Generated::round_trip(input, Report { numbers, num_bits }))
+    }
 }
 
-fn rating(data: &[u16], num_bits: usize, bit_criteria: bool) -> usize {
-    let mut remaining = data.iter().copied().collect_vec();
-    for bit_pos in (0..num_bits).rev() {
+fn rating(report: &Report, bit_criteria: bool) -> anyhow::Result<usize> {
+    let mut remaining = report.numbers.clone();
+    for bit_pos in (0..report.num_bits).rev() {
         let mask = 1 << bit_pos;
-        let mut groups = remaining.into_iter().into_group_map_by(|num| num & mask);
-        remaining = match bit_criteria == (groups[&mask].len() >= groups[&0].len()) {
-            true => groups.remove(&mask).unwrap(),
-            false => groups.remove(&0).unwrap(),
+        let (ones, zeros): (Vec<_>, Vec<_>) =
+            remaining.into_iter().partition(|num| num & mask != 0);
+        remaining = if bit_criteria == (ones.len() >= zeros.len()) {
+            ones
+        } else {
+            zeros
         };
-        if remaining.len() == 1 {
-            return remaining[0] as usize;
+        if let [rating] = remaining[..] {
+            return Ok(rating as usize);
         }
     }
-    panic!()
+    bail!("more than one number matches the bit criteria")
+}
+
+fn main() -> anyhow::Result<()> {
+    Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    const SAMPLE_DATA: &str = include_str!("sample.txt");
 
     #[test]
-    fn test_a() {
-        assert_eq!(part_a(SAMPLE_DATA), 198);
+    fn a() -> anyhow::Result<()> {
+        Day::test_part_a()
     }
 
     #[test]
-    fn test_b() {
-        assert_eq!(part_b(SAMPLE_DATA), 230);
+    fn b() -> anyhow::Result<()> {
+        Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2021::sample_tests!(Day, day3);
 }
@@ -1,34 +1,98 @@
+use std::borrow::Cow;
+
+use advent_2021::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, Solution,
+};
+use anyhow::Context;
 use itertools::Itertools;
 use ndarray::Array2;
 
-const DATA: &str = include_str!("data.txt");
-fn main() {
-    let (mut boards, draws) = parse(DATA);
-    let win_turns = all_win_turns(&mut boards, &draws);
-    println!("part a: {}", part_a_calc(&win_turns, &boards, &draws));
-    println!("part b: {}", part_b_calc(&win_turns, &boards, &draws));
-}
+struct Day;
 
 type Board = Array2<(u8, bool)>;
 
-fn parse(data: &'static str) -> (Vec<Board>, Vec<u8>) {
-    let mut split = data.trim().split("\n\n");
-    let draws: Vec<u8> = split
-        .next()
-        .unwrap()
-        .split(',')
-        .map(|n| n.parse().unwrap())
-        .collect();
-    let boards = split
-        .map(|board_str| {
-            let board_vec = board_str
-                .split_whitespace()
-                .map(|n| (n.parse().unwrap(), false))
-                .collect();
-            Array2::from_shape_vec([5, 5], board_vec).unwrap()
+#[derive(Debug, Clone)]
+struct Bingo {
+    boards: Vec<Board>,
+    draws: Vec<u8>,
+    win_turns: Vec<usize>,
+}
+
+impl BasicSolution for Day {
+    const INPUT: Option<&'static str> = embedded_input!(day4);
+    const ANSWERS: Answers = embedded_answers!(day4);
+
+    type Shared = Bingo;
+    type Answer = usize;
+
+    fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
+        let mut split = input.trim().split("\n\n");
+        let draws: Vec<u8> = split
+            .next()
+            .context("missing draws")?
+            .split(',')
+            .map(|n| n.parse().with_context(|| format!("bad draw {n:?}")))
+            .try_collect()?;
+        let mut boards: Vec<Board> = split.map(board).try_collect()?;
+        let win_turns = boards
+            .iter_mut()
+            .enumerate()
+            .map(|(i, b)| {
+                board_win_turn(b, &draws).with_context(|| format!("board {i} never wins"))
+            })
+            .try_collect()?;
+        Ok(Bingo {
+            boards,
+            draws,
+            win_turns,
+        })
+    }
+
+    fn part_a(bingo: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
+        let best_board_id = bingo.win_turns.iter().position_min().context("no boards")?;
+        Ok(bingo.score(best_board_id))
+    }
+
+    fn part_b(bingo: Self::Shared) -> anyhow::Result<Self::Answer> {
+        let worst_board_id = bingo.win_turns.iter().position_max().context("no boards")?;
+        Ok(bingo.score(worst_board_id))
+    }
+
+    /// `size` boards, with every number drawn in the end so they all win.
+    /// Only checked to parse, since parsing also plays each board to find
+    /// when it wins.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let mut draws = (0..100).collect_vec();
+        rng.shuffle(&mut draws);
+        let mut input = draws.iter().join(",") + "\n";
+        for _ in 0..size {
+            let mut numbers = (0..100).collect_vec();
+            rng.shuffle(&mut numbers);
+            input.push('\n');
+            for row in numbers[..25].chunks(5) {
+                input.push_str(&format!(
+                    "{}\n",
+                    row.iter().map(|n| format!("{n:2}")).join(" ")
+                ));
+            }
+        }
+        Some(Generated::new(input))
+    }
+}
+
+fn board(board_str: &str) -> anyhow::Result<Board> {
+    let board_vec = board_str
+        .split_whitespace()
+        .map(|n| {
+            Ok((
+                n.parse().with_context(|| format!("bad number {n:?}"))?,
+                false,
+            ))
         })
-        .collect();
-    (boards, draws)
+        .collect::<anyhow::Result<_>>()?;
+    Array2::from_shape_vec([5, 5], board_vec).context("boards should be 5x5")
 }
 
 fn mark_board(board: &mut Board, draw: u8) -> Option<(usize, usize)> {
@@ -41,61 +105,52 @@ fn mark_board(board: &mut Board, draw: u8) -> Option<(usize, usize)> {
         })
 }
 
-fn board_win_turn(board: &mut Board, draws: &[u8]) -> usize {
-    draws
-        .iter()
-        .position(|&draw| {
-            if let Some((row, col)) = mark_board(board, draw) {
-                return board.row(row).iter().all(|&(_num, mark)| mark)
-                    || board.column(col).iter().all(|&(_num, mark)| mark);
-            }
-            false
-        })
-        .unwrap()
-}
-
-fn all_win_turns(boards: &mut [Board], draws: &[u8]) -> Vec<usize> {
-    boards
-        .iter_mut()
-        .map(|b| board_win_turn(b, draws))
-        .collect()
+fn board_win_turn(board: &mut Board, draws: &[u8]) -> Option<usize> {
+    draws.iter().position(|&draw| {
+        if let Some((row, col)) = mark_board(board, draw) {
+            return board.row(row).iter().all(|&(_num, mark)| mark)
+                || board.column(col).iter().all(|&(_num, mark)| mark);
+        }
+        false
+    })
 }
 
-fn score(board: &Board, winning_draw: u8) -> usize {
-    board
-        .iter()
-        .filter(|&&(_num, mark)| !mark)
-        .map(|&(num, _mark)| num as usize)
-        .sum::<usize>()
-        * winning_draw as usize
+impl Bingo {
+    fn score(&self, board_id: usize) -> usize {
+        let winning_draw = self.draws[self.win_turns[board_id]];
+        self.boards[board_id]
+            .iter()
+            .filter(|&&(_num, mark)| !mark)
+            .map(|&(num, _mark)| num as usize)
+            .sum::<usize>()
+            * winning_draw as usize
+    }
 }
 
-fn part_a_calc(win_turns: &[usize], boards: &[Board], draws: &[u8]) -> usize {
-    let best_board_id = win_turns.iter().position_min().unwrap();
-    score(&boards[best_board_id], draws[win_turns[best_board_id]])
+fn main() -> anyhow::Result<()> {
+    Day::main()
 }
 
-fn part_b_calc(win_turns: &[usize], boards: &[Board], draws: &[u8]) -> usize {
-    let worst_board_id = win_turns.iter().position_max().unwrap();
-    score(&boards[worst_board_id], draws[win_turns[worst_board_id]])
-}
+register!(Day);
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const SAMPLE_DATA: &str = include_str!("sample.txt");
 
     #[test]
-    fn test_a() {
-        let (mut boards, draws) = parse(SAMPLE_DATA);
-        let win_turns = all_win_turns(&mut boards, &draws);
-        assert_eq!(part_a_calc(&win_turns, &boards, &draws), 4512);
+    fn a() -> anyhow::Result<()> {
+        Day::test_part_a()
     }
 
     #[test]
-    fn test_b() {
-        let (mut boards, draws) = parse(SAMPLE_DATA);
-        let win_turns = all_win_turns(&mut boards, &draws);
-        assert_eq!(part_b_calc(&win_turns, &boards, &draws), 1924);
+    fn b() -> anyhow::Result<()> {
+        Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2021::sample_tests!(Day, day4);
 }
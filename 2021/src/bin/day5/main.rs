@@ -1,32 +1,88 @@
-use std::ops::RangeInclusive;
+use std::{borrow::Cow, ops::RangeInclusive};
 
+use advent_2021::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, Solution,
+};
+use anyhow::{ensure, Context};
 use itertools::Itertools;
 use ndarray::{s, Array2};
 
-const DATA: &str = include_str!("data.txt");
+struct Day;
 
-fn main() {
-    println!("part a: {}", part_a(DATA));
-    println!("part b: {}", part_b(DATA));
+type Line = (usize, usize, usize, usize);
+
+const GRID_SIZE: usize = 1000;
+
+impl BasicSolution for Day {
+    const INPUT: Option<&'static str> = embedded_input!(day5);
+    const ANSWERS: Answers = embedded_answers!(day5);
+
+    type Shared = Vec<Line>;
+    type Answer = usize;
+
+    fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
+        input.lines().map(line).collect()
+    }
+
+    fn part_a(lines: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
+        Ok(solution(&lines, false))
+    }
+
+    fn part_b(lines: Self::Shared) -> anyhow::Result<Self::Answer> {
+        Ok(solution(&lines, true))
+    }
+
+    /// `size` times 10 horizontal, vertical and diagonal lines.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let lines = (0..size * 10)
+            .map(|_| {
+                let (x1, y1) = (rng.usize(0..GRID_SIZE), rng.usize(0..GRID_SIZE));
+                let len = rng.usize(0..GRID_SIZE - x1.max(y1));
+                match rng.u8(0..3) {
+                    0 => (x1, y1, x1 + len, y1),
+                    1 => (x1, y1, x1, y1 + len),
+                    _ => (x1, y1, x1 + len, y1 + len),
+                }
+            })
+            .collect_vec();
+        let input = lines
+            .iter()
+            .map(|(x1, y1, x2, y2)| format!("{x1},{y1} -> {x2},{y2}\n"))
+            .collect();
+        Some(Generated::round_trip(input, lines))
+    }
 }
 
-fn parse(data: &'static str) -> impl Iterator<Item = (usize, usize, usize, usize)> {
-    data.lines().map(|line| {
-        line.split(" -> ")
-            .flat_map(|point| point.split(','))
-            .map(|coord| coord.parse().unwrap())
-            .collect_tuple()
-            .unwrap()
-    })
+fn line(line: &str) -> anyhow::Result<Line> {
+    let coords: Line = line
+        .split(" -> ")
+        .flat_map(|point| point.split(','))
+        .map(|coord| {
+            coord
+                .parse()
+                .with_context(|| format!("bad coordinate {coord:?}"))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?
+        .into_iter()
+        .collect_tuple()
+        .with_context(|| format!("expected 'x1,y1 -> x2,y2', got {line:?}"))?;
+    let (x1, y1, x2, y2) = coords;
+    ensure!(
+        [x1, y1, x2, y2].iter().all(|&c| c < GRID_SIZE),
+        "coordinates should be below {GRID_SIZE}: {line:?}"
+    );
+    Ok(coords)
 }
 
 fn range(c1: usize, c2: usize) -> RangeInclusive<usize> {
     c1.min(c2)..=c1.max(c2)
 }
 
-fn solution(data: &'static str, calc_diagonal: bool) -> usize {
-    let mut grid = Array2::zeros([1000, 1000]);
-    for (x1, y1, x2, y2) in parse(data) {
+fn solution(lines: &[Line], calc_diagonal: bool) -> usize {
+    let mut grid = Array2::zeros([GRID_SIZE, GRID_SIZE]);
+    for &(x1, y1, x2, y2) in lines {
         if x1 == x2 {
             let mut points = grid.slice_mut(s![x1, range(y1, y2)]);
             points += 1;
@@ -47,26 +103,30 @@ fn solution(data: &'static str, calc_diagonal: bool) -> usize {
     grid.fold(0, |acc, &cur| acc + (cur >= 2) as usize)
 }
 
-fn part_a(data: &'static str) -> usize {
-    solution(data, false)
+fn main() -> anyhow::Result<()> {
+    Day::main()
 }
 
-fn part_b(data: &'static str) -> usize {
-    solution(data, true)
-}
+register!(Day);
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const SAMPLE_DATA: &str = include_str!("sample.txt");
 
     #[test]
-    fn test_a() {
-        assert_eq!(part_a(SAMPLE_DATA), 5);
+    fn a() -> anyhow::Result<()> {
+        Day::test_part_a()
     }
 
     #[test]
-    fn test_b() {
-        assert_eq!(part_b(SAMPLE_DATA), 12);
+    fn b() -> anyhow::Result<()> {
+        Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2021::sample_tests!(Day, day5);
 }
@@ -1,16 +1,46 @@
-use std::collections::HashMap;
+use std::{borrow::Cow, collections::HashMap};
 
+use advent_2021::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, Solution,
+};
+use anyhow::Context;
 use itertools::{iterate, Itertools};
 
-const DATA: &str = include_str!("data.txt");
+struct Day;
 
-fn main() {
-    println!("part a: {}", part_a(DATA));
-    println!("part b: {}", part_b(DATA));
-}
+impl BasicSolution for Day {
+    const INPUT: Option<&'static str> = embedded_input!(day6);
+    const ANSWERS: Answers = embedded_answers!(day6);
+
+    type Shared = HashMap<i8, usize>;
+    type Answer = usize;
+
+    fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
+        Ok(input
+            .trim()
+            .split(',')
+            .map(|v| v.parse().with_context(|| format!("bad age {v:?}")))
+            .collect::<anyhow::Result<Vec<i8>>>()?
+            .into_iter()
+            .counts())
+    }
+
+    fn part_a(age_counts: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
+        Ok(num_fish(age_counts.into_owned(), 80))
+    }
+
+    fn part_b(age_counts: Self::Shared) -> anyhow::Result<Self::Answer> {
+        Ok(num_fish(age_counts, 256))
+    }
 
-fn parse(data: &'static str) -> HashMap<i8, usize> {
-    data.trim().split(',').map(|v| v.parse().unwrap()).counts()
+    /// `size` times 10 fish. Only checked to parse: they're counted into a
+    /// `HashMap`, whose order isn't the same from one map to the next.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let ages = (0..size * 10).map(|_| rng.u8(1..=5)).join(",");
+        Some(Generated::new(ages + "\n"))
+    }
 }
 
 fn next_state(age_counts: &HashMap<i8, usize>) -> HashMap<i8, usize> {
@@ -25,34 +55,38 @@ fn next_state(age_counts: &HashMap<i8, usize>) -> HashMap<i8, usize> {
     age_counts
 }
 
-fn num_fish(data: &'static str, generations: usize) -> usize {
-    iterate(parse(data), next_state)
+fn num_fish(age_counts: HashMap<i8, usize>, generations: usize) -> usize {
+    iterate(age_counts, next_state)
         .nth(generations)
-        .unwrap()
+        .expect("iterate is infinite")
         .values()
         .sum()
 }
 
-fn part_a(data: &'static str) -> usize {
-    num_fish(data, 80)
+fn main() -> anyhow::Result<()> {
+    Day::main()
 }
 
-fn part_b(data: &'static str) -> usize {
-    num_fish(data, 256)
-}
+register!(Day);
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const SAMPLE_DATA: &str = include_str!("sample.txt");
 
     #[test]
-    fn test_a() {
-        assert_eq!(part_a(SAMPLE_DATA), 5934);
+    fn a() -> anyhow::Result<()> {
+        Day::test_part_a()
     }
 
     #[test]
-    fn test_b() {
-        assert_eq!(part_b(SAMPLE_DATA), 26984457539);
+    fn b() -> anyhow::Result<()> {
+        Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2021::sample_tests!(Day, day6);
 }
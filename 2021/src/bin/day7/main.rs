@@ -1,19 +1,61 @@
-const DATA: &str = include_str!("data.txt");
+use std::borrow::Cow;
 
-fn main() {
-    println!("part a: {}", part_a(DATA));
-    println!("part b: {}", part_b(DATA));
-}
+use advent_2021::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, Solution,
+};
+use anyhow::{ensure, Context};
 
-fn parse(data: &'static str) -> Vec<i64> {
-    data.trim().split(',').map(|n| n.parse().unwrap()).collect()
-}
+struct Day;
+
+impl BasicSolution for Day {
+    const INPUT: Option<&'static str> = embedded_input!(day7);
+    const ANSWERS: Answers = embedded_answers!(day7);
+
+    type Shared = Vec<i64>;
+    type Answer = i64;
+
+    fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
+        let positions: Vec<_> = input
+            .trim()
+            .split(',')
+            .map(|n| n.parse().with_context(|| format!("bad position {n:?}")))
+            .collect::<anyhow::Result<_>>()?;
+        ensure!(!positions.is_empty(), "no crabs");
+        Ok(positions)
+    }
+
+    fn part_a(positions: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
+        let mut positions = positions.into_owned();
+        positions.sort_unstable();
+        let align = positions[positions.len() / 2];
+        Ok(positions.iter().map(|pos| (pos - align).abs()).sum())
+    }
 
-fn part_a(data: &'static str) -> i64 {
-    let mut positions = parse(data);
-    positions.sort_unstable();
-    let align = positions[positions.len() / 2];
-    positions.iter().map(|pos| (pos - align).abs()).sum()
+    /// We can't just round the average, because the derivative is
+    /// align - avg + sum(signum(align - pos)) / 2N
+    /// As opposed to
+    /// align - avg.
+    /// Since the last term can never be more than 0.5,
+    /// We only need to check the floor and ceiling of avg
+    fn part_b(positions: Self::Shared) -> anyhow::Result<Self::Answer> {
+        let avg = positions.iter().sum::<i64>() as f64 / positions.len() as f64;
+        let floor = avg.floor() as i64;
+        let ceil = avg.ceil() as i64;
+        Ok(total_fuel(&positions, floor).min(total_fuel(&positions, ceil)))
+    }
+
+    /// `size` times 10 crabs.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let positions = (0..size * 10).map(|_| rng.i64(0..2000)).collect::<Vec<_>>();
+        let input = positions
+            .iter()
+            .map(i64::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+        Some(Generated::round_trip(input + "\n", positions))
+    }
 }
 
 fn fuel_needed(steps: i64) -> i64 {
@@ -27,32 +69,30 @@ fn total_fuel(positions: &[i64], align: i64) -> i64 {
         .sum()
 }
 
-/// We can't just round the average, because the derivative is
-/// align - avg + sum(signum(align - pos)) / 2N
-/// As opposed to
-/// align - avg.
-/// Since the last term can never be more than 0.5,
-/// We only need to check the floor and ceiling of avg
-fn part_b(data: &'static str) -> i64 {
-    let positions = parse(data);
-    let avg = positions.iter().sum::<i64>() as f64 / positions.len() as f64;
-    let floor = avg.floor() as i64;
-    let ceil = avg.ceil() as i64;
-    total_fuel(&positions, floor).min(total_fuel(&positions, ceil))
+fn main() -> anyhow::Result<()> {
+    Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    const SAMPLE_DATA: &str = include_str!("sample.txt");
 
     #[test]
-    fn test_a() {
-        assert_eq!(part_a(SAMPLE_DATA), 37);
+    fn a() -> anyhow::Result<()> {
+        Day::test_part_a()
     }
 
     #[test]
-    fn test_b() {
-        assert_eq!(part_b(SAMPLE_DATA), 168);
+    fn b() -> anyhow::Result<()> {
+        Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2021::sample_tests!(Day, day7);
 }
@@ -1,56 +1,148 @@
 use std::{
+    borrow::Cow,
     collections::{BTreeSet, HashMap},
     iter::repeat,
 };
 
+use advent_2021::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, Solution,
+};
+use anyhow::Context;
 use itertools::Itertools;
 
-const DATA: &str = include_str!("data.txt");
-
-fn main() {
-    println!("part a: {}", part_a(DATA));
-    println!("part b: {}", part_b(DATA));
-}
+struct Day;
 
-#[derive(Default)]
+#[derive(Debug, Clone)]
 struct FourDigitDisplay {
     patterns: Vec<&'static str>,
     output: Vec<&'static str>,
 }
 
-impl From<&'static str> for FourDigitDisplay {
-    fn from(s: &'static str) -> Self {
-        let (patterns_str, output_str) = s.split(" | ").collect_tuple().unwrap();
-        let patterns = patterns_str.split_whitespace().collect();
-        let output = output_str.split_whitespace().collect();
-        Self { patterns, output }
+impl BasicSolution for Day {
+    const INPUT: Option<&'static str> = embedded_input!(day8);
+    const ANSWERS: Answers = embedded_answers!(day8);
+
+    type Shared = Vec<FourDigitDisplay>;
+    type Answer = usize;
+
+    fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
+        input.lines().map(four_digit_display).collect()
+    }
+
+    fn part_a(displays: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
+        Ok(displays
+            .iter()
+            .flat_map(|fdd| &fdd.output)
+            .filter(|digit| matches!(digit.len(), 2 | 3 | 4 | 7))
+            .count())
+    }
+
+    fn part_b(displays: Self::Shared) -> anyhow::Result<Self::Answer> {
+        let decode = get_decoder();
+        displays
+            .iter()
+            .map(|fdd| {
+                let translation = decode(&fdd.patterns)?;
+                fdd.output
+                    .iter()
+                    .map(|&out_pat| {
+                        let pat = out_pat
+                            .chars()
+                            .map(|c| translation.get(&c).context("unknown segment"))
+                            .collect::<anyhow::Result<Vec<_>>>()?
+                            .into_iter()
+                            .sorted_unstable()
+                            .collect::<String>();
+                        PATTERNS
+                            .iter()
+                            .position(|&p| p == pat)
+                            .with_context(|| format!("{out_pat:?} doesn't decode to a digit"))
+                    })
+                    .rev()
+                    .enumerate()
+                    .map(|(i, num)| Ok(num? * 10_usize.pow(i as u32)))
+                    .sum::<anyhow::Result<usize>>()
+            })
+            .sum()
+    }
+
+    /// `size` displays, each with its own wiring and its segments listed in
+    /// any order.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        fn scramble(rng: &mut Rng, wiring: &[u8], pattern: &str) -> &'static str {
+            let mut segments = pattern
+                .bytes()
+                .map(|segment| char::from(wiring[usize::from(segment - b'a')]))
+                .collect_vec();
+            rng.shuffle(&mut segments);
+            segments.into_iter().collect::<String>().leak()
+        }
+        let displays = (0..size)
+            .map(|_| {
+                let mut wiring = b"abcdefg".to_vec();
+                rng.shuffle(&mut wiring);
+                let mut patterns = PATTERNS.map(|pattern| scramble(rng, &wiring, pattern));
+                rng.shuffle(&mut patterns);
+                let output = (0..4)
+                    .map(|_| {
+                        let digit = rng.usize(0..10);
+                        scramble(rng, &wiring, PATTERNS[digit])
+                    })
+                    .collect();
+                FourDigitDisplay {
+                    patterns: patterns.to_vec(),
+                    output,
+                }
+            })
+            .collect_vec();
+        let input = displays
+            .iter()
+            .map(|display| {
+                format!(
+                    "{} | {}\n",
+                    display.patterns.join(" "),
+                    display.output.join(" ")
+                )
+            })
+            .collect();
+        Some(Generated::round_trip(input, displays))
     }
 }
 
-fn part_a(data: &'static str) -> usize {
-    data.lines()
-        .map(FourDigitDisplay::from)
-        .flat_map(|fdd| fdd.output)
-        .filter(|digit| matches!(digit.len(), 2 | 3 | 4 | 7))
-        .count()
+fn four_digit_display(line: &'static str) -> anyhow::Result<FourDigitDisplay> {
+    let (patterns_str, output_str) = line
+        .split_once(" | ")
+        .with_context(|| format!("expected '<patterns> | <output>', got {line:?}"))?;
+    let patterns = patterns_str.split_whitespace().collect();
+    let output = output_str.split_whitespace().collect();
+    Ok(FourDigitDisplay { patterns, output })
 }
 
 const PATTERNS: [&str; 10] = [
     "abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg", "abcdfg",
 ];
 
-fn get_decoder() -> impl Fn(&[&'static str]) -> HashMap<char, char> {
-    let key_to_char_true = get_key_to_char(&PATTERNS);
+type Key = (usize, BTreeSet<usize>);
+
+fn get_decoder() -> impl Fn(&[&'static str]) -> anyhow::Result<HashMap<char, char>> {
+    let key_to_char_true = get_key_to_char(&PATTERNS).expect("the true patterns are complete");
     move |patterns| {
-        let key_to_char_mangled = get_key_to_char(patterns);
+        let key_to_char_mangled = get_key_to_char(patterns)?;
         key_to_char_mangled
             .into_iter()
-            .map(|(key, c)| (c, key_to_char_true[&key]))
+            .map(|(key, c)| {
+                let true_c = key_to_char_true
+                    .get(&key)
+                    .with_context(|| format!("segment {c} matches no true segment"))?;
+                Ok((c, *true_c))
+            })
             .collect()
     }
 }
 
-fn get_key_to_char(patterns: &[&'static str]) -> HashMap<(usize, BTreeSet<usize>), char> {
+fn get_key_to_char(patterns: &[&'static str]) -> anyhow::Result<HashMap<Key, char>> {
     let occurences = patterns.iter().flat_map(|pat| pat.chars()).counts();
     let pat_lengths = patterns
         .iter()
@@ -58,48 +150,41 @@ fn get_key_to_char(patterns: &[&'static str]) -> HashMap<(usize, BTreeSet<usize>
         .into_group_map();
     ('a'..='g')
         .map(|c| {
-            let lengths = pat_lengths[&c].iter().copied().collect();
-            ((occurences[&c], lengths), c)
+            let lengths = pat_lengths
+                .get(&c)
+                .with_context(|| format!("segment {c} never lights up"))?
+                .iter()
+                .copied()
+                .collect();
+            Ok(((occurences[&c], lengths), c))
         })
         .collect()
 }
 
-fn part_b(data: &'static str) -> usize {
-    let decode = get_decoder();
-    data.lines()
-        .map(FourDigitDisplay::from)
-        .map(|fdd| {
-            let translation = decode(&fdd.patterns);
-            fdd.output
-                .iter()
-                .map(|&out_pat| {
-                    let pat = out_pat
-                        .chars()
-                        .map(|c| translation[&c])
-                        .sorted_unstable()
-                        .collect::<String>();
-                    PATTERNS.iter().find_position(|&&p| p == pat).unwrap().0
-                })
-                .rev()
-                .enumerate()
-                .map(|(i, num)| num * 10_usize.pow(i as u32))
-                .sum::<usize>()
-        })
-        .sum()
+fn main() -> anyhow::Result<()> {
+    Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    const SAMPLE_DATA: &str = include_str!("sample.txt");
 
     #[test]
-    fn test_a() {
-        assert_eq!(part_a(SAMPLE_DATA), 26);
+    fn a() -> anyhow::Result<()> {
+        Day::test_part_a()
     }
 
     #[test]
-    fn test_b() {
-        assert_eq!(part_b(SAMPLE_DATA), 61229);
+    fn b() -> anyhow::Result<()> {
+        Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2021::sample_tests!(Day, day8);
 }
@@ -1,83 +1,113 @@
-use std::collections::{HashMap, HashSet};
+use std::{borrow::Cow, collections::HashSet};
 
+use advent_2021::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    grid::{Grid, Pos, ORTHOGONAL},
+    register, Answers, BasicSolution, Solution,
+};
 use itertools::Itertools;
 
-const DATA: &str = include_str!("data.txt");
+struct Day;
 
-fn main() {
-    println!("part a: {}", part_a(DATA));
-    println!("part b: {}", part_b(DATA));
-}
+type HeightMap = Grid<u8>;
 
-fn parse(data: &'static str) -> HashMap<(i64, i64), usize> {
-    data.lines()
-        .enumerate()
-        .flat_map(|(row, line)| {
-            line.chars()
-                .map(|c| c.to_digit(10).unwrap() as usize)
-                .enumerate()
-                .map(move |(col, val)| ((row as i64, col as i64), val))
-        })
-        .collect()
-}
+impl BasicSolution for Day {
+    const INPUT: Option<&'static str> = embedded_input!(day9);
+    const ANSWERS: Answers = embedded_answers!(day9);
 
-fn adjacent_points(
-    (y, x): (i64, i64),
-    data: &HashMap<(i64, i64), usize>,
-) -> impl Iterator<Item = (i64, i64)> + '_ {
-    [(-1, 0), (1, 0), (0, -1), (0, 1)]
-        .map(|(dy, dx)| (y + dy, x + dx))
-        .into_iter()
-        .filter(|p| data.contains_key(p))
-}
+    type Shared = HeightMap;
+    type Answer = usize;
+
+    fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
+        Ok(Grid::parse_with(input, |c| {
+            c.is_ascii_digit().then(|| c - b'0')
+        })?)
+    }
+
+    fn part_a(heights: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
+        Ok(get_low_points(&heights)
+            .map(|p| heights[p] as usize + 1)
+            .sum())
+    }
+
+    fn part_b(heights: Self::Shared) -> anyhow::Result<Self::Answer> {
+        Ok(get_low_points(&heights)
+            .map(|p| get_basin_size(p, &heights))
+            .sorted_unstable()
+            .rev()
+            .take(3)
+            .product())
+    }
 
-fn get_low_points(data: &HashMap<(i64, i64), usize>) -> impl Iterator<Item = (i64, i64)> + '_ {
-    data.keys()
-        .copied()
-        .filter(|&p| adjacent_points(p, data).all(|adj| data[&adj] > data[&p]))
+    /// A square map `size` times 5 positions across, with enough 9s to wall
+    /// off small basins like the real input.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let side = size * 5;
+        let heights = (0..side * side)
+            .map(|_| match rng.u8(0..20) {
+                0..=8 => 9,
+                _ => rng.u8(0..9),
+            })
+            .collect();
+        let height_map = Grid::from_vec(side, heights)?;
+        let input = height_map.map(|&height| b'0' + height).to_string();
+        Some(Generated::round_trip(input, height_map))
+    }
 }
 
-fn part_a(data: &'static str) -> usize {
-    let data = parse(data);
-    get_low_points(&data).map(|p| data[&p] + 1).sum()
+fn get_low_points(data: &HeightMap) -> impl Iterator<Item = Pos> + '_ {
+    data.positions().filter(|&p| {
+        data.neighbors(p, &ORTHOGONAL)
+            .all(|adj| data[adj] > data[p])
+    })
 }
 
-fn get_basin_size(p: (i64, i64), data: &HashMap<(i64, i64), usize>) -> usize {
+fn get_basin_size(p: Pos, data: &HeightMap) -> usize {
     let mut basin = HashSet::new();
     crawl(p, data, &mut basin);
     basin.len()
 }
 
-fn crawl(p: (i64, i64), data: &HashMap<(i64, i64), usize>, basin: &mut HashSet<(i64, i64)>) {
-    if basin.contains(&p) || data[&p] == 9 {
+fn crawl(p: Pos, data: &HeightMap, basin: &mut HashSet<Pos>) {
+    if basin.contains(&p) || data[p] == 9 {
         return;
     }
     basin.insert(p);
-    adjacent_points(p, data).for_each(|p| crawl(p, data, basin))
+    data.neighbors(p, &ORTHOGONAL)
+        .for_each(|p| crawl(p, data, basin))
 }
 
-fn part_b(data: &'static str) -> usize {
-    let data = parse(data);
-    get_low_points(&data)
-        .map(|p| get_basin_size(p, &data))
-        .sorted_unstable()
-        .rev()
-        .take(3)
-        .product()
+fn main() -> anyhow::Result<()> {
+    Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    const SAMPLE_DATA: &str = include_str!("sample.txt");
 
     #[test]
-    fn test_a() {
-        assert_eq!(part_a(SAMPLE_DATA), 15);
+    fn a() -> anyhow::Result<()> {
+        Day::test_part_a()
+    }
+
+    #[test]
+    fn b() -> anyhow::Result<()> {
+        Day::test_part_b()
     }
 
     #[test]
-    fn test_b() {
-        assert_eq!(part_b(SAMPLE_DATA), 1134);
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
     }
+
+    #[test]
+    fn non_digit() {
+        let error = <Day as Solution>::shared("12\n3#\n").unwrap_err();
+        assert!(error.to_string().contains("unexpected '#'"), "{error}");
+    }
+
+    advent_2021::sample_tests!(Day, day9);
 }
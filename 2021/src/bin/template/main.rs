@@ -1,36 +1,48 @@
-const DATA: &str = include_str!("data.txt");
+use std::borrow::Cow;
 
-fn main() {
-    println!("part a: {}", part_a(DATA));
-    println!("part b: {}", part_b(DATA));
-}
+use advent_2021::{embedded_answers, embedded_input, register, Answers, BasicSolution, Solution};
 
-fn parse(data: &'static str) {
-    println!("{data}")
-}
+struct Day;
+
+impl BasicSolution for Day {
+    const INPUT: Option<&'static str> = embedded_input!(template);
+    const ANSWERS: Answers = embedded_answers!(template);
+
+    type Shared = Vec<&'static str>;
+    type Answer = usize;
+
+    fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
+        Ok(input.lines().collect())
+    }
 
-fn part_a(data: &'static str) -> usize {
-    parse(data);
-    0
+    fn part_a(shared: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
+        todo!("{shared:?}")
+    }
+
+    fn part_b(_: Self::Shared) -> anyhow::Result<Self::Answer> {
+        Ok(0)
+    }
 }
 
-fn part_b(data: &'static str) -> usize {
-    parse(data);
-    0
+fn main() -> anyhow::Result<()> {
+    Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    const SAMPLE_DATA: &str = include_str!("sample.txt");
 
     #[test]
-    fn test_a() {
-        assert_eq!(part_a(SAMPLE_DATA), 0);
+    fn a() -> anyhow::Result<()> {
+        Day::test_part_a()
     }
 
     #[test]
-    fn test_b() {
-        assert_eq!(part_b(SAMPLE_DATA), 0);
+    fn b() -> anyhow::Result<()> {
+        Day::test_part_b()
     }
+
+    advent_2021::sample_tests!(Day, template);
 }
@@ -0,0 +1,11 @@
+pub use aoc_core::{
+    branch_and_bound, cycle, embedded_answers, embedded_input, generate, grid, ranges, register,
+    sample_tests, search, Answers, BasicSolution, ParseError, Registered, Solution, Step,
+};
+
+extern crate self as advent_2021;
+
+#[cfg(not(test))]
+mod days;
+#[cfg(not(test))]
+pub use days::DAYS;
@@ -1,6 +1,9 @@
 use std::cmp::Reverse;
 
-use advent_2022::*;
+use advent_2022::{
+    generate::{Generated, Rng},
+    *,
+};
 use itertools::Itertools;
 use nom::{
     character::complete::{line_ending, u32},
@@ -8,13 +11,11 @@ use nom::{
 };
 use nom_supreme::ParserExt;
 
-boilerplate!(Day);
+boilerplate!(Day, day1);
 
 impl BasicSolution for Day {
     type Parsed = Vec<u32>;
     type Answer = u32;
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 24000;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 45000;
 
     fn parse(data: &str) -> IResult<'_, Self::Parsed> {
         separated_list1(
@@ -27,14 +28,39 @@ impl BasicSolution for Day {
         )(data)
     }
 
-    fn a(data: Self::Parsed) -> Self::Answer {
-        data.into_iter().max().expect("At least one elf")
+    fn a(data: Self::Parsed) -> OutResult<Self::Answer> {
+        data.into_iter()
+            .max()
+            .ok_or_else(|| SolveError::no_answer("no elves"))
     }
 
-    fn b(data: Self::Parsed) -> Self::Answer {
-        data.into_iter()
+    fn b(data: Self::Parsed) -> OutResult<Self::Answer> {
+        Ok(data
+            .into_iter()
             .sorted_unstable_by_key(|&cals| Reverse(cals))
             .take(3)
-            .sum()
+            .sum())
+    }
+
+    /// `size` elves, carrying a few snacks each.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Parsed>> {
+        let elves = (0..size)
+            .map(|_| {
+                (0..rng.usize(1..6))
+                    .map(|_| rng.u32(1000..60000))
+                    .collect_vec()
+            })
+            .collect_vec();
+        let input = elves
+            .iter()
+            .map(|snacks| {
+                snacks
+                    .iter()
+                    .map(|calories| format!("{calories}\n"))
+                    .join("")
+            })
+            .join("\n");
+        let totals = elves.iter().map(|snacks| snacks.iter().sum()).collect();
+        Some(Generated::round_trip(input, totals))
     }
 }
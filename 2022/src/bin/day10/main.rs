@@ -1,6 +1,9 @@
 use std::iter;
 
-use advent_2022::*;
+use advent_2022::{
+    generate::{Generated, Rng},
+    *,
+};
 use itertools::{repeat_n, Itertools};
 use nom::{
     branch::alt,
@@ -11,36 +14,27 @@ use nom::{
 };
 use nom_supreme::ParserExt;
 
-boilerplate!(Day);
+boilerplate!(Day, day10);
 
 impl BasicSolution for Day {
     type Parsed = Vec<Operation>;
     type Answer = String;
-    type TestAnswer = &'static str;
-    const SAMPLE_ANSWER_A: Self::TestAnswer = "13140";
-    const SAMPLE_ANSWER_B: Self::TestAnswer = "
-##..##..##..##..##..##..##..##..##..##..
-###...###...###...###...###...###...###.
-####....####....####....####....####....
-#####.....#####.....#####.....#####.....
-######......######......######......####
-#######.......#######.......#######.....";
 
     fn parse(data: &'static str) -> IResult<'static, Self::Parsed> {
         separated_list1(line_ending, parse_operation)(data)
     }
 
-    fn a(data: Self::Parsed) -> Self::Answer {
-        iter_register(&data)
+    fn a(data: Self::Parsed) -> OutResult<Self::Answer> {
+        Ok(iter_register(&data)
             .zip(1..)
             .filter(|(_, cycle)| [20, 60, 100, 140, 180, 220].contains(cycle))
             .map(|(reg_x, cycle)| reg_x * cycle)
             .sum::<i32>()
-            .to_string()
+            .to_string())
     }
 
-    fn b(data: Self::Parsed) -> Self::Answer {
-        iter_register(&data)
+    fn b(data: Self::Parsed) -> OutResult<Self::Answer> {
+        Ok(iter_register(&data)
             .chunks(40)
             .into_iter()
             .flat_map(|row| {
@@ -52,7 +46,28 @@ impl BasicSolution for Day {
                     }
                 }))
             })
-            .collect()
+            .collect())
+    }
+
+    /// `size` times 10 instructions.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Parsed>> {
+        let operations = (0..size * 10)
+            .map(|_| {
+                if rng.bool() {
+                    Noop
+                } else {
+                    Add(rng.i32(-20..=20))
+                }
+            })
+            .collect_vec();
+        let input = operations
+            .iter()
+            .map(|operation| match operation {
+                Noop => "noop\n".to_owned(),
+                Add(x) => format!("addx {x}\n"),
+            })
+            .collect();
+        Some(Generated::round_trip(input, operations))
     }
 }
 
@@ -1,6 +1,9 @@
 use std::cmp::Reverse;
 
-use advent_2022::*;
+use advent_2022::{
+    generate::{Generated, Rng},
+    *,
+};
 use itertools::Itertools;
 use nom::{
     branch::alt,
@@ -12,26 +15,79 @@ use nom::{
 };
 use nom_supreme::ParserExt;
 
-boilerplate!(Day);
+boilerplate!(Day, day11);
 
 impl BasicSolution for Day {
     type Parsed = Vec<Monkey>;
     type Answer = u64;
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 10605;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 2713310158;
 
     fn parse(data: &'static str) -> IResult<'static, Self::Parsed> {
         separated_list1(line_ending, monkey)(data)
     }
 
-    fn a(mut monkeys: Self::Parsed) -> Self::Answer {
+    fn a(mut monkeys: Self::Parsed) -> OutResult<Self::Answer> {
         stuff_slinging_simian_shenanigans(&mut monkeys, 20, |n| n / 3)
     }
 
-    fn b(mut monkeys: Self::Parsed) -> Self::Answer {
+    fn b(mut monkeys: Self::Parsed) -> OutResult<Self::Answer> {
         let least_common_denominator: u64 = monkeys.iter().map(|m| m.test).product();
+        if least_common_denominator == 0 {
+            return Err(SolveError::unsupported(
+                "a monkey tests for divisibility by 0",
+            ));
+        }
         stuff_slinging_simian_shenanigans(&mut monkeys, 10000, |n| n % least_common_denominator)
     }
+
+    /// Up to 8 monkeys, with `size` items between them.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Parsed>> {
+        const PRIMES: [u64; 8] = [2, 3, 5, 7, 11, 13, 17, 19];
+        let count = rng.usize(2..=8);
+        let mut monkeys = (0..count)
+            .map(|i| {
+                let other = |rng: &mut Rng| (i + rng.usize(1..count)) % count;
+                Monkey {
+                    inspected: 0,
+                    items: Vec::new(),
+                    operation: match rng.u8(0..3) {
+                        0 => Op::Add(rng.u64(1..10)),
+                        1 => Op::Multiply(rng.u64(2..20)),
+                        _ => Op::Square,
+                    },
+                    test: PRIMES[i],
+                    if_true: other(rng),
+                    if_false: other(rng),
+                }
+            })
+            .collect_vec();
+        for _ in 0..size {
+            monkeys[rng.usize(0..count)].items.push(rng.u64(50..100));
+        }
+        let input = monkeys
+            .iter()
+            .enumerate()
+            .map(|(i, monkey)| {
+                let operation = match monkey.operation {
+                    Op::Add(n) => format!("+ {n}"),
+                    Op::Multiply(n) => format!("* {n}"),
+                    Op::Square => "* old".to_owned(),
+                };
+                format!(
+                    "Monkey {i}:\n  \
+                     Starting items: {}\n  \
+                     Operation: new = old {operation}\n  \
+                     Test: divisible by {}\n    \
+                     If true: throw to monkey {}\n    \
+                     If false: throw to monkey {}\n",
+                    monkey.items.iter().join(", "),
+                    monkey.test,
+                    monkey.if_true,
+                    monkey.if_false,
+                )
+            })
+            .join("\n");
+        Some(Generated::round_trip(input, monkeys))
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -121,25 +177,36 @@ fn stuff_slinging_simian_shenanigans(
     monkeys: &mut [Monkey],
     rounds: usize,
     manage_worry_level: impl Fn(u64) -> u64,
-) -> u64 {
+) -> OutResult<u64> {
     for (_, turn) in (0..rounds).cartesian_product(0..monkeys.len()) {
         while let Some(item) = monkeys[turn].items.pop() {
             monkeys[turn].inspected += 1;
             let new = match monkeys[turn].operation {
-                Op::Add(n) => item + n,
-                Op::Multiply(n) => item * n,
-                Op::Square => item * item,
-            };
+                Op::Add(n) => item.checked_add(n),
+                Op::Multiply(n) => item.checked_mul(n),
+                Op::Square => item.checked_mul(item),
+            }
+            .ok_or_else(|| {
+                SolveError::unsupported(format!("worry level {item} overflows at monkey {turn}"))
+            })?;
             let new = manage_worry_level(new);
             let throw_to = if new.is_multiple_of(monkeys[turn].test) {
                 monkeys[turn].if_true
             } else {
                 monkeys[turn].if_false
             };
-            monkeys[throw_to].items.push(new);
+            monkeys
+                .get_mut(throw_to)
+                .ok_or_else(|| {
+                    SolveError::unsupported(format!(
+                        "monkey {turn} throws to a missing monkey {throw_to}"
+                    ))
+                })?
+                .items
+                .push(new);
         }
     }
-    monkey_business(monkeys)
+    Ok(monkey_business(monkeys))
 }
 
 fn monkey_business(monkeys: &[Monkey]) -> u64 {
@@ -1,36 +1,22 @@
-use std::collections::VecDeque;
-
-use advent_2022::*;
+use advent_2022::{
+    generate::{Generated, Rng},
+    grid::{Grid, Pos, ORTHOGONAL},
+    *,
+};
 use itertools::Itertools;
 
-boilerplate!(Day);
+boilerplate!(Day, day12);
 
 impl BasicSolution for Day {
     type Parsed = Input;
     type Answer = u32;
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 31;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 29;
 
     fn parse(data: &'static str) -> IResult<'static, Self::Parsed> {
-        let mut height_map = data
-            .lines()
-            .map(|row| row.as_bytes().to_vec())
-            .collect_vec();
-
+        let mut height_map = Grid::parse(data)?;
         let mut find_position_and_assign = |from, to| {
-            height_map
-                .iter_mut()
-                .enumerate()
-                .find_map(|(x, row)| {
-                    row.iter_mut()
-                        .enumerate()
-                        .find(|(_, &mut height)| height == from)
-                        .map(|(y, height)| {
-                            *height = to;
-                            (x, y)
-                        })
-                })
-                .expect("The character should be present in the input")
+            let pos = height_map.find(&from)?;
+            height_map[pos] = to;
+            Some(pos)
         };
         Ok((
             "",
@@ -42,60 +28,71 @@ impl BasicSolution for Day {
         ))
     }
 
-    fn a(data: Self::Parsed) -> Self::Answer {
-        bfs(&data, |&pos| pos == data.start)
+    fn a(data: Self::Parsed) -> OutResult<Self::Answer> {
+        let start = data
+            .start
+            .ok_or_else(|| SolveError::unsupported("no S in the height map"))?;
+        bfs(&data, |&pos| pos == start)
     }
 
-    fn b(data: Self::Parsed) -> Self::Answer {
-        bfs(&data, |&(x, y)| data.height_map[x][y] == b'a')
+    fn b(data: Self::Parsed) -> OutResult<Self::Answer> {
+        bfs(&data, |&pos| data.height_map[pos] == b'a')
     }
-}
 
-type Position = (usize, usize);
-type HeightMap = Vec<Vec<u8>>;
+    /// A hill `size` + 1 rows tall, rising from S in the top left corner to
+    /// E in the bottom right one, with a little noise that sometimes makes
+    /// it too steep to climb.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Parsed>> {
+        let (rows, columns) = (size + 1, size * 2 + 2);
+        let heights = (0..rows)
+            .cartesian_product(0..columns)
+            .map(|(x, y)| {
+                let slope = (x + y) * 25 / (rows + columns - 2);
+                match (x, y) {
+                    (0, 0) => b'a',
+                    _ if (x, y) == (rows - 1, columns - 1) => b'z',
+                    _ => b'a' + slope.saturating_add_signed(rng.isize(-1..=1)).min(25) as u8,
+                }
+            })
+            .collect();
+        let height_map = Grid::from_vec(columns, heights)?;
+        let mut with_ends = height_map.clone();
+        with_ends[(0, 0)] = b'S';
+        with_ends[(rows - 1, columns - 1)] = b'E';
+        let input = with_ends.to_string();
+        let parsed = Input {
+            start: Some((0, 0)),
+            end: Some((rows - 1, columns - 1)),
+            height_map,
+        };
+        Some(Generated::round_trip(input, parsed))
+    }
+}
 
 #[derive(Debug, Clone)]
 struct Input {
-    start: Position,
-    end: Position,
-    height_map: HeightMap,
+    start: Option<Pos>,
+    end: Option<Pos>,
+    height_map: Grid<u8>,
 }
 
-fn neighbors(height_map: &HeightMap, (x, y): Position) -> impl Iterator<Item = Position> + '_ {
-    let checked_add_signed_2d =
-        move |(dx, dy)| x.checked_add_signed(dx).zip(y.checked_add_signed(dy));
-    let is_high_enough = move |&height| height >= height_map[x][y] - 1;
-    let is_valid_neighbor = move |&(x, y): &Position| {
-        height_map
-            .get(x)
-            .and_then(|row| row.get(y))
-            .is_some_and(is_high_enough)
-    };
-    [(-1, 0), (1, 0), (0, -1), (0, 1)]
-        .into_iter()
-        .filter_map(checked_add_signed_2d)
-        .filter(is_valid_neighbor)
+fn neighbors(height_map: &Grid<u8>, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+    let min_height = height_map[pos].saturating_sub(1);
+    height_map
+        .neighbors(pos, &ORTHOGONAL)
+        .filter(move |&neighbor| height_map[neighbor] >= min_height)
 }
 
 fn bfs(
     Input {
         end, height_map, ..
     }: &Input,
-    success: impl Fn(&Position) -> bool,
-) -> u32 {
-    let mut seen = vec![vec![false; height_map[0].len()]; height_map.len()];
-    let mut queue = VecDeque::from([(*end, 0)]);
-    while let Some((pos, steps)) = queue.pop_front() {
-        if success(&pos) {
-            return steps;
-        }
-        if seen[pos.0][pos.1] {
-            continue;
-        }
-        seen[pos.0][pos.1] = true;
-        for neighbor in neighbors(height_map, pos) {
-            queue.push_back((neighbor, steps + 1));
-        }
-    }
-    unreachable!("should always find a path")
+    success: impl FnMut(&Pos) -> bool,
+) -> OutResult<u32> {
+    let end = end.ok_or_else(|| SolveError::unsupported("no E in the height map"))?;
+    let path = search::bfs(end, |&pos| neighbors(height_map, pos), success)
+        .ok_or_else(|| SolveError::no_answer("there's no path to E"))?;
+    path.cost
+        .try_into()
+        .map_err(|_| SolveError::unsupported("the path to E is too long"))
 }
@@ -1,6 +1,9 @@
 use std::cmp::Ordering;
 
-use advent_2022::*;
+use advent_2022::{
+    generate::{Generated, Rng},
+    *,
+};
 use itertools::Itertools;
 use nom::{
     branch::alt,
@@ -12,13 +15,11 @@ use nom::{
 use nom_supreme::ParserExt;
 use Value::*;
 
-boilerplate!(Day);
+boilerplate!(Day, day13);
 
 impl BasicSolution for Day {
     type Parsed = Vec<(Value, Value)>;
     type Answer = usize;
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 13;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 140;
 
     fn parse(data: &'static str) -> IResult<'static, Self::Parsed> {
         separated_list1(
@@ -27,15 +28,16 @@ impl BasicSolution for Day {
         )(data)
     }
 
-    fn a(data: Self::Parsed) -> Self::Answer {
-        data.into_iter()
+    fn a(data: Self::Parsed) -> OutResult<Self::Answer> {
+        Ok(data
+            .into_iter()
             .enumerate()
             .filter(|(_, (a, b))| b >= a)
             .map(|(i, _)| i + 1)
-            .sum()
+            .sum())
     }
 
-    fn b(data: Self::Parsed) -> Self::Answer {
+    fn b(data: Self::Parsed) -> OutResult<Self::Answer> {
         let divider_a = List(vec![List(vec![Integer(2)])]);
         let divider_b = List(vec![List(vec![Integer(6)])]);
         let all_packets = data
@@ -44,8 +46,34 @@ impl BasicSolution for Day {
             .chain([&divider_a, &divider_b])
             .sorted_unstable()
             .collect_vec();
-        all_packets.partition_point(|&v| v <= &divider_a)
-            * all_packets.partition_point(|&v| v <= &divider_b)
+        Ok(all_packets.partition_point(|&v| v <= &divider_a)
+            * all_packets.partition_point(|&v| v <= &divider_b))
+    }
+
+    /// `size` pairs of packets nested up to 4 deep.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Parsed>> {
+        fn packet(rng: &mut Rng, depth: usize) -> Value {
+            if depth > 0 && rng.u8(0..3) == 0 {
+                Integer(rng.u8(0..=10))
+            } else {
+                let items = if depth < 4 { rng.usize(0..5) } else { 0 };
+                List((0..items).map(|_| packet(rng, depth + 1)).collect())
+            }
+        }
+        fn write(value: &Value) -> String {
+            match value {
+                Integer(n) => n.to_string(),
+                List(items) => format!("[{}]", items.iter().map(write).join(",")),
+            }
+        }
+        let pairs = (0..size)
+            .map(|_| (packet(rng, 0), packet(rng, 0)))
+            .collect_vec();
+        let input = pairs
+            .iter()
+            .map(|(a, b)| format!("{}\n{}\n", write(a), write(b)))
+            .join("\n");
+        Some(Generated::round_trip(input, pairs))
     }
 }
 
@@ -1,18 +1,20 @@
-use advent_2022::*;
+use advent_2022::{
+    generate::{Generated, Rng},
+    *,
+};
 use itertools::process_results;
 use nom::{
     character::complete::{char, u16, u8},
     sequence::separated_pair,
     Parser,
 };
+use nom_supreme::ParserExt;
 
-boilerplate!(Day);
+boilerplate!(Day, day14);
 
 impl BasicSolution for Day {
     type Parsed = (BitGrid, usize);
     type Answer = usize;
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 24;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 93;
 
     fn parse(data: &str) -> IResult<'_, Self::Parsed> {
         let mut rocks = BitGrid::new();
@@ -33,12 +35,39 @@ impl BasicSolution for Day {
         Ok(("", (rocks, max_y)))
     }
 
-    fn a((rocks, max_y): Self::Parsed) -> Self::Answer {
-        solve::<false>(rocks, max_y)
+    fn a((rocks, max_y): Self::Parsed) -> OutResult<Self::Answer> {
+        Ok(solve::<false>(rocks, max_y))
+    }
+
+    fn b((rocks, max_y): Self::Parsed) -> OutResult<Self::Answer> {
+        Ok(solve::<true>(rocks, max_y))
     }
 
-    fn b((rocks, max_y): Self::Parsed) -> Self::Answer {
-        solve::<true>(rocks, max_y)
+    /// `size` paths of rock under the source.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Parsed>> {
+        let mut input = String::new();
+        let (mut rocks, mut max_y) = (BitGrid::new(), 0);
+        for _ in 0..size {
+            let (mut x, mut y) = (rng.usize(480..520), rng.usize(1..30));
+            input.push_str(&format!("{x},{y}"));
+            for _ in 0..rng.usize(1..5) {
+                let (from_x, from_y) = (x, y);
+                if rng.bool() {
+                    x = x.saturating_add_signed(rng.isize(-6..=6)).clamp(480, 520);
+                } else {
+                    y = y.saturating_add_signed(rng.isize(-6..=6)).clamp(1, 30);
+                }
+                input.push_str(&format!(" -> {x},{y}"));
+                max_y = max_y.max(from_y).max(y);
+                for x in from_x.min(x)..=from_x.max(x) {
+                    for y in from_y.min(y)..=from_y.max(y) {
+                        rocks.insert(x, y);
+                    }
+                }
+            }
+            input.push('\n');
+        }
+        Some(Generated::round_trip(input, (rocks, max_y)))
     }
 }
 
@@ -51,8 +80,13 @@ fn solve<const SOLID_FLOOR: bool>(mut taken_coords: BitGrid, max_y: usize) -> us
     taken_coords.len() - num_rocks
 }
 
+/// Only rocks the sand can reach without falling out of the [`BitGrid`]
 fn parse_coords(data: &str) -> IResult<'_, (usize, usize)> {
-    separated_pair(u16.map(usize::from), char(','), u8.map(usize::from))(data)
+    separated_pair(
+        u16.map(usize::from).verify(|x| (340..660).contains(x)),
+        char(','),
+        u8.map(usize::from).verify(|&y| y < 158),
+    )(data)
 }
 
 fn drop_sand<const SOLID_FLOOR: bool>(
@@ -97,3 +131,6 @@ impl BitGrid {
         self.0[x + 160 - 500][y / 32] & (1 << (y % 32)) != 0
     }
 }
+
+#[cfg(test)]
+mod reference;
@@ -0,0 +1,69 @@
+//! Sand dropped one grain at a time onto a set of taken positions, to test
+//! the bit-packed version against.
+use std::collections::HashSet;
+
+use advent_2022::{
+    differential::{check, Reference},
+    Nom,
+};
+use anyhow::Context;
+
+use super::Day;
+
+const REFERENCE: Reference<usize> = Reference {
+    a: |input| drop_sand(input, false),
+    b: |input| drop_sand(input, true),
+    max_size: 5,
+    cases: 200,
+};
+
+/// The number of grains that come to rest.
+fn drop_sand(input: &str, solid_floor: bool) -> anyhow::Result<usize> {
+    let mut taken = HashSet::new();
+    for line in input.lines() {
+        let corners = line
+            .split(" -> ")
+            .map(|corner| {
+                let (x, y) = corner.split_once(',').context("no comma")?;
+                Ok((x.parse::<i32>()?, y.parse::<i32>()?))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        for pair in corners.windows(2) {
+            let [(x1, y1), (x2, y2)] = pair else {
+                unreachable!()
+            };
+            for x in *x1.min(x2)..=*x1.max(x2) {
+                for y in *y1.min(y2)..=*y1.max(y2) {
+                    taken.insert((x, y));
+                }
+            }
+        }
+    }
+    let max_y = taken.iter().map(|&(_, y)| y).max().context("no rocks")?;
+    let rocks = taken.len();
+    while !taken.contains(&(500, 0)) {
+        let (mut x, mut y) = (500, 0);
+        loop {
+            if y == max_y + 1 {
+                break;
+            }
+            match [x, x - 1, x + 1]
+                .into_iter()
+                .find(|&x| !taken.contains(&(x, y + 1)))
+            {
+                Some(next_x) => (x, y) = (next_x, y + 1),
+                None => break,
+            }
+        }
+        if y == max_y + 1 && !solid_floor {
+            break;
+        }
+        taken.insert((x, y));
+    }
+    Ok(taken.len() - rocks)
+}
+
+#[test]
+fn matches_reference() -> anyhow::Result<()> {
+    check::<Nom<Day>>(&REFERENCE, include_str!("sample.txt"))
+}
@@ -1,6 +1,10 @@
 use std::{collections::HashMap, ops::Range};
 
-use advent_2022::*;
+use advent_2022::{
+    generate::{Generated, Rng},
+    ranges::IntervalSet,
+    *,
+};
 use itertools::Itertools;
 use nom::{
     bytes::complete::tag,
@@ -10,13 +14,12 @@ use nom::{
 };
 use nom_supreme::ParserExt;
 
-boilerplate!(Day);
+boilerplate!(Day, day15);
 
 impl Solution for Day {
     type Parsed = Vec<Pair>;
+    type ParsedTest = Self::Parsed;
     type Answer = i64;
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 26;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 56_000_011;
 
     fn parse(data: &str) -> IResult<'_, Self::Parsed> {
         separated_list1(line_ending, pair)(data)
@@ -26,21 +29,49 @@ impl Solution for Day {
         Self::parse(data)
     }
 
-    fn a(data: Self::Parsed) -> Self::Answer {
-        solve_a::<2_000_000>(data) as i64
+    fn a(data: Self::Parsed) -> OutResult<Self::Answer> {
+        Ok(solve_a::<2_000_000>(data) as i64)
     }
 
-    fn a_test(data: Self::Parsed) -> Self::Answer {
-        solve_a::<10>(data) as i64
+    fn a_test(data: Self::Parsed) -> OutResult<Self::Answer> {
+        Ok(solve_a::<10>(data) as i64)
     }
 
-    fn b(data: Self::Parsed) -> Self::Answer {
+    fn b(data: Self::Parsed) -> OutResult<Self::Answer> {
         solve_b::<4_000_000>(data)
     }
 
-    fn b_test(data: Self::Parsed) -> Self::Answer {
+    fn b_test(data: Self::Parsed) -> OutResult<Self::Answer> {
         solve_b::<20>(data)
     }
+
+    /// `size` sensors spread over the area part b searches, each with a
+    /// beacon nearby.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Parsed>> {
+        let pairs = (0..size)
+            .map(|_| {
+                let sensor = Point {
+                    x: rng.i32(0..=4_000_000),
+                    y: rng.i32(0..=4_000_000),
+                };
+                let beacon = Point {
+                    x: sensor.x + rng.i32(-1_000_000..=1_000_000),
+                    y: sensor.y + rng.i32(-1_000_000..=1_000_000),
+                };
+                Pair { sensor, beacon }
+            })
+            .collect_vec();
+        let input = pairs
+            .iter()
+            .map(|Pair { sensor, beacon }| {
+                format!(
+                    "Sensor at x={}, y={}: closest beacon is at x={}, y={}\n",
+                    sensor.x, sensor.y, beacon.x, beacon.y
+                )
+            })
+            .collect();
+        Some(Generated::round_trip(input, pairs))
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -74,78 +105,72 @@ fn pair(input: &str) -> IResult<'_, Pair> {
 }
 
 fn solve_a<const EXAMPLE_ROW: i32>(pairs: Vec<Pair>) -> usize {
-    let count_covered_xs: usize = pairs
+    let covered_xs: IntervalSet<i32> = pairs
         .iter()
-        .flat_map(|pair| pair.covered_xs(EXAMPLE_ROW))
-        .sorted_unstable_by_key(|range| range.start)
-        .coalesce(|a, b| {
-            if a.end >= b.start {
-                Ok(a.start..b.end.max(a.end))
-            } else {
-                Err((a, b))
-            }
-        })
-        .map(|xs| xs.len())
-        .sum();
+        .map(|pair| pair.covered_xs(EXAMPLE_ROW))
+        .collect();
     let blocked_xs = pairs
         .into_iter()
         .flat_map(|pair| [pair.sensor, pair.beacon])
         .filter(|p| p.y == EXAMPLE_ROW)
         .unique()
         .count();
-    count_covered_xs - blocked_xs
+    covered_xs.len() as usize - blocked_xs
 }
 
-fn solve_b<const MAX_COORD: i32>(pairs: Vec<Pair>) -> i64 {
-    let top_right = pairs
+fn solve_b<const MAX_COORD: i32>(pairs: Vec<Pair>) -> OutResult<i64> {
+    // The lone uncovered position sits just outside several sensors' ranges,
+    // where their edges lie on top of each other going both ways
+    let down = overlaps(&pairs, Pair::bottom_left, Pair::top_right);
+    let up = overlaps(&pairs, Pair::top_left, Pair::bottom_right);
+    let Point { x, y } = down
         .iter()
-        .map(Pair::top_right)
-        .into_group_map_by(Line::y_intercept);
-    let positive_slope_overlaps = pairs
-        .iter()
-        .map(Pair::bottom_left)
-        .into_grouping_map_by(Line::y_intercept)
-        .fold(vec![], fold_compatible_overlaps(&top_right));
-    let top_left = pairs
-        .iter()
-        .map(Pair::top_left)
-        .into_group_map_by(Line::y_intercept);
-    let negative_slope_overlaps = pairs
-        .iter()
-        .map(Pair::bottom_right)
-        .into_grouping_map_by(Line::y_intercept)
-        .fold(vec![], fold_compatible_overlaps(&top_left));
-    let Point { x, y } = positive_slope_overlaps
-        .values()
-        .flatten()
-        .cartesian_product(negative_slope_overlaps.values().flatten())
-        .find_map(|(positive, negative)| {
-            positive.interception(negative).filter(|p| {
-                p.x >= 0
-                    && p.x <= MAX_COORD
-                    && p.y >= 0
-                    && p.y <= MAX_COORD
-                    && pairs.iter().all(|pair| !pair.covers(p))
-            })
+        .cartesian_product(&up)
+        .find_map(|((down_intercept, down_xs), (up_intercept, up_xs))| {
+            // Where y = x + down_intercept meets y = -x + up_intercept
+            let twice_x = up_intercept - down_intercept;
+            let x = twice_x / 2;
+            let p = Point {
+                x,
+                y: x + down_intercept,
+            };
+            let on_both = twice_x % 2 == 0 && down_xs.contains(x) && up_xs.contains(x);
+            (on_both
+                && (0..=MAX_COORD).contains(&p.x)
+                && (0..=MAX_COORD).contains(&p.y)
+                && pairs.iter().all(|pair| !pair.covers(&p)))
+            .then_some(p)
         })
-        .expect("should be an interception");
+        .ok_or_else(|| SolveError::no_answer("no position is out of every sensor's range"))?;
 
-    x as i64 * 4_000_000 + y as i64
+    Ok(x as i64 * 4_000_000 + y as i64)
 }
 
-fn fold_compatible_overlaps(
-    parallel: &HashMap<i32, Vec<Line>>,
-) -> impl Fn(Vec<Line>, &i32, Line) -> Vec<Line> + '_ {
-    |mut overlaps, y_intercept, line| {
-        overlaps.extend(
-            parallel
-                .get(y_intercept)
-                .iter()
-                .flat_map(|v| v.iter())
-                .filter_map(|other| line.overlap(other)),
-        );
-        overlaps
-    }
+/// The xs where a line from `first` lies on top of a line from `second`,
+/// grouped by the diagonal they're on.
+fn overlaps(
+    pairs: &[Pair],
+    first: fn(&Pair) -> Line,
+    second: fn(&Pair) -> Line,
+) -> HashMap<i32, IntervalSet<i32>> {
+    let xs_per_diagonal = |side: fn(&Pair) -> Line| {
+        pairs
+            .iter()
+            .map(side)
+            .into_grouping_map_by(Line::y_intercept)
+            .fold(IntervalSet::new(), |mut xs, _, line| {
+                xs.insert(line.start.x..line.end.x + 1);
+                xs
+            })
+    };
+    let second = xs_per_diagonal(second);
+    xs_per_diagonal(first)
+        .into_iter()
+        .filter_map(|(y_intercept, xs)| {
+            let overlap = xs.intersection(second.get(&y_intercept)?);
+            (!overlap.is_empty()).then_some((y_intercept, overlap))
+        })
+        .collect()
 }
 
 impl Point {
@@ -215,9 +240,10 @@ impl Pair {
         self.sensor.manhattan_distance(point) <= self.cover_distance()
     }
 
-    fn covered_xs(&self, row: i32) -> Option<Range<i32>> {
+    /// Empty if the sensor doesn't reach `row`.
+    fn covered_xs(&self, row: i32) -> Range<i32> {
         let x_offset = self.cover_distance() - (self.sensor.y - row).abs();
-        Some(self.sensor.x - x_offset..self.sensor.x + x_offset + 1).filter(|r| !r.is_empty())
+        self.sensor.x - x_offset..self.sensor.x + x_offset + 1
     }
 }
 
@@ -233,36 +259,4 @@ impl Line {
     fn y_intercept(&self) -> i32 {
         self.start.y - self.slope() * self.start.x
     }
-
-    fn y_at(&self, x: i32) -> Option<i32> {
-        if self.start.x > x || self.end.x < x {
-            return None;
-        }
-        Some(self.slope() * x + self.y_intercept())
-    }
-
-    fn overlap(&self, other: &Line) -> Option<Line> {
-        debug_assert_eq!(self.slope(), other.slope());
-        debug_assert_eq!(self.y_intercept(), other.y_intercept());
-        let x = self.start.x.max(other.start.x);
-        let y = self.y_at(x)?;
-        let start = Point { x, y };
-
-        let x = self.end.x.min(other.end.x);
-        let y = self.y_at(x)?;
-        let end = Point { x, y };
-        Some(Line { start, end })
-    }
-
-    fn interception(&self, other: &Line) -> Option<Point> {
-        debug_assert_ne!(self.slope(), other.slope());
-        let y_intercept_diff = other.y_intercept() - self.y_intercept();
-        let slope_diff = self.slope() - other.slope();
-        let x = y_intercept_diff / slope_diff;
-        let y = self.y_at(x)?;
-        if y != other.y_at(x)? {
-            return None;
-        }
-        Some(Point { x, y })
-    }
 }
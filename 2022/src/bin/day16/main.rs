@@ -1,6 +1,10 @@
-use std::{cmp::Reverse, collections::HashMap};
+use std::{cell::Cell, cmp::Reverse, collections::HashMap, iter};
 
-use advent_2022::*;
+use advent_2022::{
+    branch_and_bound::{self, Order, Problem},
+    generate::{Generated, Rng},
+    *,
+};
 use itertools::Itertools;
 use nom::{
     bytes::complete::{tag, take},
@@ -11,123 +15,207 @@ use nom::{
 };
 use nom_supreme::ParserExt;
 
-boilerplate!(Day);
+boilerplate!(Day, day16);
 
 impl BasicSolution for Day {
-    type Parsed = (FlowRates, ShortestPathLengths, FlowRateIndices, usize);
-    type Answer = u16;
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 1651;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 1707;
+    type Parsed = Network;
+    type Answer = u32;
 
-    fn parse(data: &str) -> IResult<'_, Self::Parsed> {
+    fn parse(data: &'static str) -> IResult<'static, Self::Parsed> {
         let (input, rows) = separated_list1(line_ending, parse_row)(data)?;
-        let shortest_path_lengths_uncompressed = floyd_warshall(&rows);
+        Ok((input, compress(data, &rows)?))
+    }
 
-        let interesting_valve_indices = rows
-            .iter()
-            .enumerate()
-            .filter(|&(_, &(name, flow, _))| name == "AA" || flow > 0)
-            .map(|(i, _)| i)
-            .collect_vec();
+    fn a(network: Self::Parsed) -> OutResult<Self::Answer> {
+        Ok(most_released_alone(&network, 30))
+    }
+
+    fn b(network: Self::Parsed) -> OutResult<Self::Answer> {
+        Ok(most_released_together(&network, prune))
+    }
 
-        let flow_rates = interesting_valve_indices
+    fn validate(network: &Self::Parsed) -> OutResult<()> {
+        let pruned = most_released_together(network, prune);
+        // If a pair of paths released more, one of them would have to release
+        // more than what's left after the best single path
+        let alone = most_released_alone(network, 26);
+        let threshold = pruned.saturating_sub(alone);
+        let exhaustive = most_released_together(network, move |bound, _| bound > threshold);
+        if exhaustive != pruned {
+            return Err(SolveError::unsupported(format!(
+                "part b's pruning misses the best pair of paths, which releases {exhaustive} \
+                 rather than {pruned}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// AA and `size` more valves, at most 15 of which have a flow rate,
+    /// mostly connected to the last few before them so they're far apart
+    /// like in the real inputs.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Parsed>> {
+        const LETTERS: &str = "BCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let names = iter::once("AA")
+            .chain((0..size.min(LETTERS.len() - 1)).map(|i| &LETTERS[i..i + 2]))
+            .collect_vec();
+        let mut tunnels = vec![Vec::new(); names.len()];
+        for i in 1..names.len() {
+            let near = rng.usize(i.saturating_sub(3)..i);
+            let far = (rng.u8(0..4) == 0).then(|| rng.usize(0..i));
+            for j in iter::once(near).chain(far) {
+                if !tunnels[i].contains(&names[j]) {
+                    tunnels[i].push(names[j]);
+                    tunnels[j].push(names[i]);
+                }
+            }
+        }
+        let mut valves_with_flow = 0;
+        let rows = names
             .iter()
-            .map(|&i| rows[i].1)
+            .zip(tunnels)
+            .map(|(&name, tunnels)| {
+                let flow_rate = if name != "AA" && valves_with_flow < 15 && rng.bool() {
+                    valves_with_flow += 1;
+                    rng.u8(1..25)
+                } else {
+                    0
+                };
+                (name, flow_rate, tunnels)
+            })
             .collect_vec();
-
-        let shortest_path_lengths = interesting_valve_indices
+        let input = rows
             .iter()
-            .map(|&i| {
-                interesting_valve_indices
-                    .iter()
-                    .map(|&j| shortest_path_lengths_uncompressed[i][j])
-                    .collect()
+            .map(|(name, flow_rate, tunnels)| {
+                let tunnels = match tunnels.as_slice() {
+                    [tunnel] => format!("tunnel leads to valve {tunnel}"),
+                    tunnels => format!("tunnels lead to valves {}", tunnels.join(", ")),
+                };
+                format!("Valve {name} has flow rate={flow_rate}; {tunnels}\n")
             })
             .collect();
-
-        let starting_node = interesting_valve_indices
-            .iter()
-            .position(|&i| rows[i].0 == "AA")
-            .expect("a valve called AA");
-
-        let sorted_flow_rate_indices = flow_rates
-            .iter()
-            .enumerate()
-            .sorted_unstable_by_key(|&(_, &flow)| Reverse(flow))
-            .map(|(i, _)| i)
-            .collect_vec();
-
-        Ok((
-            input,
-            (
-                flow_rates,
-                shortest_path_lengths,
-                sorted_flow_rate_indices,
-                starting_node,
-            ),
-        ))
+        Some(Generated::round_trip(input, compress("", &rows).ok()?))
     }
+}
 
-    fn a(
-        (flow_rates, shortest_paths, sorted_flow_rate_indices, starting_idx): Self::Parsed,
-    ) -> u16 {
-        let mut best = 0;
-        branch_and_bound(
-            &flow_rates,
-            &sorted_flow_rate_indices,
-            &shortest_paths,
-            State::new(starting_idx as u8, 30),
-            &mut [],
-            &mut best,
-            |bound, best| bound > best,
-        );
-        best
-    }
+/// Part b's pruning. This could technically produce an incorrect result,
+/// `validate` checks that it doesn't.
+fn prune(bound: u32, best: u32) -> bool {
+    bound > best * 3 / 4
+}
 
-    fn b(
-        (flow_rates, shortest_paths, sorted_flow_rate_indices, starting_idx): Self::Parsed,
-    ) -> u16 {
-        let mut best_per_visited = vec![0; u16::MAX as usize];
-        branch_and_bound(
-            &flow_rates,
-            &sorted_flow_rate_indices,
-            &shortest_paths,
-            State::new(starting_idx as u8, 26),
-            &mut best_per_visited,
-            &mut 0,
-            // this could technically produce an incorrect result,
-            // but it doesn't on my input or the test input
-            |bound, best| bound > best * 3 / 4,
-        );
-        let best_per_visited_filtered_sorted = best_per_visited
-            .into_iter()
-            .enumerate()
-            .filter(|&(_, best)| best > 0)
-            .map(|(i, best)| (i as u16, best))
-            .sorted_unstable_by_key(|&(_, best)| Reverse(best))
-            .collect_vec();
-        let mut best = 0;
-        for (i, &(my_visited, my_best)) in best_per_visited_filtered_sorted.iter().enumerate() {
-            for &(elephant_visited, elephant_best) in &best_per_visited_filtered_sorted[i + 1..] {
-                let score = my_best + elephant_best;
-                if score <= best {
-                    break;
-                }
-                if my_visited & elephant_visited == 0 {
-                    best = score;
-                    break;
-                }
+/// The most pressure one can release in `minutes`.
+fn most_released_alone(network: &Network, minutes: u8) -> u32 {
+    let valves = Valves::new(network, 0, |bound, best| bound > best);
+    let root = State::new(network.starting_idx as u8, minutes);
+    let outcome = branch_and_bound::solve(&valves, root, Order::DepthFirst);
+    outcome.score().unwrap_or(0)
+}
+
+/// The most pressure two can release in 26 minutes, when each only explores
+/// the branches that `filter_bound` keeps.
+fn most_released_together(network: &Network, filter_bound: impl Fn(u32, u32) -> bool) -> u32 {
+    let valves = Valves::new(network, u16::MAX as usize, filter_bound);
+    let root = State::new(network.starting_idx as u8, 26);
+    branch_and_bound::solve(&valves, root, Order::DepthFirst);
+    let best_per_visited_filtered_sorted = valves
+        .best_per_visited
+        .into_iter()
+        .map(Cell::into_inner)
+        .enumerate()
+        .filter(|&(_, best)| best > 0)
+        .map(|(i, best)| (i as u16, best))
+        .sorted_unstable_by_key(|&(_, best)| Reverse(best))
+        .collect_vec();
+    let mut best = 0;
+    for (i, &(my_visited, my_best)) in best_per_visited_filtered_sorted.iter().enumerate() {
+        for &(elephant_visited, elephant_best) in &best_per_visited_filtered_sorted[i + 1..] {
+            let score = my_best + elephant_best;
+            if score <= best {
+                break;
+            }
+            if my_visited & elephant_visited == 0 {
+                best = score;
+                break;
             }
         }
-        best
     }
+    best
 }
 
+type Row = (&'static str, u8, Vec<&'static str>);
 type FlowRates = Vec<u8>;
 type FlowRateIndices = Vec<usize>;
 type ShortestPathLengths = Vec<Vec<u8>>;
 
-fn parse_row(data: &str) -> IResult<'_, (&str, u8, Vec<&str>)> {
+/// The valves worth visiting, and the starting valve, with the shortest paths
+/// between them.
+#[derive(Debug, Clone)]
+struct Network {
+    flow_rates: FlowRates,
+    shortest_path_lengths: ShortestPathLengths,
+    sorted_flow_rate_indices: FlowRateIndices,
+    starting_idx: usize,
+}
+
+/// Keeps only the valves worth visiting, and the starting valve, with the
+/// shortest paths between them. Fails if there are more of them than fit in
+/// [`State`]'s sets of valves.
+fn compress(data: &'static str, rows: &[Row]) -> Result<Network, ParseError> {
+    let shortest_path_lengths_uncompressed = floyd_warshall(rows)?;
+
+    let interesting_valve_indices = rows
+        .iter()
+        .enumerate()
+        .filter(|&(_, &(name, flow, _))| name == "AA" || flow > 0)
+        .map(|(i, _)| i)
+        .collect_vec();
+    if let Some(&i) = interesting_valve_indices.get(u16::BITS as usize) {
+        return Err(ParseError::new(
+            rows[i].0,
+            0,
+            format!(
+                "only {} valves can be worth visiting, counting AA",
+                u16::BITS
+            ),
+        ));
+    }
+
+    let flow_rates = interesting_valve_indices
+        .iter()
+        .map(|&i| rows[i].1)
+        .collect_vec();
+
+    let shortest_path_lengths = interesting_valve_indices
+        .iter()
+        .map(|&i| {
+            interesting_valve_indices
+                .iter()
+                .map(|&j| shortest_path_lengths_uncompressed[i][j])
+                .collect()
+        })
+        .collect();
+
+    let starting_idx = interesting_valve_indices
+        .iter()
+        .position(|&i| rows[i].0 == "AA")
+        .ok_or_else(|| ParseError::new(data, 0, "no valve called AA"))?;
+
+    let sorted_flow_rate_indices = flow_rates
+        .iter()
+        .enumerate()
+        .sorted_unstable_by_key(|&(_, &flow)| Reverse(flow))
+        .map(|(i, _)| i)
+        .collect_vec();
+
+    Ok(Network {
+        flow_rates,
+        shortest_path_lengths,
+        sorted_flow_rate_indices,
+        starting_idx,
+    })
+}
+
+fn parse_row(data: &'static str) -> IResult<'static, Row> {
     tuple((
         tag("Valve ").precedes(take(2usize)),
         tag(" has flow rate=").precedes(u8),
@@ -138,7 +226,7 @@ fn parse_row(data: &str) -> IResult<'_, (&str, u8, Vec<&str>)> {
 }
 
 // simplified copy of petgraph's implementation
-fn floyd_warshall(rows: &[(&str, u8, Vec<&str>)]) -> Vec<Vec<u8>> {
+fn floyd_warshall(rows: &[Row]) -> Result<Vec<Vec<u8>>, ParseError> {
     let valve_name_to_idx: HashMap<&str, _> = rows
         .iter()
         .enumerate()
@@ -148,7 +236,9 @@ fn floyd_warshall(rows: &[(&str, u8, Vec<&str>)]) -> Vec<Vec<u8>> {
     let mut dist = vec![vec![u8::MAX; rows.len()]; rows.len()];
     for (i, (_, _, tunnels)) in rows.iter().enumerate() {
         for tunnel in tunnels {
-            let j = valve_name_to_idx[tunnel];
+            let j = *valve_name_to_idx
+                .get(tunnel)
+                .ok_or_else(|| ParseError::new(tunnel, 0, "no such valve"))?;
             dist[i][j] = 1;
         }
     }
@@ -165,41 +255,56 @@ fn floyd_warshall(rows: &[(&str, u8, Vec<&str>)]) -> Vec<Vec<u8>> {
             }
         }
     }
-    dist
+    Ok(dist)
+}
+
+/// The valves worth visiting, as a tree of the orders to open them in.
+struct Valves<'a, F> {
+    network: &'a Network,
+    /// The most pressure released with each set of valves opened, or nothing
+    /// if that isn't needed
+    best_per_visited: Vec<Cell<u32>>,
+    filter_bound: F,
 }
 
-fn branch_and_bound(
-    flow_rates: &FlowRates,
-    sorted_flow_rate_indices: &[usize],
-    shortest_path_lengths: &ShortestPathLengths,
-    state: State,
-    best_for_visited: &mut [u16],
-    best: &mut u16,
-    filter_bound: impl Fn(u16, u16) -> bool + Copy,
-) {
-    if let Some(cur_best) = best_for_visited.get_mut(state.visited as usize) {
-        *cur_best = state.pressure_released.max(*cur_best);
+impl<'a, F: Fn(u32, u32) -> bool> Valves<'a, F> {
+    fn new(network: &'a Network, visited_sets: usize, filter_bound: F) -> Self {
+        Self {
+            network,
+            best_per_visited: vec![Cell::new(0); visited_sets],
+            filter_bound,
+        }
     }
-    *best = state.pressure_released.max(*best);
-    let bound_branch_pairs = state
-        .branch(flow_rates, shortest_path_lengths)
-        .into_iter()
-        .map(|state| (state.bound(flow_rates, sorted_flow_rate_indices), state))
-        .filter(|&(bound, _)| filter_bound(bound, *best))
-        .sorted_unstable_by_key(|(bound, _)| Reverse(*bound))
-        .collect_vec();
-    for (bound, branch) in bound_branch_pairs {
-        if filter_bound(bound, *best) {
-            branch_and_bound(
-                flow_rates,
-                sorted_flow_rate_indices,
-                shortest_path_lengths,
-                branch,
-                best_for_visited,
-                best,
-                filter_bound,
-            );
+}
+
+impl<F: Fn(u32, u32) -> bool> Problem for Valves<'_, F> {
+    type Node = State;
+    type Score = u32;
+    type Key = ();
+
+    fn score(&self, state: &State) -> Option<u32> {
+        if let Some(best) = self.best_per_visited.get(state.visited as usize) {
+            best.set(state.pressure_released.max(best.get()));
         }
+        Some(state.pressure_released)
+    }
+
+    fn bound(&self, state: &State) -> u32 {
+        state.bound(
+            &self.network.flow_rates,
+            &self.network.sorted_flow_rate_indices,
+        )
+    }
+
+    fn branch(&self, state: &State) -> impl IntoIterator<Item = State> {
+        state.branch(
+            &self.network.flow_rates,
+            &self.network.shortest_path_lengths,
+        )
+    }
+
+    fn promising(&self, bound: u32, best: u32) -> bool {
+        (self.filter_bound)(bound, best)
     }
 }
 
@@ -207,13 +312,11 @@ fn branch_and_bound(
 struct State {
     visited: u16,
     avoid: u16,
-    pressure_released: u16,
+    pressure_released: u32,
     minutes_remaining: u8,
     position: u8,
 }
 
-/// Attempt to follow the 'branch and bound' algorithm from wikipedia:
-/// https://en.wikipedia.org/wiki/Branch_and_bound
 impl State {
     fn new(position: u8, minutes_remaining: u8) -> Self {
         Self {
@@ -231,7 +334,7 @@ impl State {
 
     /// Assuming the shortest path lengths are all 1, the best solution is
     /// to visit the valves in order of descending flow rate.
-    fn bound(self, flow_rates: &FlowRates, sorted_flow_rate_indices: &[usize]) -> u16 {
+    fn bound(self, flow_rates: &FlowRates, sorted_flow_rate_indices: &[usize]) -> u32 {
         self.pressure_released
             + (0..=self.minutes_remaining)
                 .rev()
@@ -243,8 +346,8 @@ impl State {
                         .filter(|&&i| self.can_visit(i))
                         .map(|&i| flow_rates[i]),
                 )
-                .map(|(minutes, flow)| minutes as u16 * flow as u16)
-                .sum::<u16>()
+                .map(|(minutes, flow)| minutes as u32 * flow as u32)
+                .sum::<u32>()
     }
 
     fn branch<'a>(
@@ -262,7 +365,7 @@ impl State {
                     visited: self.visited | (1 << destination),
                     avoid: self.avoid,
                     pressure_released: self.pressure_released
-                        + minutes_remaining as u16 * flow_rates[destination] as u16,
+                        + minutes_remaining as u32 * flow_rates[destination] as u32,
                     minutes_remaining,
                     position: destination as u8,
                 })
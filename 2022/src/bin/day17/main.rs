@@ -1,16 +1,16 @@
-#![feature(array_windows)]
-use advent_2022::*;
+use advent_2022::{
+    generate::{Generated, Rng},
+    *,
+};
 use itertools::Itertools;
 use nom::{branch::alt, character::complete::char, multi::many1};
 use nom_supreme::ParserExt;
 
-boilerplate!(Day);
+boilerplate!(Day, day17);
 
 impl BasicSolution for Day {
     type Parsed = Vec<Direction>;
     type Answer = usize;
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 3068;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 1_514_285_714_288;
 
     fn parse(data: &str) -> IResult<'_, Self::Parsed> {
         many1(alt((
@@ -19,38 +19,47 @@ impl BasicSolution for Day {
         )))(data)
     }
 
-    fn a(data: Self::Parsed) -> usize {
-        simulate(&data, 2022).len()
+    fn a(data: Self::Parsed) -> OutResult<Self::Answer> {
+        let mut chamber = Chamber::new(&data);
+        (0..2022).for_each(|_| chamber.drop_rock());
+        Ok(chamber.height())
     }
 
-    fn b(data: Self::Parsed) -> usize {
-        let chamber = simulate(&data, 3000);
-        let pattern_length = chamber[..chamber.len() - 100]
-            .windows(100)
-            .rev()
-            .position(|a| a == &chamber[chamber.len() - 100..])
-            .map(|p| p + 100)
-            .expect("There should be a pattern!");
-        let pattern_start = chamber
-            .windows(100)
-            .zip(chamber[pattern_length..].windows(100))
-            .position(|(a, b)| a == b)
-            .expect("The pattern should start somewhere");
-
-        let rocks_before_pattern = count_rocks_in_chamber_slice(&chamber[..pattern_start]);
-
-        let rocks_generated_in_pattern =
-            count_rocks_in_chamber_slice(&chamber[pattern_start..pattern_start + pattern_length]);
-        let num_pattern_repetitions =
-            (1_000_000_000_000 - rocks_before_pattern) / rocks_generated_in_pattern;
-        let leftover_rocks =
-            (1_000_000_000_000 - rocks_before_pattern) % rocks_generated_in_pattern;
-        let leftover_rocks_height = (0..=pattern_length).collect_vec().partition_point(|&i| {
-            count_rocks_in_chamber_slice(&chamber[pattern_start..pattern_start + i])
-                < leftover_rocks
-        });
-        // print_chamber(&chamber);
-        num_pattern_repetitions * pattern_length + pattern_start + leftover_rocks_height
+    fn b(data: Self::Parsed) -> OutResult<Self::Answer> {
+        let cycle = cycle::detect(
+            Chamber::new(&data),
+            Chamber::drop_rock,
+            Chamber::key,
+            Chamber::height,
+            10_000,
+        )
+        .ok_or_else(|| {
+            SolveError::unsupported("the rocks don't settle into a repeating pattern")
+        })?;
+        cycle
+            .extrapolate(1_000_000_000_000)
+            .ok_or_else(|| SolveError::unsupported("the tower is too tall"))
+    }
+
+    /// `size` times 10 jets of gas.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Parsed>> {
+        let jets = (0..size * 10)
+            .map(|_| {
+                if rng.bool() {
+                    Direction::Right
+                } else {
+                    Direction::Left
+                }
+            })
+            .collect_vec();
+        let input = jets
+            .iter()
+            .map(|direction| match direction {
+                Direction::Right => '>',
+                Direction::Left => '<',
+            })
+            .collect();
+        Some(Generated::round_trip(input, jets))
     }
 }
 
@@ -60,7 +69,7 @@ enum Direction {
     Left,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum Cell {
     NA,
     AA,
@@ -72,56 +81,91 @@ enum Cell {
 
 use Cell::*;
 
-fn gen_rocks() -> impl Iterator<Item = Vec<[Cell; 7]>> {
-    let rocks = vec![
-        vec![
-            // ####
-            [NA, NA, AA, AA, AA, AA, NA],
-        ],
-        vec![
-            // .#.
-            // ###
-            // .#.
-            [NA, NA, NA, BB, NA, NA, NA],
-            [NA, NA, BB, BB, BB, NA, NA],
-            [NA, NA, NA, BB, NA, NA, NA],
-        ],
-        vec![
-            // ..#
-            // ..#
-            // ###
-            [NA, NA, NA, NA, CC, NA, NA],
-            [NA, NA, NA, NA, CC, NA, NA],
-            [NA, NA, CC, CC, CC, NA, NA],
-        ],
-        vec![
-            // #
-            // #
-            // #
-            // #
-            [NA, NA, DD, NA, NA, NA, NA],
-            [NA, NA, DD, NA, NA, NA, NA],
-            [NA, NA, DD, NA, NA, NA, NA],
-            [NA, NA, DD, NA, NA, NA, NA],
-        ],
-        vec![
-            // ##
-            // ##
-            [NA, NA, EE, EE, NA, NA, NA],
-            [NA, NA, EE, EE, NA, NA, NA],
-        ],
-    ];
-    rocks.into_iter().cycle()
+const ROCKS: [&[[Cell; 7]]; 5] = [
+    &[
+        // ####
+        [NA, NA, AA, AA, AA, AA, NA],
+    ],
+    &[
+        // .#.
+        // ###
+        // .#.
+        [NA, NA, NA, BB, NA, NA, NA],
+        [NA, NA, BB, BB, BB, NA, NA],
+        [NA, NA, NA, BB, NA, NA, NA],
+    ],
+    &[
+        // ..#
+        // ..#
+        // ###
+        [NA, NA, NA, NA, CC, NA, NA],
+        [NA, NA, NA, NA, CC, NA, NA],
+        [NA, NA, CC, CC, CC, NA, NA],
+    ],
+    &[
+        // #
+        // #
+        // #
+        // #
+        [NA, NA, DD, NA, NA, NA, NA],
+        [NA, NA, DD, NA, NA, NA, NA],
+        [NA, NA, DD, NA, NA, NA, NA],
+        [NA, NA, DD, NA, NA, NA, NA],
+    ],
+    &[
+        // ##
+        // ##
+        [NA, NA, EE, EE, NA, NA, NA],
+        [NA, NA, EE, EE, NA, NA, NA],
+    ],
+];
+
+/// How many rows from the top of the tower count towards the state, since
+/// rocks practically never fall further than this
+const SURFACE_ROWS: usize = 100;
+
+#[derive(Debug, Clone)]
+struct Chamber<'a> {
+    air_directions: &'a [Direction],
+    next_direction: usize,
+    rocks_dropped: usize,
+    rows: Vec<[Cell; 7]>,
 }
 
-fn simulate(air_directions: &[Direction], num_rocks: usize) -> Vec<[Cell; 7]> {
-    let mut chamber: Vec<[Cell; 7]> = vec![];
-    let mut air_directions = air_directions.iter().cycle();
-    for mut rock in gen_rocks().take(num_rocks) {
+impl<'a> Chamber<'a> {
+    fn new(air_directions: &'a [Direction]) -> Self {
+        Self {
+            air_directions,
+            next_direction: 0,
+            rocks_dropped: 0,
+            rows: Vec::new(),
+        }
+    }
+
+    fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// The next rock and jet of gas, and the top of the tower.
+    fn key(&self) -> (usize, usize, Vec<[Cell; 7]>) {
+        let surface = &self.rows[self.rows.len().saturating_sub(SURFACE_ROWS)..];
+        (
+            self.rocks_dropped % ROCKS.len(),
+            self.next_direction,
+            surface.to_vec(),
+        )
+    }
+
+    fn drop_rock(&mut self) {
+        let chamber = &mut self.rows;
+        let mut rock = ROCKS[self.rocks_dropped % ROCKS.len()].to_vec();
+        self.rocks_dropped += 1;
         let mut rock_top = chamber.len() + 3 + rock.len();
         loop {
+            // There's at least one direction, so this never runs out
+            let dir = self.air_directions[self.next_direction];
+            self.next_direction = (self.next_direction + 1) % self.air_directions.len();
             rock_top -= 1;
-            let &dir = air_directions.next().unwrap();
             let air_push_successful = rock
                 .iter()
                 .enumerate()
@@ -180,17 +224,6 @@ fn simulate(air_directions: &[Direction], num_rocks: usize) -> Vec<[Cell; 7]> {
             }
         }
     }
-    chamber
-}
-
-fn count_rocks_in_chamber_slice(slice: &[[Cell; 7]]) -> usize {
-    slice
-        .iter()
-        .flatten()
-        .filter(|&&cell| !matches!(cell, NA))
-        .count()
-        * 5
-        / 22
 }
 
 // Was really useful for debugging
@@ -1,12 +1,17 @@
-use advent_2022::*;
+use advent_2022::{
+    generate::{Generated, Rng},
+    *,
+};
+use itertools::Itertools;
 use nom::{
     character::complete::{char, line_ending, u8},
     multi::separated_list1,
     sequence::tuple,
     Parser,
 };
+use nom_supreme::ParserExt;
 
-boilerplate!(Day);
+boilerplate!(Day, day18);
 
 const SIZE: usize = 24;
 const TEST_SIZE: usize = 9;
@@ -15,31 +20,53 @@ impl Solution for Day {
     type Parsed = (Vec<Tuple>, Arr3D<SIZE>);
     type ParsedTest = (Vec<Tuple>, Arr3D<TEST_SIZE>);
     type Answer = usize;
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 64;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 58;
 
     fn parse(data: &'static str) -> IResult<'static, Self::Parsed> {
         parse(data)
     }
 
-    fn a(data: Self::Parsed) -> Self::Answer {
-        part_a(data)
+    fn a(data: Self::Parsed) -> OutResult<Self::Answer> {
+        Ok(part_a(data))
     }
 
-    fn b(data: Self::Parsed) -> Self::Answer {
-        part_b(data)
+    fn b(data: Self::Parsed) -> OutResult<Self::Answer> {
+        Ok(part_b(data))
     }
 
     fn parse_test(data: &'static str) -> IResult<'static, Self::ParsedTest> {
         parse(data)
     }
 
-    fn a_test(data: Self::ParsedTest) -> Self::Answer {
-        part_a(data)
+    fn a_test(data: Self::ParsedTest) -> OutResult<Self::Answer> {
+        Ok(part_a(data))
+    }
+
+    fn b_test(data: Self::ParsedTest) -> OutResult<Self::Answer> {
+        Ok(part_b(data))
     }
 
-    fn b_test(data: Self::ParsedTest) -> Self::Answer {
-        part_b(data)
+    /// `size` times 20 cubes, clumped together around the middle of the
+    /// space.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Parsed>> {
+        let coord = |rng: &mut Rng| (rng.u8(0..=21) + rng.u8(0..=21)) / 2;
+        let cubes = (0..size * 20)
+            .map(|_| (coord(rng), coord(rng), coord(rng)))
+            .unique()
+            .collect_vec();
+        let input = cubes
+            .iter()
+            .map(|(x, y, z)| format!("{x},{y},{z}\n"))
+            .collect();
+        // Parsing leaves a layer of air around the cubes
+        let coords = cubes
+            .into_iter()
+            .map(|(x, y, z)| (x + 1, y + 1, z + 1))
+            .collect_vec();
+        let mut matrix = [[[false; SIZE]; SIZE]; SIZE];
+        for &(x, y, z) in &coords {
+            matrix[x as usize][y as usize][z as usize] = true;
+        }
+        Some(Generated::round_trip(input, (coords, matrix)))
     }
 }
 
@@ -47,7 +74,7 @@ type Tuple = (u8, u8, u8);
 type Arr3D<const N: usize> = [[[bool; N]; N]; N];
 
 fn parse<const N: usize>(data: &str) -> IResult<'_, (Vec<Tuple>, Arr3D<N>)> {
-    separated_list1(line_ending, parse_cube)
+    separated_list1(line_ending, parse_cube::<N>)
         .map(|coords| {
             let mut matrix = [[[false; N]; N]; N];
             for &(x, y, z) in &coords {
@@ -86,9 +113,12 @@ fn part_b<const N: usize>((_coords, matrix): (Vec<Tuple>, Arr3D<N>)) -> usize {
     encountered
 }
 
-fn parse_cube(data: &str) -> IResult<'_, (u8, u8, u8)> {
-    tuple((u8, char(','), u8, char(','), u8))
-        .map(|(a, _, b, _, c)| (a + 1, b + 1, c + 1))
+/// Shifts the cube by one, so there's room for air on every side of the
+/// droplet within the `N` wide grid.
+fn parse_cube<const N: usize>(data: &str) -> IResult<'_, (u8, u8, u8)> {
+    let coord = || u8.verify(|&c| usize::from(c) + 2 < N).map(|c| c + 1);
+    tuple((coord(), char(','), coord(), char(','), coord()))
+        .map(|(a, _, b, _, c)| (a, b, c))
         .parse(data)
 }
 
@@ -1,6 +1,10 @@
 use std::ops::{Add, Mul};
 
-use advent_2022::*;
+use advent_2022::{
+    branch_and_bound::{self, Order, Problem},
+    generate::{Generated, Rng},
+    *,
+};
 use nom::{
     bytes::complete::tag,
     character::complete::{line_ending, u8},
@@ -9,46 +13,87 @@ use nom::{
     Parser,
 };
 
-boilerplate!(Day);
+boilerplate!(Day, day19);
 
 impl BasicSolution for Day {
     type Parsed = Vec<Blueprint>;
     type Answer = u32;
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 33;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 56 * 62;
 
     fn parse(data: &'static str) -> IResult<'static, Self::Parsed> {
         separated_list1(line_ending, blueprint)(data)
     }
 
-    fn a(data: Self::Parsed) -> Self::Answer {
-        data.iter()
-            .map(|blueprint| {
-                let mut best = 0;
-                branch_and_bound(blueprint, State::new(24), &mut best);
-                blueprint.id as u32 * best as u32
-            })
-            .sum()
+    fn a(data: Self::Parsed) -> OutResult<Self::Answer> {
+        Ok(data
+            .iter()
+            .map(|blueprint| blueprint.id as u32 * most_geodes(blueprint, 24) as u32)
+            .sum())
     }
 
-    fn b(data: Self::Parsed) -> Self::Answer {
-        data.iter()
+    fn b(data: Self::Parsed) -> OutResult<Self::Answer> {
+        Ok(data
+            .iter()
             .take(3)
+            .map(|blueprint| most_geodes(blueprint, 32) as u32)
+            .product())
+    }
+
+    /// `size` blueprints, with costs in the same ranges as the real ones.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Parsed>> {
+        let blueprints = (1..=size)
+            .map(|id| {
+                Some(Blueprint {
+                    id: id.try_into().ok()?,
+                    ore_robot_cost: ONE_ORE * rng.u8(2..=4),
+                    clay_robot_cost: ONE_ORE * rng.u8(2..=4),
+                    obsidian_robot_cost: ONE_ORE * rng.u8(2..=4) + ONE_CLAY * rng.u8(5..=20),
+                    geode_robot_cost: ONE_ORE * rng.u8(2..=4) + ONE_OBSIDIAN * rng.u8(5..=20),
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let input = blueprints
+            .iter()
             .map(|blueprint| {
-                let mut best = 0;
-                branch_and_bound(blueprint, State::new(32), &mut best);
-                best as u32
+                format!(
+                    "Blueprint {}: \
+                     Each ore robot costs {} ore. \
+                     Each clay robot costs {} ore. \
+                     Each obsidian robot costs {} ore and {} clay. \
+                     Each geode robot costs {} ore and {} obsidian.\n",
+                    blueprint.id,
+                    blueprint.ore_robot_cost.ore,
+                    blueprint.clay_robot_cost.ore,
+                    blueprint.obsidian_robot_cost.ore,
+                    blueprint.obsidian_robot_cost.clay,
+                    blueprint.geode_robot_cost.ore,
+                    blueprint.geode_robot_cost.obsidian,
+                )
             })
-            .product()
+            .collect();
+        Some(Generated::round_trip(input, blueprints))
     }
 }
 
-fn branch_and_bound(blueprint: &Blueprint, state: State, best: &mut u8) {
-    *best = state.geodes_secured.max(*best);
-    for state in state.branch(blueprint) {
-        if state.bound(blueprint) > *best {
-            branch_and_bound(blueprint, state, best);
-        }
+fn most_geodes(blueprint: &Blueprint, minutes: u8) -> u8 {
+    let outcome = branch_and_bound::solve(blueprint, State::new(minutes), Order::DepthFirst);
+    outcome.score().unwrap_or(0)
+}
+
+impl Problem for Blueprint {
+    type Node = State;
+    type Score = u8;
+    type Key = ();
+
+    fn score(&self, state: &State) -> Option<u8> {
+        Some(state.geodes_secured)
+    }
+
+    fn bound(&self, state: &State) -> u8 {
+        state.bound(self)
+    }
+
+    fn branch(&self, state: &State) -> impl IntoIterator<Item = State> {
+        state.branch(self)
     }
 }
 
@@ -1,4 +1,8 @@
-use advent_2022::*;
+use advent_2022::{
+    generate::{Generated, Rng},
+    *,
+};
+use itertools::Itertools;
 use nom::{
     branch::alt,
     character::complete::{char, line_ending},
@@ -7,13 +11,11 @@ use nom::{
 };
 use nom_supreme::ParserExt;
 
-boilerplate!(Day);
+boilerplate!(Day, day2);
 
 impl BasicSolution for Day {
     type Parsed = Vec<(i8, i8)>;
     type Answer = u32;
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 15;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 12;
 
     fn parse(data: &str) -> IResult<'_, Self::Parsed> {
         separated_list1(
@@ -26,28 +28,47 @@ impl BasicSolution for Day {
         )(data)
     }
 
-    fn a(data: Self::Parsed) -> Self::Answer {
-        data.into_iter()
+    fn a(data: Self::Parsed) -> OutResult<Self::Answer> {
+        Ok(data
+            .into_iter()
             .map(|(opponent_choice, own_choice)| {
                 let result_score = match (own_choice - opponent_choice).rem_euclid(3) {
                     0 => 3, // tie
                     1 => 6, // win
-                    2 => 0, // lose
-                    _ => unreachable!(),
+                    _ => 0, // lose
                 };
                 let choice_score = own_choice as u32 + 1;
                 choice_score + result_score
             })
-            .sum()
+            .sum())
     }
 
-    fn b(data: Self::Parsed) -> Self::Answer {
-        data.into_iter()
+    fn b(data: Self::Parsed) -> OutResult<Self::Answer> {
+        Ok(data
+            .into_iter()
             .map(|(opponent_choice, result)| {
                 let choice_score = (opponent_choice + result - 1).rem_euclid(3) as u32 + 1;
                 let result_score = result as u32 * 3;
                 choice_score + result_score
             })
-            .sum()
+            .sum())
+    }
+
+    /// `size` rounds.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Parsed>> {
+        let rounds = (0..size)
+            .map(|_| (rng.i8(0..3), rng.i8(0..3)))
+            .collect_vec();
+        let input = rounds
+            .iter()
+            .map(|&(opponent, own)| {
+                format!(
+                    "{} {}\n",
+                    (b'A' + opponent as u8) as char,
+                    (b'X' + own as u8) as char
+                )
+            })
+            .collect();
+        Some(Generated::round_trip(input, rounds))
     }
 }
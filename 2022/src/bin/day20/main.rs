@@ -1,17 +1,19 @@
-use advent_2022::*;
+use advent_2022::{
+    generate::{Generated, Rng},
+    *,
+};
 use itertools::{iterate, Itertools};
 use nom::{
     character::complete::{i64, line_ending},
     multi::separated_list1,
 };
 
-boilerplate!(Day);
+boilerplate!(Day, day20);
 
 impl Solution for Day {
     type Parsed = Vec<i64>;
+    type ParsedTest = Self::Parsed;
     type Answer = i64;
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 3;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 1623178306;
 
     fn parse(data: &'static str) -> IResult<'static, Self::Parsed> {
         separated_list1(line_ending, i64)(data)
@@ -21,24 +23,62 @@ impl Solution for Day {
         Self::parse(data)
     }
 
-    fn a(data: Self::Parsed) -> Self::Answer {
-        solve::<25>(data, 1, 1)
+    fn a(data: Self::Parsed) -> OutResult<Self::Answer> {
+        mix(data, 1, 1)
     }
 
-    fn b(data: Self::Parsed) -> Self::Answer {
-        solve::<25>(data, 811589153, 10)
+    fn b(data: Self::Parsed) -> OutResult<Self::Answer> {
+        mix(data, 811589153, 10)
     }
 
-    fn a_test(data: Self::ParsedTest) -> Self::TestAnswer {
+    fn a_test(data: Self::ParsedTest) -> OutResult<Self::Answer> {
         solve::<1>(data, 1, 1)
     }
 
-    fn b_test(data: Self::ParsedTest) -> Self::TestAnswer {
+    fn b_test(data: Self::ParsedTest) -> OutResult<Self::Answer> {
         solve::<1>(data, 811589153, 10)
     }
+
+    /// A list of up to `size` times 8 numbers, with a single 0 and the
+    /// other numbers repeating often.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Parsed>> {
+        let len = rng.usize(2..=size * 8);
+        let zero = rng.usize(0..len);
+        let numbers = (0..len)
+            .map(|i| {
+                if i == zero {
+                    0
+                } else {
+                    rng.i64(1..200) * if rng.bool() { 1 } else { -1 }
+                }
+            })
+            .collect_vec();
+        let input = numbers.iter().map(|n| format!("{n}\n")).collect();
+        Some(Generated::round_trip(input, numbers))
+    }
+}
+
+/// Skipping 25 numbers at a time only works when there are more than that
+fn mix(data: Vec<i64>, decryption_key: i64, iterations: usize) -> OutResult<i64> {
+    if data.len() > 25 {
+        solve::<25>(data, decryption_key, iterations)
+    } else {
+        solve::<1>(data, decryption_key, iterations)
+    }
 }
 
-fn solve<const NEXT_SIZE: usize>(data: Vec<i64>, decryption_key: i64, iterations: usize) -> i64 {
+fn solve<const NEXT_SIZE: usize>(
+    data: Vec<i64>,
+    decryption_key: i64,
+    iterations: usize,
+) -> OutResult<i64> {
+    if !(2..=u16::MAX as usize).contains(&data.len()) {
+        return Err(SolveError::unsupported(format!(
+            "can't mix {} numbers, there should be between 2 and {}",
+            data.len(),
+            u16::MAX
+        )));
+    }
     let numbers = data.into_iter().map(|x| x * decryption_key).collect_vec();
     let mut prev = (0..numbers.len() as u16).collect_vec();
     let mut next = prev.clone();
@@ -67,14 +107,14 @@ fn solve<const NEXT_SIZE: usize>(data: Vec<i64>, decryption_key: i64, iterations
     let zero_index = numbers
         .iter()
         .position(|&x| x == 0)
-        .expect("an element with value 0");
-    iterate(zero_index as u16, |&cur| {
+        .ok_or_else(|| SolveError::unsupported("no number is 0"))?;
+    Ok(iterate(zero_index as u16, |&cur| {
         find_target::<NEXT_SIZE>(cur, 1000, &prev, &next)
     })
     .skip(1)
     .take(3)
     .map(|i| numbers[i as usize])
-    .sum()
+    .sum())
 }
 
 fn fix_pairs_backwards(left: u16, right: u16, prev: &mut [u16], next: &mut [u16], stop: u16) {
@@ -84,7 +124,7 @@ fn fix_pairs_backwards(left: u16, right: u16, prev: &mut [u16], next: &mut [u16]
             next[before as usize] = after;
         })
         .find(|&(_, after)| prev[after as usize] == stop)
-        .unwrap();
+        .expect("iterate is infinite");
     prev[immediate_next as usize] = left;
     next[prev[far_prev as usize] as usize] = left;
 }
@@ -97,8 +137,11 @@ fn find_target<const NEXT_SIZE: usize>(
 ) -> u16 {
     let overshot_target = iterate(from, |&cur| next[cur as usize])
         .nth((NEXT_SIZE + amount_to_move) / NEXT_SIZE)
-        .unwrap();
+        .expect("iterate is infinite");
     iterate(overshot_target, |&cur| prev[cur as usize])
         .nth(NEXT_SIZE - amount_to_move % NEXT_SIZE)
-        .unwrap()
+        .expect("iterate is infinite")
 }
+
+#[cfg(test)]
+mod reference;
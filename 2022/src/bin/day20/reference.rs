@@ -0,0 +1,50 @@
+//! Mixing by removing and reinserting into a `Vec`, to test the skip list
+//! against.
+use advent_2022::{
+    differential::{check, Reference},
+    Nom,
+};
+use anyhow::Context;
+
+use super::Day;
+
+const REFERENCE: Reference<i64> = Reference {
+    a: |input| mix(input, 1, 1),
+    b: |input| mix(input, 811_589_153, 10),
+    max_size: 10,
+    cases: 200,
+};
+
+fn mix(input: &str, decryption_key: i64, rounds: usize) -> anyhow::Result<i64> {
+    let numbers = input
+        .lines()
+        .map(|line| Ok(line.parse::<i64>()? * decryption_key))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    // The original index of each number, in the mixed order
+    let mut mixed: Vec<usize> = (0..numbers.len()).collect();
+    for _ in 0..rounds {
+        for (i, &n) in numbers.iter().enumerate() {
+            let from = mixed
+                .iter()
+                .position(|&j| j == i)
+                .context("lost a number")?;
+            mixed.remove(from);
+            let to = (from as i64 + n).rem_euclid(mixed.len() as i64) as usize;
+            mixed.insert(to, i);
+        }
+    }
+    let zero = numbers
+        .iter()
+        .position(|&n| n == 0)
+        .context("no number is 0")?;
+    let zero = mixed.iter().position(|&j| j == zero).context("lost 0")?;
+    Ok([1000, 2000, 3000]
+        .iter()
+        .map(|offset| numbers[mixed[(zero + offset) % mixed.len()]])
+        .sum())
+}
+
+#[test]
+fn matches_reference() -> anyhow::Result<()> {
+    check::<Nom<Day>>(&REFERENCE, include_str!("sample.txt"))
+}
@@ -1,6 +1,9 @@
 use std::collections::HashMap;
 
-use advent_2022::*;
+use advent_2022::{
+    generate::{Generated, Rng},
+    *,
+};
 use nom::{
     branch::alt,
     bytes::complete::tag,
@@ -11,13 +14,11 @@ use nom::{
 };
 use nom_supreme::ParserExt;
 
-boilerplate!(Day);
+boilerplate!(Day, day21);
 
 impl BasicSolution for Day {
     type Parsed = HashMap<&'static str, Expression<'static>>;
     type Answer = i64;
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 152;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 301;
 
     fn parse(data: &'static str) -> IResult<'static, Self::Parsed> {
         separated_list1(line_ending, monkey)
@@ -25,27 +26,86 @@ impl BasicSolution for Day {
             .parse(data)
     }
 
-    fn a(data: Self::Parsed) -> i64 {
+    fn a(data: Self::Parsed) -> OutResult<Self::Answer> {
         eval(&data, "root")
     }
 
-    fn b(data: Self::Parsed) -> i64 {
+    fn b(data: Self::Parsed) -> OutResult<Self::Answer> {
         let mut knowns = HashMap::new();
-        fill_knowns(&mut knowns, &data, "root");
+        fill_knowns(&mut knowns, &data, "root")?;
         let (mut unknown, mut result, mut correction) = ("root", 0, -1);
         while unknown != "humn" {
-            let Operation((left, op, right)) = data[unknown] else {
-                panic!("{unknown} should be an operation")
+            let Operation((left, op, right)) = expression_of(&data, unknown)? else {
+                return Err(SolveError::unsupported(format!(
+                    "{unknown} depends on humn, so it should be an operation"
+                )));
             };
             (unknown, result) = match (knowns.get(&left), knowns.get(&right)) {
-                (None, Some(&val)) => (left, op.solve_for_left(result, val)),
-                (Some(&val), None) => (right, op.solve_for_right(result, val)),
-                _ => panic!("exactly one child of {unknown} should be known"),
+                (None, Some(&val)) => (left, op.solve_for_left(result, val)?),
+                (Some(&val), None) => (right, op.solve_for_right(result, val)?),
+                _ => {
+                    return Err(SolveError::unsupported(format!(
+                        "exactly one child of {unknown} should depend on humn"
+                    )))
+                }
             };
             result *= correction;
             correction = 1;
         }
-        result
+        Ok(result)
+    }
+
+    /// A tree of `size` operations, with humn on one side of root. Only
+    /// checked to parse, since the monkeys end up in a `HashMap`.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Parsed>> {
+        /// Adds a subtree of at most `operations_left` operations to
+        /// `monkeys`, returning its root and its value.
+        fn subtree(
+            rng: &mut Rng,
+            monkeys: &mut Vec<String>,
+            operations_left: &mut usize,
+            has_humn: bool,
+        ) -> (String, i64) {
+            let leaf = *operations_left == 0 || (!has_humn && rng.bool());
+            if leaf {
+                let name = if has_humn {
+                    "humn".to_owned()
+                } else {
+                    name(monkeys.len())
+                };
+                let n = rng.i64(1..20);
+                monkeys.push(format!("{name}: {n}"));
+                return (name, n);
+            }
+            *operations_left -= 1;
+            let humn_on_left = rng.bool();
+            let (left, l) = subtree(rng, monkeys, operations_left, has_humn && humn_on_left);
+            let (right, r) = subtree(rng, monkeys, operations_left, has_humn && !humn_on_left);
+            let (symbol, value) = match rng.u8(0..4) {
+                0 if l.abs() < 1_000_000 && r.abs() < 1_000_000 => ('*', l * r),
+                1 if r != 0 && l % r == 0 => ('/', l / r),
+                2 => ('-', l - r),
+                _ => ('+', l + r),
+            };
+            let name = name(monkeys.len());
+            monkeys.push(format!("{name}: {left} {symbol} {right}"));
+            (name, value)
+        }
+        /// A name of four letters for the `i`th monkey, which can't be humn
+        /// or root.
+        fn name(i: usize) -> String {
+            (0..4)
+                .map(|digit| char::from(b'a' + (i / 26_usize.pow(3 - digit) % 26) as u8))
+                .collect()
+        }
+        let mut monkeys = Vec::new();
+        let mut operations_left = size;
+        let humn_on_left = rng.bool();
+        let (left, _) = subtree(rng, &mut monkeys, &mut operations_left, humn_on_left);
+        let (right, _) = subtree(rng, &mut monkeys, &mut operations_left, !humn_on_left);
+        monkeys.push(format!("root: {left} + {right}"));
+        rng.shuffle(&mut monkeys);
+        Some(Generated::new(monkeys.join("\n") + "\n"))
     }
 }
 
@@ -85,60 +145,81 @@ fn monkey(input: &str) -> IResult<'_, (&str, Expression<'_>)> {
     separated_pair(alpha1, tag(": "), expression)(input)
 }
 
-fn eval(monkeys: &HashMap<&'static str, Expression<'static>>, monkey: &str) -> i64 {
-    match monkeys[monkey] {
-        Number(n) => n,
+fn expression_of(
+    monkeys: &HashMap<&'static str, Expression<'static>>,
+    monkey: &str,
+) -> OutResult<Expression<'static>> {
+    monkeys
+        .get(monkey)
+        .copied()
+        .ok_or_else(|| SolveError::unsupported(format!("no monkey called {monkey}")))
+}
+
+fn eval(monkeys: &HashMap<&'static str, Expression<'static>>, monkey: &str) -> OutResult<i64> {
+    match expression_of(monkeys, monkey)? {
+        Number(n) => Ok(n),
         Operation((left_monkey, op, right_monkey)) => {
-            op.eval(eval(monkeys, left_monkey), eval(monkeys, right_monkey))
+            op.eval(eval(monkeys, left_monkey)?, eval(monkeys, right_monkey)?)
         }
     }
 }
 
+/// Evaluates every monkey that doesn't depend on humn, returning `None` for
+/// the ones that do.
 fn fill_knowns<'a>(
     knowns: &mut HashMap<&'a str, i64>,
     monkeys: &'a HashMap<&'static str, Expression<'static>>,
     monkey: &'a str,
-) -> Option<i64> {
+) -> OutResult<Option<i64>> {
     if monkey == "humn" {
-        return None;
+        return Ok(None);
     }
-    let val = match monkeys[monkey] {
+    let val = match expression_of(monkeys, monkey)? {
         Number(n) => n,
         Operation((left_monkey, op, right_monkey)) => {
-            let left = fill_knowns(knowns, monkeys, left_monkey);
-            let right = fill_knowns(knowns, monkeys, right_monkey);
-            op.eval(left?, right?)
+            let left = fill_knowns(knowns, monkeys, left_monkey)?;
+            let right = fill_knowns(knowns, monkeys, right_monkey)?;
+            let (Some(left), Some(right)) = (left, right) else {
+                return Ok(None);
+            };
+            op.eval(left, right)?
         }
     };
     knowns.insert(monkey, val);
-    Some(val)
+    Ok(Some(val))
+}
+
+fn divide(dividend: i64, divisor: i64) -> OutResult<i64> {
+    dividend
+        .checked_div(divisor)
+        .ok_or_else(|| SolveError::unsupported(format!("can't divide {dividend} by {divisor}")))
 }
 
 impl Operator {
-    fn eval(self, left: i64, right: i64) -> i64 {
+    fn eval(self, left: i64, right: i64) -> OutResult<i64> {
         match self {
-            Add => left + right,
-            Subtract => left - right,
-            Multiply => left * right,
-            Divide => left / right,
+            Add => Ok(left + right),
+            Subtract => Ok(left - right),
+            Multiply => Ok(left * right),
+            Divide => divide(left, right),
         }
     }
 
-    fn solve_for_left(self, result: i64, right: i64) -> i64 {
+    fn solve_for_left(self, result: i64, right: i64) -> OutResult<i64> {
         match self {
-            Add => result - right,
-            Subtract => result + right,
-            Multiply => result / right,
-            Divide => result * right,
+            Add => Ok(result - right),
+            Subtract => Ok(result + right),
+            Multiply => divide(result, right),
+            Divide => Ok(result * right),
         }
     }
 
-    fn solve_for_right(self, result: i64, left: i64) -> i64 {
+    fn solve_for_right(self, result: i64, left: i64) -> OutResult<i64> {
         match self {
-            Add => result - left,
-            Subtract => left - result,
-            Multiply => result / left,
-            Divide => left / result,
+            Add => Ok(result - left),
+            Subtract => Ok(left - result),
+            Multiply => divide(result, left),
+            Divide => divide(left, result),
         }
     }
 }
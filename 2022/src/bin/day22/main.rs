@@ -1,4 +1,7 @@
-use advent_2022::*;
+use advent_2022::{
+    generate::{Generated, Rng},
+    *,
+};
 use itertools::iterate;
 use nom::{
     branch::alt,
@@ -8,13 +11,12 @@ use nom::{
 };
 use nom_supreme::ParserExt;
 
-boilerplate!(Day);
+boilerplate!(Day, day22);
 
 impl Solution for Day {
     type Parsed = (Vec<&'static [u8]>, Vec<Move>);
+    type ParsedTest = Self::Parsed;
     type Answer = usize;
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 6032;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 5031;
 
     fn parse(data: &'static str) -> IResult<'static, Self::Parsed> {
         let mut lines = data.lines();
@@ -23,9 +25,8 @@ impl Solution for Day {
             .take_while(|line| !line.is_empty())
             .map(str::as_bytes)
             .collect();
-        let path = lines
-            .next()
-            .expect("there should be a path after the empty line");
+        // Without a path, fail parsing it at the end of the input
+        let path = lines.next().unwrap_or(&data[data.len()..]);
         many1(parse_move)
             .parse(path)
             .map(|(rest, moves)| (rest, (grid, moves)))
@@ -35,20 +36,54 @@ impl Solution for Day {
         Self::parse(data)
     }
 
-    fn a(data: Self::Parsed) -> Self::Answer {
-        solve(data, move_one_2d::<150, 200>)
+    fn a(data: Self::Parsed) -> OutResult<Self::Answer> {
+        solve::<150, 200>(data, move_one_2d::<150, 200>)
+    }
+
+    fn a_test(data: Self::ParsedTest) -> OutResult<Self::Answer> {
+        solve::<16, 12>(data, move_one_2d::<16, 12>)
     }
 
-    fn a_test(data: Self::ParsedTest) -> Self::TestAnswer {
-        solve(data, move_one_2d::<16, 12>)
+    fn b(data: Self::Parsed) -> OutResult<Self::Answer> {
+        solve::<150, 200>(data, move_one_cube)
     }
 
-    fn b(data: Self::Parsed) -> Self::Answer {
-        solve(data, move_one_cube)
+    fn b_test(data: Self::ParsedTest) -> OutResult<Self::Answer> {
+        solve::<16, 12>(data, move_one_sample_cube)
     }
 
-    fn b_test(data: Self::ParsedTest) -> Self::TestAnswer {
-        solve(data, move_one_sample_cube)
+    /// A map folding into the same cube as the real ones, and a path of
+    /// `size` times 10 moves.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Parsed>> {
+        const FACES: [&str; 4] = [" ##", " # ", "## ", "#  "];
+        let density = rng.u8(0..20);
+        let mut input = String::new();
+        let mut grid = Vec::new();
+        for y in 0..200 {
+            let row = (0..150)
+                .map(|x| match FACES[y / 50].as_bytes()[x / 50] {
+                    b' ' => ' ',
+                    _ if rng.u8(0..100) < density && (x, y) != (50, 0) => '#',
+                    _ => '.',
+                })
+                .collect::<String>();
+            let row = row.trim_end();
+            input.push_str(row);
+            input.push('\n');
+            grid.push(&*row.as_bytes().to_vec().leak());
+        }
+        input.push('\n');
+        let mut moves = Vec::new();
+        for _ in 0..size * 5 {
+            let (forward, turn) = (rng.u8(1..50), rng.usize(0..2));
+            input.push_str(&format!("{forward}{}", ['L', 'R'][turn]));
+            moves.push(Forward(usize::from(forward)));
+            moves.push(Turn([CounterClockwise, Clockwise][turn]));
+        }
+        let forward = rng.u8(1..50);
+        input.push_str(&format!("{forward}\n"));
+        moves.push(Forward(usize::from(forward)));
+        Some(Generated::round_trip(input, (grid, moves)))
     }
 }
 
@@ -90,40 +125,51 @@ struct State {
     direction: Direction,
 }
 
-fn solve((grid, path): (Vec<&'static [u8]>, Vec<Move>), move_one: fn(&State) -> State) -> usize {
-    let State { x, y, direction } = path.into_iter().fold(
-        State {
-            y: 0,
-            x: grid[0].iter().position(|&c| c == b'.').unwrap(),
-            direction: Right,
+/// Follows the path on an `X` by `Y` map.
+fn solve<const X: usize, const Y: usize>(
+    (grid, path): (Vec<&'static [u8]>, Vec<Move>),
+    move_one: fn(&State) -> State,
+) -> OutResult<usize> {
+    if grid.len() != Y || grid.iter().any(|row| row.len() > X) {
+        return Err(SolveError::unsupported(format!(
+            "the map should be {X} wide and {Y} tall"
+        )));
+    }
+    let start = State {
+        y: 0,
+        x: grid[0]
+            .iter()
+            .position(|&c| c == b'.')
+            .ok_or_else(|| SolveError::unsupported("no open tile in the top row"))?,
+        direction: Right,
+    };
+    let State { x, y, direction } = path.into_iter().fold(start, |state, m| match m {
+        Turn(Clockwise) => State {
+            direction: match state.direction {
+                Up => Right,
+                Right => Down,
+                Down => Left,
+                Left => Up,
+            },
+            ..state
         },
-        |state, m| match m {
-            Turn(Clockwise) => State {
-                direction: match state.direction {
-                    Up => Right,
-                    Right => Down,
-                    Down => Left,
-                    Left => Up,
-                },
-                ..state
-            },
-            Turn(CounterClockwise) => State {
-                direction: match state.direction {
-                    Up => Left,
-                    Left => Down,
-                    Down => Right,
-                    Right => Up,
-                },
-                ..state
-            },
-            Forward(n) => iterate(state, move_one)
-                .filter(|s| *grid[s.y].get(s.x).unwrap_or(&b' ') != b' ')
-                .take(n + 1)
-                .take_while(|s| grid[s.y][s.x] == b'.')
-                .last()
-                .unwrap(),
+        Turn(CounterClockwise) => State {
+            direction: match state.direction {
+                Up => Left,
+                Left => Down,
+                Down => Right,
+                Right => Up,
+            },
+            ..state
         },
-    );
+        // The first state is the current tile, which is always open
+        Forward(n) => iterate(state, move_one)
+            .filter(|s| *grid[s.y].get(s.x).unwrap_or(&b' ') != b' ')
+            .take(n + 1)
+            .take_while(|s| grid[s.y][s.x] == b'.')
+            .last()
+            .unwrap_or(state),
+    });
     let row_number = y + 1;
     let column_number = x + 1;
     let facing_number = match direction {
@@ -132,7 +178,7 @@ fn solve((grid, path): (Vec<&'static [u8]>, Vec<Move>), move_one: fn(&State) ->
         Left => 2,
         Up => 3,
     };
-    1000 * row_number + 4 * column_number + facing_number
+    Ok(1000 * row_number + 4 * column_number + facing_number)
 }
 
 fn move_one_2d<const X: usize, const Y: usize>(&State { x, y, direction }: &State) -> State {
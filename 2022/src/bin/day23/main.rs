@@ -1,61 +1,182 @@
-#![feature(portable_simd)]
-#![feature(iter_map_windows)]
+#![cfg_attr(feature = "nightly", feature(portable_simd))]
 
-use std::{ops::Range, simd::u8x32};
+use std::ops::Range;
 
-use advent_2022::*;
+use advent_2022::{
+    generate::{Generated, Rng},
+    *,
+};
 use itertools::{chain, Itertools};
+use row::{shift_east, shift_west, Row};
 
-boilerplate!(Day);
+boilerplate!(Day, day23);
 
 impl BasicSolution for Day {
     type Parsed = BitGrid;
     type Answer = usize;
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 110;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 20;
 
-    fn parse(data: &str) -> IResult<'_, Self::Parsed> {
+    fn parse(data: &'static str) -> IResult<'static, Self::Parsed> {
         let mut grid = BitGrid::new();
-        data.lines().enumerate().for_each(|(row, line)| {
-            line.chars()
-                .enumerate()
-                .filter(|&(_, c)| c == '#')
-                .for_each(|(col, _)| grid.insert(row + 24, col + 72))
-        });
+        for (row, line) in data.lines().enumerate() {
+            for (col, c) in line.char_indices() {
+                if c == '#' && !grid.insert(row + 24, col + 72) {
+                    // Out of the grid, fail on this elf
+                    let error = nom::error::Error::new(&line[col..], nom::error::ErrorKind::Verify);
+                    return Err(nom::Err::Failure(error));
+                }
+            }
+        }
         Ok(("", grid))
     }
 
-    fn a(mut elve_grid: Self::Parsed) -> Self::Answer {
-        elve_grid.run_simulation(10);
+    fn a(mut elve_grid: Self::Parsed) -> OutResult<Self::Answer> {
+        elve_grid.run_simulation(10)?;
         let (rows, cols) = elve_grid.bounds();
-        rows.len() * cols.len() - elve_grid.len()
+        Ok(rows.len() * cols.len() - elve_grid.len())
     }
 
-    fn b(mut elve_grid: Self::Parsed) -> Self::Answer {
+    fn b(mut elve_grid: Self::Parsed) -> OutResult<Self::Answer> {
         elve_grid
-            .run_simulation(10000)
-            .expect("not done within 10000 rounds")
+            .run_simulation(10000)?
+            .ok_or_else(|| SolveError::unsupported("not done within 10000 rounds"))
+    }
+
+    /// A patch of elves up to `size` times 2 wide and tall.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Parsed>> {
+        let (height, width) = (rng.usize(1..=size * 2), rng.usize(1..=size * 2));
+        let density = rng.u8(10..90);
+        let mut elves = (0..height * width)
+            .map(|_| rng.u8(0..100) < density)
+            .collect::<Vec<_>>();
+        if !elves.contains(&true) {
+            elves[0] = true;
+        }
+        let mut input = String::new();
+        let mut grid = BitGrid::new();
+        for (row, line) in elves.chunks(width).enumerate() {
+            for (col, &elf) in line.iter().enumerate() {
+                input.push(if elf { '#' } else { '.' });
+                if elf {
+                    grid.insert(row + 24, col + 72);
+                }
+            }
+            input.push('\n');
+        }
+        Some(Generated::round_trip(input, grid))
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-struct BitGrid([u8x32; 160]);
+struct BitGrid([Row; 160]);
+
+/// The number of bits in a [`Row`]
+const COLS: usize = 256;
+
+/// A row of the grid, a bit per column. With the `nightly` feature it's a
+/// SIMD vector, otherwise four words shifted by hand.
+#[cfg(feature = "nightly")]
+mod row {
+    use std::simd::u8x32;
+
+    pub type Row = u8x32;
+
+    pub fn shift_west(&row: &Row) -> Row {
+        (row >> u8x32::splat(1)) | (row.rotate_elements_left::<1>() << u8x32::splat(7))
+    }
+
+    pub fn shift_east(&row: &Row) -> Row {
+        (row << u8x32::splat(1)) | (row.rotate_elements_right::<1>() >> u8x32::splat(7))
+    }
+
+    pub fn insert(row: &mut Row, col: usize) {
+        row[col / 8] |= 1 << (col % 8);
+    }
+
+    pub fn get(row: &Row, col: usize) -> bool {
+        row[col / 8] & (1 << (col % 8)) != 0
+    }
 
-fn shift_west(&row: &u8x32) -> u8x32 {
-    (row >> u8x32::splat(1)) | (row.rotate_elements_left::<1>() << u8x32::splat(7))
+    pub fn count(row: &Row) -> usize {
+        row.as_array().iter().map(|x| x.count_ones() as usize).sum()
+    }
 }
 
-fn shift_east(&row: &u8x32) -> u8x32 {
-    (row << u8x32::splat(1)) | (row.rotate_elements_right::<1>() >> u8x32::splat(7))
+#[cfg(not(feature = "nightly"))]
+mod row {
+    use std::{
+        array,
+        ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not},
+    };
+
+    /// Column `col` is bit `col % 64` of word `col / 64`, like the bytes of
+    /// the SIMD vector.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+    pub struct Row([u64; 4]);
+
+    pub fn shift_west(&Row(words): &Row) -> Row {
+        Row(array::from_fn(|i| words[i] >> 1 | words[(i + 1) % 4] << 63))
+    }
+
+    pub fn shift_east(&Row(words): &Row) -> Row {
+        Row(array::from_fn(|i| words[i] << 1 | words[(i + 3) % 4] >> 63))
+    }
+
+    pub fn insert(row: &mut Row, col: usize) {
+        row.0[col / 64] |= 1 << (col % 64);
+    }
+
+    pub fn get(row: &Row, col: usize) -> bool {
+        row.0[col / 64] & (1 << (col % 64)) != 0
+    }
+
+    pub fn count(row: &Row) -> usize {
+        row.0.iter().map(|x| x.count_ones() as usize).sum()
+    }
+
+    impl BitAnd for Row {
+        type Output = Row;
+
+        fn bitand(self, rhs: Row) -> Row {
+            Row(array::from_fn(|i| self.0[i] & rhs.0[i]))
+        }
+    }
+
+    impl BitOr for Row {
+        type Output = Row;
+
+        fn bitor(self, rhs: Row) -> Row {
+            Row(array::from_fn(|i| self.0[i] | rhs.0[i]))
+        }
+    }
+
+    impl Not for Row {
+        type Output = Row;
+
+        fn not(self) -> Row {
+            Row(self.0.map(|word| !word))
+        }
+    }
+
+    impl BitAndAssign for Row {
+        fn bitand_assign(&mut self, rhs: Row) {
+            *self = *self & rhs;
+        }
+    }
+
+    impl BitOrAssign for Row {
+        fn bitor_assign(&mut self, rhs: Row) {
+            *self = *self | rhs;
+        }
+    }
 }
 
 fn propose(
-    [nw, n, ne]: &[u8x32; 3],
-    [w, cur, e]: &[u8x32; 3],
-    [sw, s, se]: &[u8x32; 3],
+    [nw, n, ne]: [Row; 3],
+    [w, cur, e]: [Row; 3],
+    [sw, s, se]: [Row; 3],
     priority: [Direction; 4],
-) -> [u8x32; 4] {
-    let mut propositions = [*cur; 4];
+) -> [Row; 4] {
+    let mut propositions = [cur; 4];
     let mut not_chosen = nw | n | ne | w | e | sw | s | se;
     for d in priority {
         let (row, dir_available) = match d {
@@ -71,15 +192,15 @@ fn propose(
 }
 
 fn collide_proposals(
-    [_, south, _, _]: &[u8x32; 4],
-    [_, _, west, east]: &[u8x32; 4],
-    [north, _, _, _]: &[u8x32; 4],
-) -> [u8x32; 4] {
+    [_, south, _, _]: [Row; 4],
+    [_, _, west, east]: [Row; 4],
+    [north, _, _, _]: [Row; 4],
+) -> [Row; 4] {
     [
-        north & !*south,
-        south & !*north,
-        shift_west(west) & !shift_east(east),
-        shift_east(east) & !shift_west(west),
+        north & !south,
+        south & !north,
+        shift_west(&west) & !shift_east(&east),
+        shift_east(&east) & !shift_west(&west),
     ]
 }
 
@@ -95,34 +216,42 @@ use Direction::*;
 
 impl BitGrid {
     fn new() -> Self {
-        Self([Default::default(); 160])
+        Self([Row::default(); 160])
     }
 
-    fn run_simulation(&mut self, max_rounds: usize) -> Option<usize> {
+    fn run_simulation(&mut self, max_rounds: usize) -> OutResult<Option<usize>> {
         let mut priority = [North, South, West, East];
         for round in 0..max_rounds {
             let moved;
-            (*self, moved) = self.play_round(priority);
+            (*self, moved) = self.play_round(priority)?;
             if !moved {
-                return Some(round + 1);
+                return Ok(Some(round + 1));
             }
             priority.rotate_left(1);
         }
-        None
+        Ok(None)
     }
 
-    fn play_round(&self, priority: [Direction; 4]) -> (Self, bool) {
+    fn play_round(&self, priority: [Direction; 4]) -> OutResult<(Self, bool)> {
         let mut new_self = self.clone();
         let mut moved = false;
-        let zeros = [Default::default(); 2];
+        let mut escaped = None;
+        let zeros = [Row::default(); 2];
         chain!(&zeros, &self.0, &zeros)
             .map(|row| [shift_east(row), *row, shift_west(row)])
-            .map_windows(|[above, cur, below]| propose(above, cur, below, priority))
-            .map_windows(|[above, cur, below]| collide_proposals(above, cur, below))
+            .tuple_windows()
+            .map(|(above, cur, below)| propose(above, cur, below, priority))
+            .tuple_windows()
+            .map(|(above, cur, below)| collide_proposals(above, cur, below))
             .enumerate()
             .for_each(|(i, [from_south, from_north, from_east, from_west])| {
                 let destinations = from_north | from_south | from_west | from_east;
-                if destinations == u8x32::splat(0) {
+                if destinations == Row::default() {
+                    return;
+                }
+                let at_edge = |col| row::get(&destinations, col);
+                if i == 0 || i == self.0.len() - 1 || at_edge(0) || at_edge(COLS - 1) {
+                    escaped.get_or_insert(i);
                     return;
                 }
                 moved = true;
@@ -132,20 +261,30 @@ impl BitGrid {
                 new_self.0[i] &= !shift_east(&from_east);
                 new_self.0[i] |= destinations;
             });
-        (new_self, moved)
+        if let Some(row) = escaped {
+            return Err(SolveError::unsupported(format!(
+                "the elves spread to the edge of the grid, at row {row}"
+            )));
+        }
+        Ok((new_self, moved))
     }
 
-    fn insert(&mut self, row: usize, col: usize) {
-        self.0[row][col / 8] |= 1 << (col % 8);
+    /// Returns whether the position is in the grid.
+    fn insert(&mut self, row: usize, col: usize) -> bool {
+        let in_grid = row < self.0.len() && col < COLS;
+        if in_grid {
+            row::insert(&mut self.0[row], col);
+        }
+        in_grid
     }
 
     fn get(&self, row: usize, col: usize) -> bool {
-        self.0[row][col / 8] & (1 << (col % 8)) != 0
+        row::get(&self.0[row], col)
     }
 
     fn iter(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
-        (0..160)
-            .cartesian_product(0..256)
+        (0..self.0.len())
+            .cartesian_product(0..COLS)
             .filter(|&(row, col)| self.get(row, col))
     }
 
@@ -164,11 +303,7 @@ impl BitGrid {
     }
 
     fn len(&self) -> usize {
-        self.0
-            .iter()
-            .flat_map(|x| x.as_array())
-            .map(|x| x.count_ones() as usize)
-            .sum()
+        self.0.iter().map(row::count).sum()
     }
 
     // for debugging
@@ -188,3 +323,6 @@ impl BitGrid {
         println!();
     }
 }
+
+#[cfg(test)]
+mod reference;
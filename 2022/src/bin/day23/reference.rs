@@ -0,0 +1,94 @@
+//! The elves as a set of positions, moved one at a time, to test the bit
+//! grid against.
+use std::collections::{HashMap, HashSet};
+
+use advent_2022::{
+    differential::{check, Reference},
+    Nom,
+};
+use anyhow::Context;
+
+use super::Day;
+
+const REFERENCE: Reference<usize> = Reference {
+    a: |input| {
+        let mut elves = parse(input);
+        for round in 0..10 {
+            play_round(&mut elves, round);
+        }
+        let rows = elves.iter().map(|&(row, _)| row);
+        let cols = elves.iter().map(|&(_, col)| col);
+        let height = rows.clone().max().context("no elves")? - rows.min().context("no elves")?;
+        let width = cols.clone().max().context("no elves")? - cols.min().context("no elves")?;
+        Ok(((height + 1) * (width + 1)) as usize - elves.len())
+    },
+    b: |input| {
+        let mut elves = parse(input);
+        (0..10000)
+            .find(|&round| !play_round(&mut elves, round))
+            .map(|round| round + 1)
+            .context("not done within 10000 rounds")
+    },
+    max_size: 6,
+    cases: 100,
+};
+
+type Elves = HashSet<(i32, i32)>;
+
+fn parse(input: &str) -> Elves {
+    input
+        .lines()
+        .enumerate()
+        .flat_map(|(row, line)| {
+            line.char_indices()
+                .filter(|&(_, c)| c == '#')
+                .map(move |(col, _)| (row as i32, col as i32))
+        })
+        .collect()
+}
+
+/// Returns whether any elf moved.
+fn play_round(elves: &mut Elves, round: usize) -> bool {
+    // The positions to check and the step to take, for north, south, west and east
+    let directions = [
+        ([(-1, -1), (-1, 0), (-1, 1)], (-1, 0)),
+        ([(1, -1), (1, 0), (1, 1)], (1, 0)),
+        ([(-1, -1), (0, -1), (1, -1)], (0, -1)),
+        ([(-1, 1), (0, 1), (1, 1)], (0, 1)),
+    ];
+    let taken = |(row, col): (i32, i32), (drow, dcol): (i32, i32)| {
+        elves.contains(&(row + drow, col + dcol))
+    };
+    let mut proposals: HashMap<(i32, i32), Vec<(i32, i32)>> = HashMap::new();
+    for &elf in elves.iter() {
+        let alone = (-1..=1)
+            .flat_map(|drow| (-1..=1).map(move |dcol| (drow, dcol)))
+            .all(|offset| offset == (0, 0) || !taken(elf, offset));
+        if alone {
+            continue;
+        }
+        let proposal = (0..4)
+            .map(|i| directions[(round + i) % 4])
+            .find(|(checked, _)| checked.iter().all(|&offset| !taken(elf, offset)));
+        if let Some((_, (drow, dcol))) = proposal {
+            proposals
+                .entry((elf.0 + drow, elf.1 + dcol))
+                .or_default()
+                .push(elf);
+        }
+    }
+    let mut moved = false;
+    for (to, from) in proposals {
+        if let [from] = from[..] {
+            elves.remove(&from);
+            elves.insert(to);
+            moved = true;
+        }
+    }
+    moved
+}
+
+#[test]
+fn matches_reference() -> anyhow::Result<()> {
+    check::<Nom<Day>>(&REFERENCE, include_str!("sample.txt"))
+}
@@ -1,28 +1,34 @@
 use std::collections::VecDeque;
 
-use advent_2022::*;
+use advent_2022::{
+    generate::{Generated, Rng},
+    *,
+};
 use itertools::izip;
 
-boilerplate!(Day);
+boilerplate!(Day, day24);
 
 impl Solution for Day {
     type Parsed = (Blizzards, usize);
+    type ParsedTest = Self::Parsed;
     type Answer = usize;
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 18;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 54;
 
     fn parse(data: &str) -> IResult<'_, Self::Parsed> {
-        let width = data.find('\n').expect("no newline") - 2;
+        // Checked by `simulate_shortest_path`
+        let width = data.find('\n').unwrap_or(data.len()).saturating_sub(2);
+        // Every line but the walls at the top and the bottom
+        let rows = data.lines().count().saturating_sub(2);
         let (up, (down, (left, right))) = data
             .lines()
-            .filter(|line| &line[2..3] != "#")
+            .skip(1)
+            .take(rows)
             .map(|line| {
                 let (mut up, mut down, mut left, mut right) = (0, 0, 0, 0);
                 line.bytes()
                     .filter(|&c| c != b'#')
                     .enumerate()
                     .for_each(|(col, c)| {
-                        let bit = 1 << col;
+                        let bit = 1u128.checked_shl(col as u32).unwrap_or_default();
                         match c {
                             b'>' => right |= bit,
                             b'<' => left |= bit,
@@ -47,21 +53,69 @@ impl Solution for Day {
         Self::parse(data)
     }
 
-    fn a((mut blizzards, width): Self::Parsed) -> Self::Answer {
+    fn a((mut blizzards, width): Self::Parsed) -> OutResult<Self::Answer> {
         simulate_shortest_path::<25>(&mut blizzards, width, Exit)
     }
 
-    fn a_test((mut blizzards, width): Self::ParsedTest) -> Self::Answer {
+    fn a_test((mut blizzards, width): Self::ParsedTest) -> OutResult<Self::Answer> {
         simulate_shortest_path::<4>(&mut blizzards, width, Exit)
     }
 
-    fn b((mut blizzards, width): Self::Parsed) -> Self::Answer {
+    fn b((mut blizzards, width): Self::Parsed) -> OutResult<Self::Answer> {
         simulate_3::<25>(&mut blizzards, width)
     }
 
-    fn b_test((mut blizzards, width): Self::ParsedTest) -> Self::Answer {
+    fn b_test((mut blizzards, width): Self::ParsedTest) -> OutResult<Self::Answer> {
         simulate_3::<4>(&mut blizzards, width)
     }
+
+    fn validate((blizzards, width): &Self::Parsed) -> OutResult<()> {
+        check_valley::<25>(blizzards, *width)
+    }
+
+    /// A valley as tall as the real ones and up to `size` + 2 wide, with no
+    /// blizzards blowing through the entrance or the exit.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Parsed>> {
+        let (height, width) = (25, rng.usize(2..size + 3));
+        let density = rng.u8(5..40);
+        let wall = |opening| {
+            (0..width + 2)
+                .map(|col| if col == opening { '.' } else { '#' })
+                .collect::<String>()
+        };
+        let mut input = wall(1) + "\n";
+        let mut blizzards = Blizzards::default();
+        for _ in 0..height {
+            input.push('#');
+            let (mut up, mut down, mut left, mut right) = (0, 0, 0, 0);
+            for col in 0..width {
+                let vertical = col != 0 && col != width - 1;
+                let tile = match rng.u8(0..100) {
+                    roll if roll >= density => '.',
+                    _ if !vertical => ['<', '>'][rng.usize(0..2)],
+                    _ => ['<', '>', '^', 'v'][rng.usize(0..4)],
+                };
+                let bit = 1 << col;
+                match tile {
+                    '>' => right |= bit,
+                    '<' => left |= bit,
+                    '^' => up |= bit,
+                    'v' => down |= bit,
+                    _ => {}
+                }
+                input.push(tile);
+            }
+            input.push_str("#\n");
+            blizzards.up.push_back(up);
+            blizzards.down.push_back(down);
+            blizzards.left.push(left);
+            blizzards.right.push(right);
+        }
+        Some(Generated::round_trip(
+            input + &wall(width) + "\n",
+            (blizzards, width),
+        ))
+    }
 }
 
 #[derive(Debug, Default, Clone)]
@@ -111,20 +165,41 @@ enum Destination {
 
 use Destination::*;
 
-fn simulate_3<const HEIGHT: usize>(blizzards: &mut Blizzards, width: usize) -> usize {
-    simulate_shortest_path::<HEIGHT>(blizzards, width, Exit)
-        + simulate_shortest_path::<HEIGHT>(blizzards, width, Entrance)
-        + simulate_shortest_path::<HEIGHT>(blizzards, width, Exit)
+fn simulate_3<const HEIGHT: usize>(blizzards: &mut Blizzards, width: usize) -> OutResult<usize> {
+    Ok(simulate_shortest_path::<HEIGHT>(blizzards, width, Exit)?
+        + simulate_shortest_path::<HEIGHT>(blizzards, width, Entrance)?
+        + simulate_shortest_path::<HEIGHT>(blizzards, width, Exit)?)
+}
+
+/// The rows are fixed-size arrays, each a `u128` with a bit per column.
+fn check_valley<const HEIGHT: usize>(blizzards: &Blizzards, width: usize) -> OutResult<()> {
+    if HEIGHT != blizzards.right.len() {
+        return Err(SolveError::unsupported(format!(
+            "the valley should be {HEIGHT} rows tall, not {}",
+            blizzards.right.len()
+        )));
+    }
+    if !(1..u128::BITS as usize).contains(&width) {
+        return Err(SolveError::unsupported(format!(
+            "the valley should be between 1 and {} columns wide, not {width}",
+            u128::BITS - 1
+        )));
+    }
+    Ok(())
 }
 
 fn simulate_shortest_path<const HEIGHT: usize>(
     blizzards: &mut Blizzards,
     width: usize,
     destination: Destination,
-) -> usize {
-    assert_eq!(HEIGHT, blizzards.right.len());
+) -> OutResult<usize> {
+    check_valley::<HEIGHT>(blizzards, width)?;
+    // Whatever is reachable is reachable again a period later, so once the
+    // reachable positions had a period to grow into each cell, there's no way
+    let period = width * HEIGHT;
+    let max_minutes = (width * HEIGHT + 1) * period;
     let mut positions = [0; HEIGHT];
-    for minute in 1.. {
+    for minute in 1..=max_minutes {
         blizzards.update(width);
         positions = adjacent_positions(&positions, width);
         match destination {
@@ -144,8 +219,13 @@ fn simulate_shortest_path<const HEIGHT: usize>(
             || matches!(destination, Entrance) && positions[0] & 1 == 1
         {
             blizzards.update(width);
-            return minute + 1;
+            return Ok(minute + 1);
         }
     }
-    unreachable!()
+    Err(SolveError::no_answer(format!(
+        "there's no way to the {destination:?}"
+    )))
 }
+
+#[cfg(test)]
+mod reference;
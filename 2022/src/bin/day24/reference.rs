@@ -0,0 +1,124 @@
+//! A breadth-first search that works out where each blizzard is from the
+//! minute, to test the `u128` rows against.
+use std::collections::{HashSet, VecDeque};
+
+use advent_2022::{
+    differential::{check, Reference},
+    Nom,
+};
+use anyhow::{bail, Context};
+
+use super::Day;
+
+const REFERENCE: Reference<usize> = Reference {
+    a: |input| {
+        let valley = Valley::parse(input)?;
+        valley.cross(valley.entrance, valley.exit, 0)
+    },
+    b: |input| {
+        let valley = Valley::parse(input)?;
+        let there = valley.cross(valley.entrance, valley.exit, 0)?;
+        let back = valley.cross(valley.exit, valley.entrance, there)?;
+        valley.cross(valley.entrance, valley.exit, back)
+    },
+    max_size: 17,
+    cases: 50,
+};
+
+type Position = (i32, i32);
+
+struct Valley {
+    height: i32,
+    width: i32,
+    /// Where each blizzard starts, and which way it blows
+    blizzards: Vec<(Position, Position)>,
+    entrance: Position,
+    exit: Position,
+}
+
+impl Valley {
+    fn parse(input: &str) -> anyhow::Result<Self> {
+        let lines: Vec<&str> = input.lines().collect();
+        let [first, inside @ .., last] = &lines[..] else {
+            bail!("the valley needs walls")
+        };
+        let opening = |line: &str| Some(line.find('.')? as i32 - 1);
+        let mut blizzards = Vec::new();
+        for (row, line) in inside.iter().enumerate() {
+            for (col, c) in line.bytes().enumerate().skip(1) {
+                let direction = match c {
+                    b'^' => (-1, 0),
+                    b'v' => (1, 0),
+                    b'<' => (0, -1),
+                    b'>' => (0, 1),
+                    _ => continue,
+                };
+                blizzards.push(((row as i32, col as i32 - 1), direction));
+            }
+        }
+        Ok(Self {
+            height: inside.len() as i32,
+            width: first.len() as i32 - 2,
+            blizzards,
+            entrance: (-1, opening(first).context("no entrance")?),
+            exit: (inside.len() as i32, opening(last).context("no exit")?),
+        })
+    }
+
+    fn blizzards_at(&self, minute: usize) -> HashSet<Position> {
+        let minute = minute as i32;
+        self.blizzards
+            .iter()
+            .map(|&((row, col), (drow, dcol))| {
+                (
+                    (row + drow * minute).rem_euclid(self.height),
+                    (col + dcol * minute).rem_euclid(self.width),
+                )
+            })
+            .collect()
+    }
+
+    /// The minute the expedition gets to `to`, leaving `from` at `start`.
+    fn cross(&self, from: Position, to: Position, start: usize) -> anyhow::Result<usize> {
+        let period = (1..)
+            .map(|n| n * self.height as usize)
+            .find(|n| n % self.width as usize == 0)
+            .context("the blizzards repeat")?;
+        // Where the expedition could be on each of the last `period` minutes
+        let mut history = VecDeque::from([HashSet::from([from])]);
+        for minute in start + 1.. {
+            let blizzards = self.blizzards_at(minute);
+            let reachable: HashSet<Position> = history[history.len() - 1]
+                .iter()
+                .flat_map(|&(row, col)| {
+                    [(0, 0), (-1, 0), (1, 0), (0, -1), (0, 1)]
+                        .map(|(drow, dcol)| (row + drow, col + dcol))
+                })
+                .filter(|&(row, col)| {
+                    (row, col) == from
+                        || (row, col) == to
+                        || (0..self.height).contains(&row)
+                            && (0..self.width).contains(&col)
+                            && !blizzards.contains(&(row, col))
+                })
+                .collect();
+            if reachable.contains(&to) {
+                return Ok(minute);
+            }
+            if history.len() == period {
+                if history[0] == reachable {
+                    bail!("there's no way across");
+                }
+                history.pop_front();
+            }
+            history.push_back(reachable);
+        }
+        unreachable!()
+    }
+}
+
+/// A valley as tall as the real ones, with no blizzards blowing through the
+#[test]
+fn matches_reference() -> anyhow::Result<()> {
+    check::<Nom<Day>>(&REFERENCE, include_str!("sample.txt"))
+}
@@ -1,4 +1,7 @@
-use advent_2022::*;
+use advent_2022::{
+    generate::{Generated, Rng},
+    *,
+};
 use nom::{
     branch::alt,
     character::complete::{char, line_ending},
@@ -6,25 +9,31 @@ use nom::{
 };
 use nom_supreme::ParserExt;
 
-boilerplate!(Day);
+boilerplate!(Day, day25);
 
 impl BasicSolution for Day {
     type Parsed = i64;
     type Answer = String;
-    type TestAnswer = &'static str;
-    const SAMPLE_ANSWER_A: Self::TestAnswer = "2=-1=0";
-    const SAMPLE_ANSWER_B: Self::TestAnswer = "";
 
     fn parse(data: &str) -> IResult<'_, Self::Parsed> {
         fold_many1(snafu.terminated(line_ending), || 0, |acc, cur| acc + cur)(data)
     }
 
-    fn a(data: Self::Parsed) -> Self::Answer {
-        to_snafu(data)
+    fn a(data: Self::Parsed) -> OutResult<Self::Answer> {
+        Ok(to_snafu(data))
+    }
+
+    fn b(_data: Self::Parsed) -> OutResult<Self::Answer> {
+        Ok("".into())
     }
 
-    fn b(_data: Self::Parsed) -> Self::Answer {
-        "".into()
+    /// `size` numbers to add up.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Parsed>> {
+        let numbers = (0..size)
+            .map(|_| rng.i64(1..1_000_000_000))
+            .collect::<Vec<_>>();
+        let input = numbers.iter().map(|&n| to_snafu(n) + "\n").collect();
+        Some(Generated::round_trip(input, numbers.iter().sum()))
     }
 }
 
@@ -47,17 +56,10 @@ fn to_snafu(number: i64) -> String {
         if *number == 0 {
             return None;
         }
-        let digit_value = (((*number % 5) + 2) % 5) - 2;
+        let digit_value = (*number + 2).rem_euclid(5) - 2;
         *number -= digit_value;
         *number /= 5;
-        Some(match digit_value {
-            -2 => '=',
-            -1 => '-',
-            0 => '0',
-            1 => '1',
-            2 => '2',
-            _ => unreachable!(),
-        })
+        Some(char::from(b"=-012"[(digit_value + 2) as usize]))
     })
     .collect::<String>()
     .chars()
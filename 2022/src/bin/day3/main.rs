@@ -1,53 +1,89 @@
-#![feature(anonymous_lifetime_in_impl_trait)]
 use std::{collections::HashSet, str::Lines};
 
-use advent_2022::*;
+use advent_2022::{
+    generate::{Generated, Rng},
+    *,
+};
 use itertools::Itertools;
 
-boilerplate!(Day);
+boilerplate!(Day, day3);
 
 impl BasicSolution for Day {
     type Parsed = Lines<'static>;
     type Answer = u32;
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 157;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 70;
 
     fn parse(data: &'static str) -> IResult<'static, Self::Parsed> {
         Ok(("", data.lines()))
     }
 
-    fn a(data: Self::Parsed) -> Self::Answer {
-        data.map(|line| line.split_at(line.len() / 2))
-            .map(|(left, right)| intersecting_item([left, right]))
-            .map(priority)
-            .sum()
+    fn a(data: Self::Parsed) -> OutResult<Self::Answer> {
+        data.map(|line| {
+            let (left, right) = line.split_at(line.len() / 2);
+            priority(line, intersecting_item([left, right])?)
+        })
+        .sum()
     }
 
-    fn b(data: Self::Parsed) -> Self::Answer {
+    fn b(data: Self::Parsed) -> OutResult<Self::Answer> {
         data.chunks(3)
             .into_iter()
-            .map(intersecting_item)
-            .map(priority)
+            .map(|group| {
+                let group = group.collect_vec();
+                priority(group[0], intersecting_item(group.iter().copied())?)
+            })
             .sum()
     }
+
+    /// `size` groups of three rucksacks. Each group's lines only share its
+    /// badge, and each line's halves only share one item.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Parsed>> {
+        let mut items = (b'a'..=b'z').chain(b'A'..=b'Z').collect_vec();
+        let mut input = String::new();
+        for _ in 0..size {
+            rng.shuffle(&mut items);
+            let (&badge, others) = items.split_first()?;
+            for pool in others.chunks(17) {
+                let half = rng.usize(2..9);
+                let mut left = pool[..half].to_vec();
+                let mut right = [&pool[..1], &pool[half..2 * half - 1]].concat();
+                left[half - 1] = badge;
+                rng.shuffle(&mut left);
+                rng.shuffle(&mut right);
+                input.extend(left.into_iter().chain(right).map(char::from));
+                input.push('\n');
+            }
+        }
+        let data = input.clone().leak();
+        Some(Generated::round_trip(input, data.lines()))
+    }
 }
 
-fn priority(item: u8) -> u32 {
-    match item {
+/// The priority of `item`, which is in `line`.
+fn priority(line: &'static str, item: u8) -> OutResult<u32> {
+    let priority = match item {
         b'a'..=b'z' => item - b'a' + 1,
         b'A'..=b'Z' => item - b'A' + 27,
-        _ => panic!("illegal character {item}"),
-    }
-    .into()
+        _ => {
+            let offset = line.bytes().position(|c| c == item).unwrap_or(0);
+            let message = format!("illegal character {:?}", char::from(item));
+            return Err(ParseError::new(line, offset, message).into());
+        }
+    };
+    Ok(priority.into())
 }
 
-fn intersecting_item(group: impl IntoIterator<Item = &str>) -> u8 {
-    group
-        .into_iter()
-        .map(|items| items.bytes().collect::<HashSet<_>>())
-        .reduce(|a, b| a.intersection(&b).copied().collect())
-        .expect("there should be more than one set")
+fn intersecting_item<'a>(group: impl IntoIterator<Item = &'a str>) -> OutResult<u8> {
+    let mut sets = group
         .into_iter()
-        .exactly_one()
-        .expect("there should be exactly one item in the intersection")
+        .map(|items| (items, items.bytes().collect::<HashSet<_>>()));
+    let (first, first_set) = sets
+        .next()
+        .ok_or_else(|| SolveError::unsupported("an empty group"))?;
+    let intersection = sets.fold(first_set, |a, (_, b)| a.intersection(&b).copied().collect());
+    intersection.into_iter().exactly_one().map_err(|items| {
+        SolveError::unsupported(format!(
+            "the group starting with {first:?} has {} items in common instead of one",
+            items.count()
+        ))
+    })
 }
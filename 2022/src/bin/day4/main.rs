@@ -1,6 +1,9 @@
 use std::ops::RangeInclusive;
 
-use advent_2022::*;
+use advent_2022::{
+    generate::{Generated, Rng},
+    *,
+};
 use nom::{
     character::complete::{char, line_ending, u32},
     multi::separated_list1,
@@ -10,13 +13,11 @@ use nom_supreme::ParserExt;
 
 type RangesPair = [RangeInclusive<u32>; 2];
 
-boilerplate!(Day);
+boilerplate!(Day, day4);
 
 impl BasicSolution for Day {
     type Parsed = Vec<RangesPair>;
     type Answer = usize;
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 2;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 4;
 
     fn parse(data: &'static str) -> IResult<'static, Self::Parsed> {
         separated_list1(
@@ -27,14 +28,29 @@ impl BasicSolution for Day {
         )(data)
     }
 
-    fn a(data: Self::Parsed) -> Self::Answer {
-        data.into_iter()
+    fn a(data: Self::Parsed) -> OutResult<Self::Answer> {
+        Ok(data
+            .into_iter()
             .filter(one_range_contains_the_other)
-            .count()
+            .count())
+    }
+
+    fn b(data: Self::Parsed) -> OutResult<Self::Answer> {
+        Ok(data.into_iter().filter(ranges_overlap).count())
     }
 
-    fn b(data: Self::Parsed) -> Self::Answer {
-        data.into_iter().filter(ranges_overlap).count()
+    /// `size` pairs of sections.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Parsed>> {
+        let mut range = || {
+            let start = rng.u32(1..99);
+            start..=rng.u32(start..100)
+        };
+        let pairs: Vec<RangesPair> = (0..size).map(|_| [range(), range()]).collect();
+        let input = pairs
+            .iter()
+            .map(|[a, b]| format!("{}-{},{}-{}\n", a.start(), a.end(), b.start(), b.end()))
+            .collect();
+        Some(Generated::round_trip(input, pairs))
     }
 }
 
@@ -1,6 +1,10 @@
 use std::collections::VecDeque;
 
-use advent_2022::*;
+use advent_2022::{
+    generate::{Generated, Rng},
+    *,
+};
+use itertools::Itertools;
 use nom::{
     branch::alt,
     bytes::complete::tag,
@@ -14,28 +18,63 @@ use nom::{
 };
 use nom_supreme::{multi::parse_separated_terminated, ParserExt};
 
-boilerplate!(Day);
+boilerplate!(Day, day5);
 
 impl BasicSolution for Day {
     type Parsed = (Stacks, Vec<Instruction>);
     type Answer = String;
-    type TestAnswer = &'static str;
-    const SAMPLE_ANSWER_A: Self::TestAnswer = "CMZ";
-    const SAMPLE_ANSWER_B: Self::TestAnswer = "MCD";
 
     fn parse(data: &'static str) -> IResult<'static, Self::Parsed> {
         separated_pair(stacks, line_ending, instructions)(data)
     }
 
-    fn a((stacks, instructions): Self::Parsed) -> Self::Answer {
+    fn a((stacks, instructions): Self::Parsed) -> OutResult<Self::Answer> {
         solve::<true>(stacks, &instructions)
     }
 
-    fn b((stacks, instructions): Self::Parsed) -> Self::Answer {
+    fn b((stacks, instructions): Self::Parsed) -> OutResult<Self::Answer> {
         solve::<false>(stacks, &instructions)
     }
+
+    /// Up to nine stacks, and `size` moves that always have enough crates
+    /// to move.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Parsed>> {
+        let stacks: Stacks = (0..rng.usize(2..10))
+            .map(|_| (0..rng.usize(1..8)).map(|_| rng.uppercase()).collect())
+            .collect();
+        let mut heights = stacks.iter().map(VecDeque::len).collect_vec();
+        let mut instructions = Vec::new();
+        for _ in 0..size {
+            let from = rng.choice((0..heights.len()).filter(|&i| heights[i] > 0).collect_vec())?;
+            let to = rng.choice((0..heights.len()).filter(|&i| i != from).collect_vec())?;
+            let amount = rng.usize(1..=heights[from]);
+            heights[from] -= amount;
+            heights[to] += amount;
+            instructions.push(Instruction {
+                amount: amount.try_into().ok()?,
+                from: from as u8 + 1,
+                to: to as u8 + 1,
+            });
+        }
+        let height = stacks.iter().map(VecDeque::len).max()?;
+        let mut input = String::new();
+        for level in (0..height).rev() {
+            let row = stacks
+                .iter()
+                .map(|stack| stack.get(level).map_or("   ".into(), |c| format!("[{c}]")))
+                .join(" ");
+            input.push_str(&format!("{row}\n"));
+        }
+        let labels = (1..=stacks.len()).map(|i| format!(" {i} ")).join(" ");
+        input.push_str(&format!("{labels}\n\n"));
+        for Instruction { amount, from, to } in &instructions {
+            input.push_str(&format!("move {amount} from {from} to {to}\n"));
+        }
+        Some(Generated::round_trip(input, (stacks, instructions)))
+    }
 }
 
+/// The stacks are numbered from 1, as in the input
 #[derive(Debug, Clone, Copy)]
 struct Instruction {
     amount: u8,
@@ -45,17 +84,27 @@ struct Instruction {
 
 type Stacks = Vec<VecDeque<char>>;
 
-fn solve<const REVERSE_ORDER: bool>(mut stacks: Stacks, instructions: &[Instruction]) -> String {
+fn solve<const REVERSE_ORDER: bool>(
+    mut stacks: Stacks,
+    instructions: &[Instruction],
+) -> OutResult<String> {
     for &Instruction { amount, from, to } in instructions {
-        let (amount, from, to) = (amount as usize, from as usize, to as usize);
-        let [from, to] = stacks
-            .get_disjoint_mut([from, to])
-            .expect("stacks should exist");
-        let at = from.len() - amount;
+        let index = |stack: u8| usize::from(stack).checked_sub(1);
+        let [from_stack, to_stack] = index(from)
+            .zip(index(to))
+            .and_then(|(from, to)| stacks.get_disjoint_mut([from, to]).ok())
+            .ok_or_else(|| {
+                SolveError::unsupported(format!(
+                    "can't move crates from stack {from} to stack {to}"
+                ))
+            })?;
+        let at = from_stack.len().checked_sub(amount.into()).ok_or_else(|| {
+            SolveError::unsupported(format!("stack {from} has fewer than {amount} crates"))
+        })?;
         if REVERSE_ORDER {
-            to.extend(from.drain(at..).rev());
+            to_stack.extend(from_stack.drain(at..).rev());
         } else {
-            to.extend(from.drain(at..));
+            to_stack.extend(from_stack.drain(at..));
         }
     }
     get_top_crates(stacks)
@@ -97,7 +146,6 @@ fn stacks(input: &str) -> IResult<'_, Stacks> {
 fn instruction(input: &str) -> IResult<'_, Instruction> {
     let (input, (_, amount, _, from, _, to)) =
         tuple((tag("move "), u8, tag(" from "), u8, tag(" to "), u8))(input)?;
-    let (from, to) = (from - 1, to - 1);
     Ok((input, Instruction { amount, from, to }))
 }
 
@@ -105,13 +153,15 @@ fn instructions(input: &str) -> IResult<'_, Vec<Instruction>> {
     separated_list1(line_ending, instruction)(input)
 }
 
-fn get_top_crates(stacks: Stacks) -> String {
+fn get_top_crates(stacks: Stacks) -> OutResult<String> {
     stacks
-        .into_iter()
-        .map(|stack| {
-            *stack
+        .iter()
+        .enumerate()
+        .map(|(i, stack)| {
+            stack
                 .back()
-                .expect("each stack should have at least one crate")
+                .copied()
+                .ok_or_else(|| SolveError::no_answer(format!("stack {} ends up empty", i + 1)))
         })
         .collect()
 }
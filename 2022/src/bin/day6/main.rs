@@ -1,31 +1,45 @@
-use advent_2022::*;
+use advent_2022::{
+    generate::{Generated, Rng},
+    *,
+};
 use itertools::Itertools;
 
-boilerplate!(Day);
+boilerplate!(Day, day6);
 
 impl BasicSolution for Day {
     type Parsed = &'static str;
     type Answer = usize;
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 7;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 19;
 
     fn parse(data: &'static str) -> IResult<'static, Self::Parsed> {
         Ok(("", data))
     }
 
-    fn a(data: Self::Parsed) -> Self::Answer {
+    fn a(data: Self::Parsed) -> OutResult<Self::Answer> {
         solve::<4>(data)
     }
 
-    fn b(data: Self::Parsed) -> Self::Answer {
+    fn b(data: Self::Parsed) -> OutResult<Self::Answer> {
         solve::<14>(data)
     }
+
+    /// `size` dozen characters, from few enough letters that they often
+    /// repeat.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Parsed>> {
+        let letters = rng.u8(4..=26);
+        let input: String = (0..size * 12)
+            .map(|_| char::from(b'a' + rng.u8(0..letters)))
+            .collect();
+        let data = input.clone().leak();
+        Some(Generated::round_trip(input, &*data))
+    }
 }
 
-fn solve<const N: usize>(data: &str) -> usize {
+fn solve<const N: usize>(data: &str) -> OutResult<usize> {
     data.as_bytes()
         .windows(N)
         .position(|window| window.iter().all_unique())
-        .expect("There should be an all unique sequence")
-        + N
+        .map(|position| position + N)
+        .ok_or_else(|| {
+            SolveError::no_answer(format!("no {N} characters in a row are all different"))
+        })
 }
@@ -1,6 +1,9 @@
 use std::collections::HashMap;
 
-use advent_2022::*;
+use advent_2022::{
+    generate::{Generated, Rng},
+    *,
+};
 use nom::{
     branch::alt,
     bytes::complete::tag,
@@ -10,7 +13,7 @@ use nom::{
 };
 use nom_supreme::ParserExt;
 
-boilerplate!(Day);
+boilerplate!(Day, day7);
 
 const TOTAL_DISK_SPACE: u32 = 70000000;
 const NEEDED_DISK_SPACE: u32 = 30000000;
@@ -18,8 +21,6 @@ const NEEDED_DISK_SPACE: u32 = 30000000;
 impl BasicSolution for Day {
     type Parsed = HashMap<Vec<&'static str>, u32>;
     type Answer = u32;
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 95437;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 24933642;
 
     fn parse(data: &'static str) -> IResult<'static, Self::Parsed> {
         let mut current_dir = vec![];
@@ -33,18 +34,54 @@ impl BasicSolution for Day {
         )(data)
     }
 
-    fn a(data: Self::Parsed) -> Self::Answer {
-        data.into_values().filter(|&size| size < 100000).sum()
+    fn a(data: Self::Parsed) -> OutResult<Self::Answer> {
+        Ok(data.into_values().filter(|&size| size < 100000).sum())
     }
 
-    fn b(data: Self::Parsed) -> Self::Answer {
-        let root_directory_size = data[&vec![]];
+    fn b(data: Self::Parsed) -> OutResult<Self::Answer> {
+        let root_directory_size = *data
+            .get(&vec![])
+            .ok_or_else(|| SolveError::unsupported("the root was never listed"))?;
         let need_to_free =
             (root_directory_size + NEEDED_DISK_SPACE).saturating_sub(TOTAL_DISK_SPACE);
         data.into_values()
             .filter(|&size| size >= need_to_free)
             .min()
-            .expect("At least one directory should be larger than the missing space")
+            .ok_or_else(|| {
+                SolveError::no_answer("no directory is large enough to free the missing space")
+            })
+    }
+
+    /// A session that lists every directory of a tree of at most `size` of
+    /// them, depth first. Only checked to parse, since the directory sizes
+    /// are summed into a `HashMap`.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Parsed>> {
+        fn list(rng: &mut Rng, input: &mut String, dirs_left: &mut usize) {
+            input.push_str("$ ls\n");
+            let children = rng.usize(0..=(*dirs_left).min(3));
+            *dirs_left -= children;
+            let names = (0..children)
+                .map(|i| format!("{}{i}", rng.lowercase()))
+                .collect::<Vec<_>>();
+            for name in &names {
+                input.push_str(&format!("dir {name}\n"));
+            }
+            for i in 0..rng.usize(0..4) {
+                input.push_str(&format!(
+                    "{} {}{i}.txt\n",
+                    rng.u32(1..300_000),
+                    rng.lowercase()
+                ));
+            }
+            for name in &names {
+                input.push_str(&format!("$ cd {name}\n"));
+                list(rng, input, dirs_left);
+                input.push_str("$ cd ..\n");
+            }
+        }
+        let mut input = "$ cd /\n".to_owned();
+        list(rng, &mut input, &mut (size - 1));
+        Some(Generated::new(input))
     }
 }
 
@@ -1,19 +1,21 @@
-use advent_2022::*;
+use advent_2022::{
+    generate::{Generated, Rng},
+    *,
+};
 
-boilerplate!(Day);
+boilerplate!(Day, day8);
 
 impl BasicSolution for Day {
     type Parsed = Vec<&'static [u8]>;
     type Answer = usize;
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 21;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 8;
 
     fn parse(data: &'static str) -> IResult<'static, Self::Parsed> {
         Ok(("", data.lines().map(|line| line.as_bytes()).collect()))
     }
 
-    fn a(data: Self::Parsed) -> Self::Answer {
-        iter_tree_directions(&data)
+    fn a(data: Self::Parsed) -> OutResult<Self::Answer> {
+        check_rectangular(&data)?;
+        Ok(iter_tree_directions(&data)
             .map(|(tree, mut directions)| {
                 let tree_is_higher = |other_tree| tree > other_tree;
                 directions.0.all(tree_is_higher)
@@ -22,10 +24,11 @@ impl BasicSolution for Day {
                     || directions.3.all(tree_is_higher)
             })
             .filter(|&visible| visible)
-            .count()
+            .count())
     }
 
-    fn b(data: Self::Parsed) -> Self::Answer {
+    fn b(data: Self::Parsed) -> OutResult<Self::Answer> {
+        check_rectangular(&data)?;
         iter_tree_directions(&data)
             .map(|(tree, directions)| {
                 count_trees_visible(tree, directions.0)
@@ -34,8 +37,38 @@ impl BasicSolution for Day {
                     * count_trees_visible(tree, directions.3)
             })
             .max()
-            .expect("At least one tree")
+            .ok_or_else(|| SolveError::no_answer("no trees"))
+    }
+
+    /// A square forest, `size` times 5 trees across.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Parsed>> {
+        let side = size * 5;
+        let rows = (0..side)
+            .map(|_| {
+                let row = (0..side).map(|_| b'0' + rng.u8(0..10)).collect::<Vec<_>>();
+                &*row.leak()
+            })
+            .collect::<Vec<_>>();
+        let input = rows
+            .iter()
+            .map(|row| String::from_utf8_lossy(row) + "\n")
+            .collect();
+        Some(Generated::round_trip(input, rows))
+    }
+}
+
+fn check_rectangular(data: &[&[u8]]) -> OutResult<()> {
+    let width = data.first().map_or(0, |row| row.len());
+    for (i, row) in data.iter().enumerate() {
+        if row.len() != width {
+            return Err(SolveError::unsupported(format!(
+                "row {} has {} trees instead of {width}",
+                i + 1,
+                row.len()
+            )));
+        }
     }
+    Ok(())
 }
 
 fn iter_tree_directions<'a>(
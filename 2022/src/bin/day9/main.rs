@@ -1,4 +1,7 @@
-use advent_2022::*;
+use advent_2022::{
+    generate::{Generated, Rng},
+    *,
+};
 use itertools::{repeat_n, Itertools};
 use nom::{
     branch::alt,
@@ -8,24 +11,45 @@ use nom::{
 };
 use nom_supreme::ParserExt;
 
-boilerplate!(Day);
+boilerplate!(Day, day9);
 
 impl BasicSolution for Day {
     type Parsed = Vec<(Direction, u8)>;
     type Answer = usize;
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 13;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 1;
 
     fn parse(data: &'static str) -> IResult<'static, Self::Parsed> {
         separated_list1(line_ending, movement)(data)
     }
 
-    fn a(data: Self::Parsed) -> Self::Answer {
-        solve::<2>(&data)
+    fn a(data: Self::Parsed) -> OutResult<Self::Answer> {
+        Ok(solve::<2>(&data))
+    }
+
+    fn b(data: Self::Parsed) -> OutResult<Self::Answer> {
+        Ok(solve::<10>(&data))
     }
 
-    fn b(data: Self::Parsed) -> Self::Answer {
-        solve::<10>(&data)
+    /// `size` times 10 moves of the head.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Parsed>> {
+        let moves = (0..size * 10)
+            .map(|_| {
+                let direction = [Right, Up, Left, Down][rng.usize(0..4)];
+                (direction, rng.u8(1..20))
+            })
+            .collect_vec();
+        let input = moves
+            .iter()
+            .map(|(direction, amount)| {
+                let letter = match direction {
+                    Right => 'R',
+                    Up => 'U',
+                    Left => 'L',
+                    Down => 'D',
+                };
+                format!("{letter} {amount}\n")
+            })
+            .collect();
+        Some(Generated::round_trip(input, moves))
     }
 }
 
@@ -82,20 +106,3 @@ fn reposition_head(direction: Direction, head: &mut Point) {
         Down => head.1 -= 1,
     }
 }
-
-#[cfg(test)]
-#[test]
-fn extra_test_b() -> OutResult {
-    const DATA: &str = "R 5
-U 8
-L 8
-D 3
-R 17
-D 10
-L 25
-U 20
-";
-    let parsed = Day::final_parse(DATA)?;
-    assert_eq!(<Day as Solution>::b(parsed), 36);
-    Ok(())
-}
@@ -1,22 +1,20 @@
 use advent_2022::*;
 
-boilerplate!(Day);
+boilerplate!(Day, template);
 
 impl BasicSolution for Day {
     type Parsed = &'static str;
     type Answer = u32;
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 0;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 0;
 
     fn parse(data: &'static str) -> IResult<'static, Self::Parsed> {
         Ok(("", data))
     }
 
-    fn a(data: Self::Parsed) -> Self::Answer {
+    fn a(data: Self::Parsed) -> OutResult<Self::Answer> {
         todo!("{data}")
     }
 
-    fn b(_: Self::Parsed) -> Self::Answer {
-        0
+    fn b(_: Self::Parsed) -> OutResult<Self::Answer> {
+        Ok(0)
     }
 }
@@ -0,0 +1,86 @@
+//! Every day's binary compiled into the library, so the workspace runner can
+//! call them in-process.
+//!
+//! Each binary's `main` and crate-level attributes only apply when it's built
+//! on its own, and lint levels follow the binary's rather than the library's.
+#![allow(dead_code, unused_attributes, clippy::pedantic)]
+use crate::Registered;
+
+#[path = "bin/day1/main.rs"]
+mod day1;
+#[path = "bin/day10/main.rs"]
+mod day10;
+#[path = "bin/day11/main.rs"]
+mod day11;
+#[path = "bin/day12/main.rs"]
+mod day12;
+#[path = "bin/day13/main.rs"]
+mod day13;
+#[path = "bin/day14/main.rs"]
+mod day14;
+#[path = "bin/day15/main.rs"]
+mod day15;
+#[path = "bin/day16/main.rs"]
+mod day16;
+#[path = "bin/day17/main.rs"]
+mod day17;
+#[path = "bin/day18/main.rs"]
+mod day18;
+#[path = "bin/day19/main.rs"]
+mod day19;
+#[path = "bin/day2/main.rs"]
+mod day2;
+#[path = "bin/day20/main.rs"]
+mod day20;
+#[path = "bin/day21/main.rs"]
+mod day21;
+#[path = "bin/day22/main.rs"]
+mod day22;
+#[path = "bin/day23/main.rs"]
+mod day23;
+#[path = "bin/day24/main.rs"]
+mod day24;
+#[path = "bin/day25/main.rs"]
+mod day25;
+#[path = "bin/day3/main.rs"]
+mod day3;
+#[path = "bin/day4/main.rs"]
+mod day4;
+#[path = "bin/day5/main.rs"]
+mod day5;
+#[path = "bin/day6/main.rs"]
+mod day6;
+#[path = "bin/day7/main.rs"]
+mod day7;
+#[path = "bin/day8/main.rs"]
+mod day8;
+#[path = "bin/day9/main.rs"]
+mod day9;
+
+pub const DAYS: &[(u8, Registered)] = &[
+    (1, day1::REGISTERED),
+    (2, day2::REGISTERED),
+    (3, day3::REGISTERED),
+    (4, day4::REGISTERED),
+    (5, day5::REGISTERED),
+    (6, day6::REGISTERED),
+    (7, day7::REGISTERED),
+    (8, day8::REGISTERED),
+    (9, day9::REGISTERED),
+    (10, day10::REGISTERED),
+    (11, day11::REGISTERED),
+    (12, day12::REGISTERED),
+    (13, day13::REGISTERED),
+    (14, day14::REGISTERED),
+    (15, day15::REGISTERED),
+    (16, day16::REGISTERED),
+    (17, day17::REGISTERED),
+    (18, day18::REGISTERED),
+    (19, day19::REGISTERED),
+    (20, day20::REGISTERED),
+    (21, day21::REGISTERED),
+    (22, day22::REGISTERED),
+    (23, day23::REGISTERED),
+    (24, day24::REGISTERED),
+    (25, day25::REGISTERED),
+];
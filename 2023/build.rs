@@ -0,0 +1,4 @@
+//! See [`aoc_core::build`].
+fn main() {
+    aoc_core::build::main();
+}
@@ -1,22 +1,22 @@
 #![warn(clippy::pedantic)]
 use std::borrow::Cow;
 
-use advent_2023::{BasicSolution, Solution};
+use advent_2023::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, Solution,
+};
 use anyhow::anyhow;
 
 struct Day;
 
 impl BasicSolution for Day {
-    const INPUT: &'static str = include_str!("data.txt");
-    const SAMPLE_INPUT: &'static str = include_str!("sample_a.txt");
-    const SAMPLE_INPUT_B: &'static str = include_str!("sample_b.txt");
+    const INPUT: Option<&'static str> = embedded_input!(day1);
+    const ANSWERS: Answers = embedded_answers!(day1);
 
     type Shared = &'static str;
     type Answer = u32;
 
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 142;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 281;
-
     fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
         Ok(input)
     }
@@ -41,6 +41,29 @@ impl BasicSolution for Day {
             ],
         )
     }
+
+    /// `size` times 10 lines of letters, spelled out digits and at least
+    /// one literal digit.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        const SPELLED: [&str; 9] = [
+            "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+        ];
+        let mut input = String::new();
+        for _ in 0..size * 10 {
+            let digit_at = rng.usize(0..8);
+            for i in 0..8 {
+                match rng.u8(0..4) {
+                    _ if i == digit_at => input.push(rng.char('1'..='9')),
+                    0 => input.push_str(SPELLED[rng.usize(0..9)]),
+                    1 => input.push(rng.char('1'..='9')),
+                    _ => input.push(rng.lowercase()),
+                }
+            }
+            input.push('\n');
+        }
+        let shared = input.clone().leak();
+        Some(Generated::round_trip(input, shared))
+    }
 }
 
 fn solve(document: &str, spelled_out_vals: &[(&str, u32)]) -> anyhow::Result<u32> {
@@ -65,6 +88,8 @@ fn main() -> anyhow::Result<()> {
     Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -78,4 +103,11 @@ mod tests {
     fn b() -> anyhow::Result<()> {
         Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2023::sample_tests!(Day, day1);
 }
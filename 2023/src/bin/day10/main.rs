@@ -1,8 +1,13 @@
 #![warn(clippy::pedantic)]
-use std::borrow::Cow;
-
-use advent_2023::{BasicSolution, Solution};
-use anyhow::anyhow;
+use std::{borrow::Cow, cmp::Ordering};
+
+use advent_2023::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    grid::Grid,
+    register, Answers, BasicSolution, Solution,
+};
+use anyhow::{anyhow, Context};
 use itertools::Itertools;
 
 struct Day;
@@ -13,19 +18,15 @@ const WEST: (isize, isize) = (0, -1);
 const EAST: (isize, isize) = (0, 1);
 
 impl BasicSolution for Day {
-    const INPUT: &'static str = include_str!("data.txt");
-    const SAMPLE_INPUT: &'static str = include_str!("sample.txt");
-    const SAMPLE_INPUT_B: &'static str = include_str!("sample_b.txt");
+    const INPUT: Option<&'static str> = embedded_input!(day10);
+    const ANSWERS: Answers = embedded_answers!(day10);
 
     type Shared = Vec<(usize, usize)>;
     type Answer = usize;
 
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 8;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 10;
-
     fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
-        let field = input.lines().map(str::as_bytes).collect_vec();
-        let start_coords = find_start_coords(&field)?;
+        let field = Grid::parse(input)?;
+        let start_coords = field.find(&b'S').context("No start found")?;
         find_loop(&field, start_coords)
     }
 
@@ -38,6 +39,95 @@ impl BasicSolution for Day {
         // Pick's theorem: A = i + b/2 - 1
         Ok(area + 1 - loop_coords.len() / 2)
     }
+
+    /// A loop around a random shape up to `size` times 4 tiles across, made
+    /// of columns that overlap their neighbours so it never touches itself,
+    /// among random pipes that don't connect to S.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let (rows, columns) = (size * 2, rng.usize(1..=size * 2));
+        let mut extents: Vec<(usize, usize)> = Vec::new();
+        for _ in 0..columns {
+            let top = match extents.last() {
+                Some(&(_, prev_bottom)) => rng.usize(0..prev_bottom),
+                None => rng.usize(0..rows),
+            };
+            let prev_top = extents.last().map_or(0, |&(prev_top, _)| prev_top);
+            extents.push((top, rng.usize(top.max(prev_top) + 1..=rows)));
+        }
+        let corners = extents
+            .iter()
+            .enumerate()
+            .flat_map(|(column, &(top, _))| [(top, column), (top, column + 1)])
+            .chain(
+                extents
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .flat_map(|(column, &(_, bottom))| [(bottom, column + 1), (bottom, column)]),
+            )
+            .collect_vec();
+        let (margin_rows, margin_columns) = (rng.usize(0..3), rng.usize(0..3));
+        let mut loop_coords = Vec::new();
+        for (&from, &to) in corners.iter().circular_tuple_windows() {
+            let [from, to] = [from, to]
+                .map(|(row, column)| (row * 2 + margin_rows, column * 2 + margin_columns));
+            let step = |from: usize, to: usize| match from.cmp(&to) {
+                Ordering::Less => from + 1,
+                Ordering::Equal => from,
+                Ordering::Greater => from - 1,
+            };
+            let mut coords = from;
+            while coords != to {
+                loop_coords.push(coords);
+                coords = (step(coords.0, to.0), step(coords.1, to.1));
+            }
+        }
+        let height = rows * 2 + 1 + margin_rows + rng.usize(0..3);
+        let width = columns * 2 + 1 + margin_columns + rng.usize(0..3);
+        let tiles = (0..width * height)
+            .map(|_| b"|-LJ7F."[rng.usize(0..7)])
+            .collect();
+        let mut field = Grid::from_vec(width, tiles)?;
+        for (&prev, &coords, &next) in loop_coords.iter().circular_tuple_windows() {
+            let direction = |(row, column): (usize, usize)| {
+                (row.cmp(&coords.0) as isize, column.cmp(&coords.1) as isize)
+            };
+            field[coords] = [
+                (b'|', [NORTH, SOUTH]),
+                (b'-', [WEST, EAST]),
+                (b'L', [NORTH, EAST]),
+                (b'J', [NORTH, WEST]),
+                (b'7', [WEST, SOUTH]),
+                (b'F', [EAST, SOUTH]),
+            ]
+            .into_iter()
+            .find(|(_, diffs)| {
+                diffs.contains(&direction(prev)) && diffs.contains(&direction(next))
+            })?
+            .0;
+        }
+        let start_idx = rng.usize(0..loop_coords.len());
+        let start = loop_coords[start_idx];
+        let neighbours = field
+            .neighbors(start, &[NORTH, SOUTH, WEST, EAST])
+            .filter(|neighbour| !loop_coords.contains(neighbour))
+            .collect_vec();
+        for neighbour in neighbours {
+            field[neighbour] = b'.';
+        }
+        field[start] = b'S';
+        // Parsing walks the loop from S, towards whichever of its two
+        // neighbours on the loop it finds first
+        loop_coords.rotate_left(start_idx);
+        let first = [NORTH, SOUTH, WEST, EAST]
+            .into_iter()
+            .filter_map(|diff| field.offset(start, diff))
+            .find(|&coords| coords == loop_coords[1] || Some(&coords) == loop_coords.last())?;
+        if first != loop_coords[1] {
+            loop_coords[1..].reverse();
+        }
+        Some(Generated::round_trip(field.to_string(), loop_coords))
+    }
 }
 
 fn shoelace_formula(loop_coords: &[(usize, usize)]) -> Result<usize, anyhow::Error> {
@@ -53,7 +143,7 @@ fn shoelace_formula(loop_coords: &[(usize, usize)]) -> Result<usize, anyhow::Err
 }
 
 fn find_loop(
-    field: &[&[u8]],
+    field: &Grid<u8>,
     start_coords: (usize, usize),
 ) -> Result<Vec<(usize, usize)>, anyhow::Error> {
     let err = |coords| anyhow!("No connected segments found at {coords:?}");
@@ -61,7 +151,7 @@ fn find_loop(
     let mut prev = start_coords;
     let mut coords = [NORTH, SOUTH, WEST, EAST]
         .into_iter()
-        .filter_map(|diff| check_add_signed_2d(start_coords, diff))
+        .filter_map(|diff| field.offset(start_coords, diff))
         .find(|&coords| connected_segments(field, coords).contains(&start_coords))
         .ok_or_else(|| err(start_coords))?;
 
@@ -77,29 +167,16 @@ fn find_loop(
     Ok(loop_coords)
 }
 
-fn find_start_coords(field: &[&[u8]]) -> anyhow::Result<(usize, usize)> {
-    field
-        .iter()
-        .enumerate()
-        .flat_map(|(row, line)| {
-            line.iter()
-                .enumerate()
-                .map(move |(col, segment)| ((row, col), segment))
-        })
-        .find(|(_, &segment)| segment == b'S')
-        .map(|(coords, _)| coords)
-        .ok_or_else(|| anyhow!("No start found"))
-}
-
 fn connected_segments(
-    field: &[&[u8]],
+    field: &Grid<u8>,
     from: (usize, usize),
-) -> impl Iterator<Item = (usize, usize)> {
-    get_2d(field, from)
-        .and_then(adjacent_diffs)
+) -> impl Iterator<Item = (usize, usize)> + '_ {
+    field
+        .get(from)
+        .and_then(|&segment| adjacent_diffs(segment))
         .into_iter()
         .flatten()
-        .filter_map(move |diff| check_add_signed_2d(from, diff))
+        .filter_map(move |diff| field.offset(from, diff))
 }
 
 fn adjacent_diffs(pipe_segment: u8) -> Option<[(isize, isize); 2]> {
@@ -114,23 +191,12 @@ fn adjacent_diffs(pipe_segment: u8) -> Option<[(isize, isize); 2]> {
     })
 }
 
-#[allow(clippy::similar_names)]
-fn check_add_signed_2d(
-    (row, col): (usize, usize),
-    (drow, dcol): (isize, isize),
-) -> Option<(usize, usize)> {
-    row.checked_add_signed(drow)
-        .zip(col.checked_add_signed(dcol))
-}
-
-fn get_2d(field: &[&[u8]], (row, col): (usize, usize)) -> Option<u8> {
-    field.get(row)?.get(col).copied()
-}
-
 fn main() -> anyhow::Result<()> {
     Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -144,4 +210,11 @@ mod tests {
     fn b() -> anyhow::Result<()> {
         Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2023::sample_tests!(Day, day10);
 }
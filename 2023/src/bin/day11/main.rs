@@ -1,6 +1,10 @@
 use std::borrow::Cow;
 
-use advent_2023::{BasicSolution, Solution};
+use advent_2023::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, ParserExt, Solution,
+};
 use winnow::{
     combinator::{alt, repeat},
     Parser,
@@ -15,19 +19,16 @@ enum Pixel {
 }
 
 impl BasicSolution for Day {
-    const INPUT: &'static str = include_str!("data.txt");
-    const SAMPLE_INPUT: &'static str = include_str!("sample.txt");
+    const INPUT: Option<&'static str> = embedded_input!(day11);
+    const ANSWERS: Answers = embedded_answers!(day11);
 
     type Shared = Vec<Vec<Pixel>>;
     type Answer = usize;
 
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 374;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 82000210;
-
     fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
         input
             .lines()
-            .map(|line| repeat(1.., pixel).parse(line).map_err(anyhow::Error::msg))
+            .map(|line| repeat(1.., pixel).parse_all(line))
             .collect()
     }
 
@@ -38,6 +39,33 @@ impl BasicSolution for Day {
     fn part_b(image: Self::Shared) -> anyhow::Result<Self::Answer> {
         Ok(solve::<1_000_000>(&image))
     }
+
+    /// A square image `size` times 10 pixels across, with a galaxy in about
+    /// one of every 10 pixels.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let side = size * 10;
+        let image: Vec<Vec<Pixel>> = (0..side)
+            .map(|_| {
+                (0..side)
+                    .map(|_| match rng.u8(0..10) {
+                        0 => Pixel::Galaxy,
+                        _ => Pixel::Space,
+                    })
+                    .collect()
+            })
+            .collect();
+        let input = image
+            .iter()
+            .map(|row| {
+                let row = row.iter().map(|pixel| match pixel {
+                    Pixel::Galaxy => '#',
+                    Pixel::Space => '.',
+                });
+                row.collect::<String>() + "\n"
+            })
+            .collect();
+        Some(Generated::round_trip(input, image))
+    }
 }
 
 fn solve<const EXPANSION: usize>(image: &[Vec<Pixel>]) -> usize {
@@ -85,6 +113,8 @@ fn main() -> anyhow::Result<()> {
     Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -98,4 +128,11 @@ mod tests {
     fn b() -> anyhow::Result<()> {
         Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2023::sample_tests!(Day, day11);
 }
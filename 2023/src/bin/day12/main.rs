@@ -1,6 +1,12 @@
 use std::borrow::Cow;
 
-use advent_2023::{BasicSolution, Solution};
+use advent_2023::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, ParserExt, Solution,
+};
+use anyhow::Context;
+use itertools::Itertools;
 use winnow::{
     ascii::dec_uint,
     combinator::{alt, repeat, separated},
@@ -25,57 +31,107 @@ struct ConditionRecord {
 }
 
 impl BasicSolution for Day {
-    const INPUT: &'static str = include_str!("data.txt");
-    const SAMPLE_INPUT: &'static str = include_str!("sample.txt");
+    const INPUT: Option<&'static str> = embedded_input!(day12);
+    const ANSWERS: Answers = embedded_answers!(day12);
 
     type Shared = Vec<ConditionRecord>;
     type Answer = u64;
 
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 21;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 525152;
-
     fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
         input
             .lines()
-            .map(|line| condition_record.parse(line).map_err(anyhow::Error::msg))
+            .map(|line| condition_record.parse_all(line))
             .collect()
     }
 
     fn part_a(condition_records: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
-        Ok(condition_records
-            .iter()
-            .cloned()
-            .map(|record| record.count_possible_arangements())
-            .sum())
+        total(
+            condition_records
+                .iter()
+                .cloned()
+                .map(ConditionRecord::count_possible_arangements),
+        )
     }
 
     fn part_b(condition_records: Self::Shared) -> anyhow::Result<Self::Answer> {
-        Ok(condition_records
-            .into_iter()
-            .map(|mut record| {
-                record.spring_conditions = record
-                    .spring_conditions
+        total(condition_records.into_iter().map(|mut record| {
+            record.spring_conditions = record
+                .spring_conditions
+                .iter()
+                .copied()
+                .chain([Unknown])
+                .cycle()
+                .take(record.spring_conditions.len() * 5 + 4)
+                .collect();
+            record.damaged_group_sizes = record
+                .damaged_group_sizes
+                .iter()
+                .copied()
+                .cycle()
+                .take(record.damaged_group_sizes.len() * 5)
+                .collect();
+            record.count_possible_arangements()
+        }))
+    }
+
+    /// 10 records of up to `size` times 5 springs, made by hiding some of
+    /// the conditions of a real arrangement so there's at least one.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let records = (0..10)
+            .map(|_| {
+                let len = rng.usize(1..=size * 5);
+                let mut arrangement = (0..len)
+                    .map(|_| if rng.bool() { Damaged } else { Operational })
+                    .collect_vec();
+                arrangement[rng.usize(0..len)] = Damaged;
+                let damaged_group_sizes = arrangement
                     .iter()
-                    .copied()
-                    .chain([Unknown])
-                    .cycle()
-                    .take(record.spring_conditions.len() * 5 + 4)
+                    .chunk_by(|&&condition| condition)
+                    .into_iter()
+                    .filter(|(condition, _)| *condition == Damaged)
+                    .map(|(_, group)| group.count())
                     .collect();
-                record.damaged_group_sizes = record
-                    .damaged_group_sizes
-                    .iter()
-                    .copied()
-                    .cycle()
-                    .take(record.damaged_group_sizes.len() * 5)
+                let unknown = rng.u8(0..40);
+                let spring_conditions = arrangement
+                    .into_iter()
+                    .map(|condition| {
+                        if rng.u8(0..100) < unknown {
+                            Unknown
+                        } else {
+                            condition
+                        }
+                    })
                     .collect();
-                record.count_possible_arangements()
+                ConditionRecord {
+                    spring_conditions,
+                    damaged_group_sizes,
+                }
             })
-            .sum())
+            .collect_vec();
+        let input = records
+            .iter()
+            .map(|record| {
+                let springs = record
+                    .spring_conditions
+                    .iter()
+                    .map(|condition| match condition {
+                        Operational => '.',
+                        Damaged => '#',
+                        Unknown => '?',
+                    })
+                    .collect::<String>();
+                format!(
+                    "{springs} {}\n",
+                    record.damaged_group_sizes.iter().join(",")
+                )
+            })
+            .collect();
+        Some(Generated::round_trip(input, records))
     }
 }
 
 impl ConditionRecord {
-    fn count_possible_arangements(mut self) -> u64 {
+    fn count_possible_arangements(mut self) -> anyhow::Result<u64> {
         // to make the Damaged recursion case simpler
         self.spring_conditions.push(Operational);
         let mut cache =
@@ -85,16 +141,26 @@ impl ConditionRecord {
             &self.damaged_group_sizes,
             &mut cache,
         )
+        .context("too many arrangements to count")
     }
 }
 
+fn total(mut arangements: impl Iterator<Item = anyhow::Result<u64>>) -> anyhow::Result<u64> {
+    arangements.try_fold(0u64, |total, arangements| {
+        total
+            .checked_add(arangements?)
+            .context("too many arrangements to count")
+    })
+}
+
+/// `None` when there are more than fit in a `u64`.
 fn count_possible_arangements_inner(
     spring_conditions: &[SpringCondition],
     damaged_group_sizes: &[usize],
     cache: &mut [Vec<Option<u64>>],
-) -> u64 {
+) -> Option<u64> {
     if let Some(cached) = cache[damaged_group_sizes.len()][spring_conditions.len()] {
-        return cached;
+        return Some(cached);
     }
     let mut arangements = None;
     if damaged_group_sizes.is_empty() {
@@ -114,27 +180,27 @@ fn count_possible_arangements_inner(
     }
     if let Some(arangements) = arangements {
         cache[damaged_group_sizes.len()][spring_conditions.len()] = Some(arangements);
-        return arangements;
+        return Some(arangements);
     }
     let mut arangements = 0;
     if spring_conditions[0] != Damaged {
         // Assume operational
-        arangements +=
-            count_possible_arangements_inner(&spring_conditions[1..], damaged_group_sizes, cache);
+        arangements =
+            count_possible_arangements_inner(&spring_conditions[1..], damaged_group_sizes, cache)?;
     }
     let next_group_size = damaged_group_sizes[0];
     if !spring_conditions[..next_group_size].contains(&Operational)
         && spring_conditions[next_group_size] != Damaged
     {
         // Assume damaged
-        arangements += count_possible_arangements_inner(
+        arangements = arangements.checked_add(count_possible_arangements_inner(
             &spring_conditions[next_group_size + 1..],
             &damaged_group_sizes[1..],
             cache,
-        );
+        )?)?;
     }
     cache[damaged_group_sizes.len()][spring_conditions.len()] = Some(arangements);
-    arangements
+    Some(arangements)
 }
 
 fn condition_record(input: &mut &'static str) -> winnow::Result<ConditionRecord> {
@@ -157,6 +223,8 @@ fn main() -> anyhow::Result<()> {
     Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -170,4 +238,11 @@ mod tests {
     fn b() -> anyhow::Result<()> {
         Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2023::sample_tests!(Day, day12);
 }
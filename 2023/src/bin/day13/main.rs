@@ -1,20 +1,21 @@
 use std::borrow::Cow;
 
-use advent_2023::{BasicSolution, Solution};
+use advent_2023::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, Solution,
+};
 use anyhow::anyhow;
 
 struct Day;
 
 impl BasicSolution for Day {
-    const INPUT: &'static str = include_str!("data.txt");
-    const SAMPLE_INPUT: &'static str = include_str!("sample.txt");
+    const INPUT: Option<&'static str> = embedded_input!(day13);
+    const ANSWERS: Answers = embedded_answers!(day13);
 
     type Shared = Vec<Vec<&'static [u8]>>;
     type Answer = usize;
 
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 405;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 400;
-
     fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
         Ok(input
             .split("\n\n")
@@ -29,6 +30,47 @@ impl BasicSolution for Day {
     fn part_b(notes: Self::Shared) -> anyhow::Result<Self::Answer> {
         notes.iter().map(|note| score(note, 1)).sum()
     }
+
+    /// `size` times 3 notes, each with a perfect reflection. They don't have
+    /// smudges, so part b usually finds no mirror.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let notes = (0..size * 3)
+            .map(|_| {
+                let (len, across) = (rng.usize(5..=17), rng.usize(5..=17));
+                let mirror = rng.usize(1..len);
+                let mut lines: Vec<Vec<u8>> = Vec::new();
+                for i in 0..len {
+                    let line = match (2 * mirror).checked_sub(i + 1) {
+                        Some(reflected) if i >= mirror => lines[reflected].clone(),
+                        _ => (0..across)
+                            .map(|_| if rng.bool() { b'#' } else { b'.' })
+                            .collect(),
+                    };
+                    lines.push(line);
+                }
+                if rng.bool() {
+                    // Reflect the columns instead
+                    lines = (0..across)
+                        .map(|column| lines.iter().map(|line| line[column]).collect())
+                        .collect();
+                }
+                lines
+                    .into_iter()
+                    .map(|line| &*line.leak())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let input = notes
+            .iter()
+            .map(|note| {
+                note.iter()
+                    .flat_map(|line| line.iter().map(|&b| char::from(b)).chain(['\n']))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        Some(Generated::round_trip(input, notes))
+    }
 }
 
 fn score(note: &[&[u8]], allowed_mismatches: usize) -> anyhow::Result<usize> {
@@ -66,6 +108,8 @@ fn main() -> anyhow::Result<()> {
     Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -79,4 +123,11 @@ mod tests {
     fn b() -> anyhow::Result<()> {
         Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2023::sample_tests!(Day, day13);
 }
@@ -1,6 +1,10 @@
 use std::{array, borrow::Cow};
 
-use advent_2023::{BasicSolution, Solution};
+use advent_2023::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, ParserExt, Solution,
+};
 use winnow::{
     ascii::{alpha1, dec_uint},
     combinator::{alt, preceded},
@@ -10,15 +14,12 @@ use winnow::{
 struct Day;
 
 impl BasicSolution for Day {
-    const INPUT: &'static str = include_str!("data.txt");
-    const SAMPLE_INPUT: &'static str = include_str!("sample.txt");
+    const INPUT: Option<&'static str> = embedded_input!(day15);
+    const ANSWERS: Answers = embedded_answers!(day15);
 
     type Shared = Vec<&'static str>;
     type Answer = usize;
 
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 1320;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 145;
-
     fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
         Ok(input.trim_end().split(',').collect())
     }
@@ -30,9 +31,7 @@ impl BasicSolution for Day {
     fn part_b(steps: Self::Shared) -> anyhow::Result<Self::Answer> {
         let mut hash_map: [Vec<(&str, u8)>; 256] = array::from_fn(|_| Vec::new());
         for step in steps {
-            let (label, operation) = (alpha1, operation)
-                .parse(step)
-                .map_err(anyhow::Error::msg)?;
+            let (label, operation) = (alpha1, operation).parse_all(step)?;
             let bucket = hash(label);
             match operation {
                 Operation::Remove => {
@@ -62,6 +61,28 @@ impl BasicSolution for Day {
             })
             .sum())
     }
+
+    /// `size` times 20 steps on `size` times 3 labels.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let labels = (0..size * 3)
+            .map(|_| {
+                (0..rng.usize(2..=6))
+                    .map(|_| rng.lowercase())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+        let steps = (0..size * 20)
+            .map(|_| {
+                let label = &labels[rng.usize(0..labels.len())];
+                let step = match rng.u8(0..3) {
+                    0 => format!("{label}-"),
+                    _ => format!("{label}={}", rng.u8(1..=9)),
+                };
+                &*step.leak()
+            })
+            .collect::<Vec<_>>();
+        Some(Generated::round_trip(steps.join(",") + "\n", steps))
+    }
 }
 
 fn hash(input: &str) -> usize {
@@ -88,6 +109,8 @@ fn main() -> anyhow::Result<()> {
     Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -101,4 +124,11 @@ mod tests {
     fn b() -> anyhow::Result<()> {
         Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2023::sample_tests!(Day, day15);
 }
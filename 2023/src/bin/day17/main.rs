@@ -1,8 +1,11 @@
-use std::{borrow::Cow, cmp::Reverse, collections::BinaryHeap};
+use std::borrow::Cow;
 
-use advent_2023::{BasicSolution, Solution};
-use anyhow::anyhow;
-use fxhash::FxHashSet;
+use advent_2023::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, search, Answers, BasicSolution, Solution,
+};
+use anyhow::{ensure, Context};
 
 struct Day;
 
@@ -15,16 +18,12 @@ struct State {
 }
 
 impl BasicSolution for Day {
-    const INPUT: &'static str = include_str!("data.txt");
-    const SAMPLE_INPUT: &'static str = include_str!("sample_a.txt");
-    const SAMPLE_INPUT_B: &'static str = include_str!("sample_b.txt");
+    const INPUT: Option<&'static str> = embedded_input!(day17);
+    const ANSWERS: Answers = embedded_answers!(day17);
 
     type Shared = Vec<&'static [u8]>;
     type Answer = u16;
 
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 102;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 71;
-
     fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
         Ok(input.lines().map(|line| line.as_bytes()).collect())
     }
@@ -64,6 +63,42 @@ impl BasicSolution for Day {
             },
         )
     }
+
+    fn validate(map: &Self::Shared) -> anyhow::Result<()> {
+        let width = map.first().map_or(0, |row| row.len());
+        ensure!(width > 0, "the map is empty");
+        ensure!(
+            map.iter().all(|row| row.len() == width),
+            "the map isn't rectangular"
+        );
+        ensure!(
+            map.iter()
+                .flat_map(|row| row.iter())
+                .all(u8::is_ascii_digit),
+            "the map should only have digits"
+        );
+        let max_side = usize::from(u8::MAX) + 1;
+        ensure!(
+            map.len() <= max_side && width <= max_side,
+            "the map is {}x{width}, but coordinates are stored as u8s",
+            map.len()
+        );
+        Ok(())
+    }
+
+    /// A square map `size` times 5 blocks across.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let side = size * 5;
+        let mut input = String::new();
+        let mut rows = Vec::new();
+        for _ in 0..side {
+            let row = (0..side).map(|_| rng.char('1'..='9')).collect::<String>();
+            input.push_str(&row);
+            input.push('\n');
+            rows.push(row.leak().as_bytes());
+        }
+        Some(Generated::round_trip(input, rows))
+    }
 }
 
 fn djkstra(
@@ -72,56 +107,42 @@ fn djkstra(
     invalid_move: impl Fn(&State, (i8, i8)) -> bool,
     target: impl Fn(&State) -> bool,
 ) -> Result<u16, anyhow::Error> {
-    let mut queue = BinaryHeap::from([(Reverse(0), initial_state)]);
-    let mut seen = FxHashSet::default();
-    while let Some((Reverse(total_heat_loss), state)) = queue.pop() {
-        if target(&state) {
-            return Ok(total_heat_loss);
-        }
-        if !seen.insert(state.clone()) {
-            continue;
-        }
-        for (drow, dcol) in [(0, 1), (1, 0), (0, -1), (-1, 0)] {
-            if state.current_direction == (-drow, -dcol) {
-                continue;
-            }
-            if invalid_move(&state, (drow, dcol)) {
-                continue;
-            }
-            let Some(row) = state.row.checked_add_signed(drow) else {
-                continue;
-            };
-            let Some(col) = state.col.checked_add_signed(dcol) else {
-                continue;
-            };
-            let Some(heat_loss) = map.get(row as usize).and_then(|row| row.get(col as usize))
-            else {
-                continue;
-            };
-            let heat_loss = heat_loss - b'0';
-            let has_moved = if state.current_direction == (drow, dcol) {
-                state.has_moved + 1
-            } else {
-                1
-            };
-            queue.push((
-                Reverse(total_heat_loss + heat_loss as u16),
-                State {
+    let invalid_move = &invalid_move;
+    let successors = |state: &State| {
+        let state = state.clone();
+        [(0, 1), (1, 0), (0, -1), (-1, 0)]
+            .into_iter()
+            .filter_map(move |(drow, dcol)| {
+                if state.current_direction == (-drow, -dcol) || invalid_move(&state, (drow, dcol)) {
+                    return None;
+                }
+                let row = state.row.checked_add_signed(drow)?;
+                let col = state.col.checked_add_signed(dcol)?;
+                let heat_loss = map.get(row as usize)?.get(col as usize)? - b'0';
+                let has_moved = if state.current_direction == (drow, dcol) {
+                    state.has_moved + 1
+                } else {
+                    1
+                };
+                let next = State {
                     row,
                     col,
                     current_direction: (drow, dcol),
                     has_moved,
-                },
-            ))
-        }
-    }
-    Err(anyhow!("No path found"))
+                };
+                Some((next, heat_loss as u16))
+            })
+    };
+    let path = search::dijkstra(initial_state, successors, target).context("No path found")?;
+    Ok(path.cost)
 }
 
 fn main() -> anyhow::Result<()> {
     Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -135,4 +156,20 @@ mod tests {
     fn b() -> anyhow::Result<()> {
         Day::test_part_b()
     }
+
+    #[test]
+    fn validate() -> anyhow::Result<()> {
+        (REGISTERED.validate)(Some(include_str!("sample_a.txt")))?;
+        let wide = format!("{0}\n{0}\n", "1".repeat(257)).leak();
+        let error = (REGISTERED.validate)(Some(wide)).unwrap_err();
+        assert!(error.to_string().contains("u8"), "{error}");
+        Ok(())
+    }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2023::sample_tests!(Day, day17);
 }
@@ -1,6 +1,10 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, cmp::Ordering};
 
-use advent_2023::{BasicSolution, Solution};
+use advent_2023::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, ParserExt, Solution,
+};
 use anyhow::bail;
 use itertools::Itertools;
 use winnow::{
@@ -27,20 +31,14 @@ struct Edge {
 }
 
 impl BasicSolution for Day {
-    const INPUT: &'static str = include_str!("data.txt");
-    const SAMPLE_INPUT: &'static str = include_str!("sample.txt");
+    const INPUT: Option<&'static str> = embedded_input!(day18);
+    const ANSWERS: Answers = embedded_answers!(day18);
 
     type Shared = Vec<Edge>;
     type Answer = u64;
 
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 62;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 952408144115;
-
     fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
-        input
-            .lines()
-            .map(|line| edge.parse(line).map_err(anyhow::Error::msg))
-            .collect()
+        input.lines().map(|line| edge.parse_all(line)).collect()
     }
 
     fn part_a(edges: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
@@ -54,6 +52,73 @@ impl BasicSolution for Day {
             .collect::<anyhow::Result<Vec<_>>>()?;
         calc_lagoon_area(&edges)
     }
+
+    /// A plan around a random shape of `size` times 2 columns, made of
+    /// columns that overlap their neighbours so it never crosses itself.
+    /// The colors hide the same shape, stretched.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let mut extents: Vec<(u64, u64)> = Vec::new();
+        for _ in 0..size * 2 {
+            let top = match extents.last() {
+                Some(&(_, prev_bottom)) => rng.u64(0..prev_bottom),
+                None => rng.u64(0..10),
+            };
+            let prev_top = extents.last().map_or(0, |&(prev_top, _)| prev_top);
+            extents.push((top, rng.u64(top.max(prev_top) + 1..=10)));
+        }
+        let mut left = 0;
+        let columns = extents
+            .into_iter()
+            .map(|(top, bottom)| {
+                let width = rng.u64(1..=5);
+                left += width;
+                (top, bottom, left - width, left)
+            })
+            .collect_vec();
+        let corners = columns
+            .iter()
+            .flat_map(|&(top, _, left, right)| [(top, left), (top, right)])
+            .chain(
+                columns
+                    .iter()
+                    .rev()
+                    .flat_map(|&(_, bottom, left, right)| [(bottom, right), (bottom, left)]),
+            )
+            .collect_vec();
+        let (stretch_down, stretch_right) = (rng.u64(1..=10_000), rng.u64(1..=10_000));
+        let mut input = String::new();
+        let mut edges = Vec::new();
+        for ((from_row, from_column), (to_row, to_column)) in
+            corners.into_iter().circular_tuple_windows()
+        {
+            let (direction, length, stretch) =
+                match (to_row.cmp(&from_row), to_column.cmp(&from_column)) {
+                    (Ordering::Greater, _) => (Direction::Down, to_row - from_row, stretch_down),
+                    (Ordering::Less, _) => (Direction::Up, from_row - to_row, stretch_down),
+                    (_, Ordering::Greater) => {
+                        (Direction::Right, to_column - from_column, stretch_right)
+                    }
+                    (_, Ordering::Less) => {
+                        (Direction::Left, from_column - to_column, stretch_right)
+                    }
+                    (Ordering::Equal, Ordering::Equal) => continue,
+                };
+            let (letter, digit) = match direction {
+                Direction::Right => ('R', 0),
+                Direction::Down => ('D', 1),
+                Direction::Left => ('L', 2),
+                Direction::Up => ('U', 3),
+            };
+            let color = (length * stretch) << 4 | digit;
+            input.push_str(&format!("{letter} {length} (#{color:06x})\n"));
+            edges.push(Edge {
+                direction,
+                length,
+                color,
+            });
+        }
+        Some(Generated::round_trip(input, edges))
+    }
 }
 
 fn calc_lagoon_area(edges: &[Edge]) -> Result<u64, anyhow::Error> {
@@ -118,6 +183,8 @@ fn main() -> anyhow::Result<()> {
     Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -131,4 +198,11 @@ mod tests {
     fn b() -> anyhow::Result<()> {
         Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2023::sample_tests!(Day, day18);
 }
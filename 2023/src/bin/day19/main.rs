@@ -1,6 +1,11 @@
-use std::{array, borrow::Cow, collections::HashMap, ops::RangeInclusive};
+use std::{array, borrow::Cow, collections::HashMap};
 
-use advent_2023::{BasicSolution, Solution};
+use advent_2023::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    ranges::BoxSet,
+    register, Answers, BasicSolution, ParserExt, Solution,
+};
 use anyhow::anyhow;
 use winnow::{
     ascii::{alpha1, dec_uint},
@@ -42,36 +47,28 @@ enum Operator {
     LessThan,
 }
 
-type PartRanges = [RangeInclusive<u16>; 4];
+type PartRanges = BoxSet<u16, 4>;
 type Part = [u16; 4];
 type Category = usize;
 
-struct SplitPartRanges {
-    pass: PartRanges,
-    not_pass: PartRanges,
-}
-
 impl BasicSolution for Day {
-    const INPUT: &'static str = include_str!("data.txt");
-    const SAMPLE_INPUT: &'static str = include_str!("sample.txt");
+    const INPUT: Option<&'static str> = embedded_input!(day19);
+    const ANSWERS: Answers = embedded_answers!(day19);
 
     type Shared = (HashMap<&'static str, WorkFlow<'static>>, Vec<Part>);
     type Answer = usize;
 
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 19114;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 167409079868000;
-
     fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
         let (workflows, parts) = input
             .split_once("\n\n")
             .ok_or_else(|| anyhow!("No double newline"))?;
         let workflows = workflows
             .lines()
-            .map(|line| (alpha1, workflow).parse(line).map_err(anyhow::Error::msg))
+            .map(|line| (alpha1, workflow).parse_all(line))
             .collect::<anyhow::Result<_>>()?;
         let parts = parts
             .lines()
-            .map(|line| part.parse(line).map_err(anyhow::Error::msg))
+            .map(|line| part.parse_all(line))
             .collect::<anyhow::Result<_>>()?;
         Ok((workflows, parts))
     }
@@ -97,9 +94,48 @@ impl BasicSolution for Day {
         Ok(distinct_combinations(
             &workflows,
             Destination::Workflow("in"),
-            array::from_fn(|_| 1..=4000),
+            BoxSet::from(array::from_fn(|_| 1..4001)),
         ))
     }
+
+    /// `size` times 5 workflows that only send parts on to later ones, so
+    /// they can't loop, and `size` times 10 parts. Only checked to parse,
+    /// since the workflows are kept in a `HashMap`.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let workflows = size * 5;
+        let name = |i: usize| match i {
+            0 => "in".to_string(),
+            _ => format!(
+                "w{}{}",
+                char::from(b'a' + (i / 26) as u8),
+                char::from(b'a' + (i % 26) as u8)
+            ),
+        };
+        let mut input = String::new();
+        for i in 0..workflows {
+            let send_to = |rng: &mut Rng| match rng.usize(0..4) {
+                0 => "A".to_string(),
+                1 => "R".to_string(),
+                _ if i + 1 < workflows => name(rng.usize(i + 1..workflows)),
+                _ => "A".to_string(),
+            };
+            let rules = (0..rng.usize(1..=4))
+                .map(|_| {
+                    let category = ['x', 'm', 'a', 's'][rng.usize(0..4)];
+                    let operator = if rng.bool() { '<' } else { '>' };
+                    let value = rng.u16(2..4000);
+                    format!("{category}{operator}{value}:{},", send_to(rng))
+                })
+                .collect::<String>();
+            input.push_str(&format!("{}{{{rules}{}}}\n", name(i), send_to(rng)));
+        }
+        input.push('\n');
+        for _ in 0..size * 10 {
+            let [x, m, a, s] = [(); 4].map(|()| rng.u16(1..=4000));
+            input.push_str(&format!("{{x={x},m={m},a={a},s={s}}}\n"));
+        }
+        Some(Generated::new(input))
+    }
 }
 
 fn distinct_combinations(
@@ -107,17 +143,17 @@ fn distinct_combinations(
     destination: Destination<'static>,
     mut part_ranges: PartRanges,
 ) -> usize {
-    if part_ranges.iter().any(|r| r.is_empty()) {
+    if part_ranges.is_empty() {
         return 0;
     }
     let workflow = match destination {
-        Destination::Accept => return part_ranges.iter().map(|r| r.len()).product(),
+        Destination::Accept => return part_ranges.volume() as usize,
         Destination::Reject => return 0,
         Destination::Workflow(w) => w,
     };
     let mut count = 0;
     for rule in &workflows[workflow].rules {
-        let SplitPartRanges { pass, not_pass } = split_part_ranges(&part_ranges, rule.condition);
+        let (pass, not_pass) = rule.condition.split(&part_ranges);
         part_ranges = not_pass;
         count += distinct_combinations(workflows, rule.destination, pass);
     }
@@ -138,32 +174,20 @@ impl WorkFlow<'_> {
 
 impl Condition {
     fn test(&self, part: &Part) -> bool {
+        self.passes(part[self.category])
+    }
+
+    fn passes(&self, rating: u16) -> bool {
         match self.operator {
-            Operator::GreaterThan => part[self.category] > self.value,
-            Operator::LessThan => part[self.category] < self.value,
+            Operator::GreaterThan => rating > self.value,
+            Operator::LessThan => rating < self.value,
         }
     }
-}
 
-fn split_part_ranges(part_ranges: &PartRanges, condition: Condition) -> SplitPartRanges {
-    let mut pass = part_ranges.clone();
-    let mut not_pass = part_ranges.clone();
-    match condition.operator {
-        Operator::GreaterThan => {
-            pass[condition.category] = (*pass[condition.category].start()).max(condition.value + 1)
-                ..=*pass[condition.category].end();
-            not_pass[condition.category] = *not_pass[condition.category].start()
-                ..=(*not_pass[condition.category].end()).min(condition.value);
-        }
-        Operator::LessThan => {
-            pass[condition.category] = *pass[condition.category].start()
-                ..=(*pass[condition.category].end()).min(condition.value - 1);
-            not_pass[condition.category] = (*not_pass[condition.category].start())
-                .max(condition.value)
-                ..=*not_pass[condition.category].end();
-        }
+    /// The parts that pass, and the ones that don't.
+    fn split(&self, part_ranges: &PartRanges) -> (PartRanges, PartRanges) {
+        part_ranges.split(self.category, |rating| self.passes(rating))
     }
-    SplitPartRanges { pass, not_pass }
 }
 
 fn workflow(input: &mut &'static str) -> winnow::Result<WorkFlow<'static>> {
@@ -236,6 +260,8 @@ fn main() -> anyhow::Result<()> {
     Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -249,4 +275,11 @@ mod tests {
     fn b() -> anyhow::Result<()> {
         Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2023::sample_tests!(Day, day19);
 }
@@ -1,7 +1,12 @@
 #![warn(clippy::pedantic)]
 use std::borrow::Cow;
 
-use advent_2023::{BasicSolution, Solution};
+use advent_2023::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, ParserExt, Solution,
+};
+use itertools::Itertools;
 use winnow::{
     ascii::dec_uint,
     combinator::{alt, opt, preceded, repeat},
@@ -21,20 +26,14 @@ const GREEN: usize = 1;
 const BLUE: usize = 2;
 
 impl BasicSolution for Day {
-    const INPUT: &'static str = include_str!("data.txt");
-    const SAMPLE_INPUT: &'static str = include_str!("sample.txt");
+    const INPUT: Option<&'static str> = embedded_input!(day2);
+    const ANSWERS: Answers = embedded_answers!(day2);
 
     type Shared = Vec<Game>;
     type Answer = u32;
 
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 8;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 2286;
-
     fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
-        input
-            .lines()
-            .map(|line| game.parse(line).map_err(anyhow::Error::msg))
-            .collect()
+        input.lines().map(|line| game.parse_all(line)).collect()
     }
 
     fn part_a(games: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
@@ -57,6 +56,31 @@ impl BasicSolution for Day {
             })
             .sum())
     }
+
+    /// `size` times 10 games of up to 5 reveals each.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let (lines, games): (Vec<_>, _) = (1..=u8::try_from(size * 10).ok()?)
+            .map(|id| {
+                let mut revealed = [0; 3];
+                let reveals = (0..rng.usize(1..=5))
+                    .map(|_| {
+                        let mut colors = [RED, GREEN, BLUE];
+                        rng.shuffle(&mut colors);
+                        colors[..rng.usize(1..=3)]
+                            .iter()
+                            .map(|&color| {
+                                let n = rng.u8(1..=20);
+                                revealed[color] = revealed[color].max(n);
+                                format!("{n} {}", ["red", "green", "blue"][color])
+                            })
+                            .join(", ")
+                    })
+                    .join("; ");
+                (format!("Game {id}: {reveals}\n"), Game { id, revealed })
+            })
+            .unzip();
+        Some(Generated::round_trip(lines.concat(), games))
+    }
 }
 
 fn game(input: &mut &str) -> winnow::Result<Game> {
@@ -94,6 +118,8 @@ fn main() -> anyhow::Result<()> {
     Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -107,4 +133,11 @@ mod tests {
     fn b() -> anyhow::Result<()> {
         Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2023::sample_tests!(Day, day2);
 }
@@ -3,7 +3,11 @@ use std::{
     collections::{HashMap, VecDeque},
 };
 
-use advent_2023::Solution;
+use advent_2023::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, ParserExt, Solution,
+};
 use anyhow::{anyhow, bail};
 use itertools::Itertools;
 use num::Integer;
@@ -46,21 +50,16 @@ enum PulseStrength {
 }
 
 impl Solution for Day {
-    const INPUT: &'static str = include_str!("data.txt");
-    const SAMPLE_INPUT: &'static str = include_str!("sample.txt");
+    const INPUT: Option<&'static str> = embedded_input!(day20);
+    const ANSWERS: Answers = embedded_answers!(day20);
 
     type Shared = Graph;
+    type SharedTest = Self::Shared;
     type Answer = u64;
 
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 11687500;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 0;
-
     fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
         let mut graph = Graph::default();
-        for parsed_line in input
-            .lines()
-            .map(|line| node.parse(line).map_err(anyhow::Error::msg))
-        {
+        for parsed_line in input.lines().map(|line| node.parse_all(line)) {
             let (module, name, destinations) = parsed_line?;
             graph.modules.insert(name, module);
             graph.destinations.insert(name, destinations);
@@ -131,6 +130,51 @@ impl Solution for Day {
         Err(anyhow!("Couldn't find a solution within 100,000 presses"))
     }
 
+    /// Binary counters like the real input's, each of flip-flops that a
+    /// conjunction resets after a random number of presses. There are up to
+    /// 4 counters of up to 12 flip-flops, growing with `size` until then.
+    /// Only checked to parse, since the modules are kept in `HashMap`s.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let letter = |i: usize| char::from(b'a' + i as u8);
+        let bits = (size + 4).min(12);
+        let mut lines = Vec::new();
+        let mut counters = Vec::new();
+        for counter in 0..size.min(4) {
+            // The first and last bits are always set
+            let presses = rng.usize(1 << (bits - 1)..1 << bits) | 1;
+            let flip_flop = |bit| format!("f{}{}", letter(counter), letter(bit));
+            let hub = format!("hub{}", letter(counter));
+            let mut resets = vec![flip_flop(0)];
+            for bit in 0..bits {
+                let mut destinations = Vec::new();
+                if bit + 1 < bits {
+                    destinations.push(flip_flop(bit + 1));
+                }
+                if presses & 1 << bit == 0 {
+                    resets.push(flip_flop(bit));
+                } else {
+                    destinations.push(hub.clone());
+                }
+                rng.shuffle(&mut destinations);
+                lines.push(format!(
+                    "%{} -> {}",
+                    flip_flop(bit),
+                    destinations.join(", ")
+                ));
+            }
+            let inverter = format!("inv{}", letter(counter));
+            resets.push(inverter.clone());
+            rng.shuffle(&mut resets);
+            lines.push(format!("&{hub} -> {}", resets.join(", ")));
+            lines.push(format!("&{inverter} -> zz"));
+            counters.push(flip_flop(0));
+        }
+        lines.push(format!("broadcaster -> {}", counters.join(", ")));
+        lines.push("&zz -> rx".to_string());
+        rng.shuffle(&mut lines);
+        Some(Generated::new(lines.join("\n") + "\n"))
+    }
+
     fn shared_test(input: &'static str) -> anyhow::Result<Self::SharedTest> {
         Self::shared(input)
     }
@@ -220,6 +264,8 @@ fn main() -> anyhow::Result<()> {
     Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -233,4 +279,11 @@ mod tests {
     fn b() -> anyhow::Result<()> {
         Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2023::sample_tests!(Day, day20);
 }
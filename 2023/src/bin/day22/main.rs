@@ -1,6 +1,10 @@
 use std::borrow::Cow;
 
-use advent_2023::{BasicSolution, Solution};
+use advent_2023::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, ParserExt, Solution,
+};
 use anyhow::anyhow;
 use fxhash::FxHashSet;
 use itertools::Itertools;
@@ -18,19 +22,16 @@ type Grid = Vec<[[u16; 10]; 10]>;
 type Ends = Vec<u16>;
 
 impl BasicSolution for Day {
-    const INPUT: &'static str = include_str!("data.txt");
-    const SAMPLE_INPUT: &'static str = include_str!("sample.txt");
+    const INPUT: Option<&'static str> = embedded_input!(day22);
+    const ANSWERS: Answers = embedded_answers!(day22);
 
     type Shared = (Vec<Brick>, Grid, Ends);
     type Answer = usize;
 
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 5;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 7;
-
     fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
         let mut bricks = input
             .lines()
-            .map(|line| brick.parse(line).map_err(anyhow::Error::msg))
+            .map(|line| brick.parse_all(line))
             .collect::<anyhow::Result<Vec<_>>>()?;
         let (grid, ends) = compute_resting_position(&mut bricks)?;
         Ok((bricks, grid, ends))
@@ -68,6 +69,31 @@ impl BasicSolution for Day {
             })
             .sum())
     }
+
+    /// `size` times 20 bricks up to 4 cubes long, each snapshotted above
+    /// the last so none overlap. Only checked to parse, since parsing also
+    /// lets the bricks fall to rest.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let mut top = 0;
+        let mut lines = (0..size * 20)
+            .map(|_| {
+                let from = (rng.u8(0..10), rng.u8(0..10), top + rng.u16(1..=3));
+                let length = rng.u8(0..4);
+                let to = match rng.u8(0..3) {
+                    0 => ((from.0 + length).min(9), from.1, from.2),
+                    1 => (from.0, (from.1 + length).min(9), from.2),
+                    _ => (from.0, from.1, from.2 + u16::from(length)),
+                };
+                top = to.2;
+                format!(
+                    "{},{},{}~{},{},{}\n",
+                    from.0, from.1, from.2, to.0, to.1, to.2
+                )
+            })
+            .collect_vec();
+        rng.shuffle(&mut lines);
+        Some(Generated::new(lines.concat()))
+    }
 }
 
 fn chain_reaction(supported: &[FxHashSet<u16>], supported_counts: &mut [usize], remove: u16) {
@@ -161,6 +187,8 @@ fn main() -> anyhow::Result<()> {
     Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -174,4 +202,11 @@ mod tests {
     fn b() -> anyhow::Result<()> {
         Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2023::sample_tests!(Day, day22);
 }
@@ -1,28 +1,31 @@
 use std::borrow::Cow;
 
-use advent_2023::{BasicSolution, Solution};
+use advent_2023::{
+    branch_and_bound::{self, Order, Problem},
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    grid::{Grid, Pos, ORTHOGONAL},
+    register, Answers, BasicSolution, Solution,
+};
+use anyhow::ensure;
 use arrayvec::ArrayVec;
 use fxhash::{FxHashMap, FxHashSet};
 use itertools::Itertools;
 
 struct Day;
 
-type Coords = (u8, u8);
-type BuildGraph = FxHashMap<Coords, FxHashMap<Coords, u16>>;
+type BuildGraph = FxHashMap<Pos, FxHashMap<Pos, u16>>;
 type FinalGraph = Vec<ArrayVec<(u8, u16), 4>>;
 
 impl BasicSolution for Day {
-    const INPUT: &'static str = include_str!("data.txt");
-    const SAMPLE_INPUT: &'static str = include_str!("sample.txt");
+    const INPUT: Option<&'static str> = embedded_input!(day23);
+    const ANSWERS: Answers = embedded_answers!(day23);
 
-    type Shared = Vec<&'static [u8]>;
+    type Shared = Grid<u8>;
     type Answer = u16;
 
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 94;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 154;
-
     fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
-        Ok(input.lines().map(str::as_bytes).collect())
+        Ok(Grid::parse(input)?)
     }
 
     fn part_a(grid: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
@@ -32,63 +35,159 @@ impl BasicSolution for Day {
     fn part_b(grid: Self::Shared) -> anyhow::Result<Self::Answer> {
         solve::<false>(&grid)
     }
+
+    fn validate(grid: &Self::Shared) -> anyhow::Result<()> {
+        let (height, width) = (grid.height(), grid.width());
+        ensure!(height >= 2 && width >= 3, "the grid should be at least 2x3");
+        ensure!(
+            grid[(0, 1)] == b'.' && grid[(height - 1, width - 2)] == b'.',
+            "the path should start at the second column of the first row, and end at the \
+             second to last column of the last row"
+        );
+        Ok(())
+    }
+
+    /// A lattice of junctions like the real input's, up to 5 by 5 as `size`
+    /// grows, with slopes down and to the right around each of them and some
+    /// of the paths between them missing.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let junctions = (size + 1).min(5);
+        let spacing = rng.usize(3..=6);
+        let (height, width) = ((junctions - 1) * spacing + 5, (junctions - 1) * spacing + 3);
+        let mut grid = Grid::new(width, height, b'#');
+        // Slopes into the first junction and out of the last, so the way
+        // back to the start and end is uphill
+        grid[(0, 1)] = b'.';
+        grid[(1, 1)] = b'v';
+        grid[(height - 2, width - 2)] = b'v';
+        grid[(height - 1, width - 2)] = b'.';
+        let mut paths = (0..junctions)
+            .cartesian_product(0..junctions)
+            .flat_map(|(i, j)| [((i, j), (i, j + 1)), ((i, j), (i + 1, j))])
+            .filter(|&(_, (i, j))| i < junctions && j < junctions)
+            .collect_vec();
+        rng.shuffle(&mut paths);
+        // Paths into and out of each junction, counting the start and end
+        let mut ins = vec![vec![2; junctions]; junctions];
+        let mut outs = vec![vec![2; junctions]; junctions];
+        for i in 0..junctions {
+            ins[0][i] -= 1;
+            ins[i][0] -= 1;
+            outs[junctions - 1][i] -= 1;
+            outs[i][junctions - 1] -= 1;
+        }
+        ins[0][0] += 1;
+        outs[junctions - 1][junctions - 1] += 1;
+        // Every junction keeps a way in and out, since the solution can't
+        // handle dead ends
+        paths.retain(|&((i1, j1), (i2, j2))| {
+            let keep = outs[i1][j1] < 2 || ins[i2][j2] < 2 || rng.bool();
+            if !keep {
+                outs[i1][j1] -= 1;
+                ins[i2][j2] -= 1;
+            }
+            keep
+        });
+        for ((i1, j1), (i2, _)) in paths {
+            let (row, col) = (2 + i1 * spacing, 1 + j1 * spacing);
+            let (slope, (drow, dcol)) = if i2 > i1 {
+                (b'v', (1, 0))
+            } else {
+                (b'>', (0, 1))
+            };
+            for step in 0..=spacing {
+                let tile = if step == 1 || step + 1 == spacing {
+                    slope
+                } else {
+                    b'.'
+                };
+                grid[(row + step * drow, col + step * dcol)] = tile;
+            }
+        }
+        Some(Generated::round_trip(grid.to_string(), grid))
+    }
 }
 
-fn solve<const PART_A: bool>(grid: &[&[u8]]) -> Result<u16, anyhow::Error> {
+fn solve<const PART_A: bool>(grid: &Grid<u8>) -> Result<u16, anyhow::Error> {
     let mut graph = BuildGraph::default();
     build_graph::<PART_A>(grid, &mut graph, (0, 1), (1, 1));
-    let target_coords = (grid.len() as u8 - 1, grid[0].len() as u8 - 2);
+    let target_coords = (grid.height() - 1, grid.width() - 2);
+    let nodes = graph.len() + usize::from(!graph.contains_key(&target_coords));
+    ensure!(
+        nodes <= u64::BITS as usize,
+        "the paths have {nodes} junctions, counting the start and end, but the ones visited \
+         are packed into a u64"
+    );
     let (final_graph, start_node, target) = finalize_graph(graph, target_coords);
-    let mut best = 0;
-    let mut seen = vec![false; final_graph.len()];
-    let total_bound = compute_total_bound(&final_graph);
-    let traveled = 0;
-    branch_and_bound(
-        &final_graph,
+    let hike = Hike {
+        graph: final_graph,
         target,
-        &mut best,
-        &mut seen,
-        total_bound,
-        traveled,
-        start_node,
-    );
-    Ok(best)
+    };
+    let start = Node {
+        junction: start_node,
+        seen: 1 << start_node,
+        traveled: 0,
+        bound: compute_total_bound(&hike.graph),
+    };
+    let outcome = branch_and_bound::solve(&hike, start, Order::DepthFirst);
+    Ok(outcome.score().unwrap_or(0))
 }
 
-fn branch_and_bound(
-    graph: &FinalGraph,
+/// The longest hike through the junctions, never visiting one twice.
+struct Hike {
+    graph: FinalGraph,
     target: u8,
-    best: &mut u16,
-    seen: &mut [bool],
-    bound: u16,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    junction: u8,
+    /// The junctions visited so far, as bits
+    seen: u64,
     traveled: u16,
-    node: u8,
-) {
-    seen[node as usize] = true;
-    // After we chose a path, we can no longer benefit
-    // from the length of the edges we didn't choose.
-    let base_bound = bound
-        - graph[node as usize]
+    /// The total length of the edges that could still be hiked along
+    bound: u16,
+}
+
+impl Problem for Hike {
+    type Node = Node;
+    type Score = u16;
+    type Key = ();
+
+    fn score(&self, node: &Node) -> Option<u16> {
+        (node.junction == self.target).then_some(node.traveled)
+    }
+
+    fn bound(&self, node: &Node) -> u16 {
+        node.bound
+    }
+
+    fn branch(&self, node: &Node) -> impl IntoIterator<Item = Node> {
+        let node = *node;
+        let edges = if node.junction == self.target {
+            &[][..]
+        } else {
+            &self.graph[node.junction as usize][..]
+        };
+        let unseen = move |&&(to, _): &&(u8, u16)| node.seen & (1 << to) == 0;
+        // After we chose a path, we can no longer benefit
+        // from the length of the edges we didn't choose.
+        let base_bound = node.bound
+            - edges
+                .iter()
+                .filter(unseen)
+                .map(|(_, length)| length)
+                .sum::<u16>();
+        edges
             .iter()
-            .filter(|&&(to, _)| !seen[to as usize])
-            .map(|(_, length)| length)
-            .sum::<u16>();
-    for &(next_node, length) in &graph[node as usize] {
-        if seen[next_node as usize] {
-            continue;
-        }
-        let traveled = traveled + length;
-        if next_node == target {
-            *best = (*best).max(traveled);
-            continue;
-        }
-        let bound = base_bound + length;
-        if bound <= *best {
-            continue;
-        }
-        branch_and_bound(graph, target, best, seen, bound, traveled, next_node);
+            .filter(unseen)
+            .map(move |&(next, length)| Node {
+                junction: next,
+                seen: node.seen | (1 << next),
+                traveled: node.traveled + length,
+                bound: base_bound + length,
+            })
     }
-    seen[node as usize] = false;
 }
 
 fn compute_total_bound(graph: &FinalGraph) -> u16 {
@@ -105,12 +204,12 @@ fn compute_total_bound(graph: &FinalGraph) -> u16 {
     total_bound
 }
 
-fn finalize_graph(graph: BuildGraph, target: Coords) -> (FinalGraph, u8, u8) {
+fn finalize_graph(graph: BuildGraph, target: Pos) -> (FinalGraph, u8, u8) {
     let mut coords_to_node = graph
         .keys()
         .enumerate()
         .map(|(node, &coords)| (coords, node as u8))
-        .collect::<FxHashMap<Coords, u8>>();
+        .collect::<FxHashMap<Pos, u8>>();
     let start_node = coords_to_node[&(0, 1)];
     let target_node = *coords_to_node.entry(target).or_insert(graph.len() as u8);
     let mut final_graph = vec![ArrayVec::new(); coords_to_node.len()];
@@ -125,15 +224,15 @@ fn finalize_graph(graph: BuildGraph, target: Coords) -> (FinalGraph, u8, u8) {
 }
 
 fn build_graph<const PART_A: bool>(
-    grid: &[&[u8]],
+    grid: &Grid<u8>,
     graph: &mut BuildGraph,
-    last_node: Coords,
-    mut coords: Coords,
+    last_node: Pos,
+    mut coords: Pos,
 ) {
     let mut prev_coords = last_node;
     for traveled in 1.. {
         if PART_A {
-            if let Some(next_coords) = match get_2d(grid, coords) {
+            if let Some(next_coords) = match grid.get(coords) {
                 Some(b'>') => Some((coords.0, coords.1 + 1)),
                 Some(b'v') => Some((coords.0 + 1, coords.1)),
                 Some(b'<') => Some((coords.0, coords.1 - 1)),
@@ -148,11 +247,9 @@ fn build_graph<const PART_A: bool>(
                 continue;
             }
         }
-        match adjacent_coords(coords)
-            .filter(|&next_coords| {
-                next_coords != prev_coords
-                    && get_2d(grid, next_coords).is_some_and(|tile| tile != b'#')
-            })
+        match grid
+            .neighbors(coords, &ORTHOGONAL)
+            .filter(|&next_coords| next_coords != prev_coords && grid[next_coords] != b'#')
             .exactly_one()
         {
             Ok(next_coords) => {
@@ -180,7 +277,7 @@ fn build_graph<const PART_A: bool>(
     }
 }
 
-fn insert_edge(graph: &mut BuildGraph, a: Coords, b: Coords, length: u16) {
+fn insert_edge(graph: &mut BuildGraph, a: Pos, b: Pos, length: u16) {
     graph
         .entry(a)
         .or_default()
@@ -189,23 +286,12 @@ fn insert_edge(graph: &mut BuildGraph, a: Coords, b: Coords, length: u16) {
         .or_insert(length);
 }
 
-fn get_2d(grid: &[&[u8]], (row, col): Coords) -> Option<u8> {
-    grid.get(row as usize)?.get(col as usize).copied()
-}
-
-fn adjacent_coords((row, col): Coords) -> impl Iterator<Item = Coords> {
-    [(1, 0), (0, 1), (-1, 0), (0, -1)]
-        .into_iter()
-        .flat_map(move |(drow, dcol)| {
-            row.checked_add_signed(drow)
-                .zip(col.checked_add_signed(dcol))
-        })
-}
-
 fn main() -> anyhow::Result<()> {
     Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -219,4 +305,19 @@ mod tests {
     fn b() -> anyhow::Result<()> {
         Day::test_part_b()
     }
+
+    #[test]
+    fn validate() -> anyhow::Result<()> {
+        (REGISTERED.validate)(Some(include_str!("sample.txt")))?;
+        let error = (REGISTERED.validate)(Some("#.#\n#.#\n##.\n")).unwrap_err();
+        assert!(error.to_string().contains("path should start"), "{error}");
+        Ok(())
+    }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2023::sample_tests!(Day, day23);
 }
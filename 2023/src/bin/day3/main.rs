@@ -1,7 +1,12 @@
 #![warn(clippy::pedantic)]
 use std::{borrow::Cow, collections::HashMap, ops::Range};
 
-use advent_2023::{BasicSolution, Solution};
+use advent_2023::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    grid::Grid,
+    register, Answers, BasicSolution, Solution,
+};
 use itertools::Itertools;
 use winnow::{
     ascii::dec_uint,
@@ -14,8 +19,8 @@ use winnow::{
 struct Day;
 
 #[derive(Debug, Clone)]
-struct Schematic<'a> {
-    raw: Vec<&'a [u8]>,
+struct Schematic {
+    raw: Grid<u8>,
     numbers: Vec<Number>,
 }
 
@@ -27,15 +32,12 @@ struct Number {
 }
 
 impl BasicSolution for Day {
-    const INPUT: &'static str = include_str!("data.txt");
-    const SAMPLE_INPUT: &'static str = include_str!("sample.txt");
+    const INPUT: Option<&'static str> = embedded_input!(day3);
+    const ANSWERS: Answers = embedded_answers!(day3);
 
-    type Shared = Schematic<'static>;
+    type Shared = Schematic;
     type Answer = u32;
 
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 4361;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 467_835;
-
     fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
         let mut numbers = Vec::new();
         for (row, line) in input.lines().enumerate() {
@@ -52,7 +54,7 @@ impl BasicSolution for Day {
             let (rest, ()) = iter_nums.finish().map_err(anyhow::Error::msg)?;
             not_dec.parse(rest).map_err(anyhow::Error::msg)?;
         }
-        let raw = input.lines().map(str::as_bytes).collect_vec();
+        let raw = Grid::parse(input)?;
         Ok(Schematic { raw, numbers })
     }
 
@@ -62,7 +64,10 @@ impl BasicSolution for Day {
             .iter()
             .filter(|number| {
                 number.adjacent_coords().any(|coords| {
-                    get_2d(&schematic.raw, coords).is_some_and(|c| !c.is_ascii_digit() && c != b'.')
+                    schematic
+                        .raw
+                        .get(coords)
+                        .is_some_and(|&c| !c.is_ascii_digit() && c != b'.')
                 })
             })
             .map(|number| number.value)
@@ -73,7 +78,7 @@ impl BasicSolution for Day {
         let mut potential_gears: HashMap<(usize, usize), Vec<u32>> = HashMap::new();
         for num in numbers {
             num.adjacent_coords()
-                .filter(|&coords| get_2d(&raw, coords) == Some(b'*'))
+                .filter(|&coords| raw.get(coords) == Some(&b'*'))
                 .for_each(|coords| {
                     potential_gears.entry(coords).or_default().push(num.value);
                 });
@@ -84,6 +89,40 @@ impl BasicSolution for Day {
             .map(|values| values.into_iter().product::<u32>())
             .sum())
     }
+
+    /// A square schematic `size` times 10 characters across, of numbers
+    /// and symbols scattered among dots.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let width = size * 10;
+        let mut input = String::new();
+        let mut numbers = Vec::new();
+        for row_idx in 0..width {
+            let mut row = String::new();
+            while row.len() < width {
+                let value = rng.u32(1..1000);
+                let number = value.to_string();
+                match rng.u8(0..10) {
+                    0 => row
+                        .push(['*', '#', '+', '$', '/', '@', '=', '%', '-', '&'][rng.usize(0..10)]),
+                    // Followed by a dot so it doesn't run into the next number
+                    1..=3 if row.len() + number.len() < width => {
+                        numbers.push(Number {
+                            row: row_idx,
+                            columns: row.len()..row.len() + number.len(),
+                            value,
+                        });
+                        row.push_str(&number);
+                        row.push('.');
+                    }
+                    _ => row.push('.'),
+                }
+            }
+            input.push_str(&row);
+            input.push('\n');
+        }
+        let raw = Grid::from_vec(width, input.bytes().filter(|&b| b != b'\n').collect())?;
+        Some(Generated::round_trip(input, Schematic { raw, numbers }))
+    }
 }
 
 fn not_dec(input: &mut LocatingSlice<&'static str>) -> winnow::Result<&'static str> {
@@ -98,14 +137,12 @@ impl Number {
     }
 }
 
-fn get_2d(grid: &[&[u8]], (row, col): (usize, usize)) -> Option<u8> {
-    grid.get(row).and_then(|line| line.get(col)).copied()
-}
-
 fn main() -> anyhow::Result<()> {
     Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -119,4 +156,11 @@ mod tests {
     fn b() -> anyhow::Result<()> {
         Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2023::sample_tests!(Day, day3);
 }
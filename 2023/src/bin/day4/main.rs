@@ -1,7 +1,12 @@
 #![warn(clippy::pedantic)]
 use std::borrow::Cow;
 
-use advent_2023::{BasicSolution, Solution};
+use advent_2023::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, ParserExt, Solution,
+};
+use anyhow::anyhow;
 use itertools::Itertools;
 use winnow::{
     token::{rest, take_until},
@@ -16,20 +21,14 @@ struct Card {
 }
 
 impl BasicSolution for Day {
-    const INPUT: &'static str = include_str!("data.txt");
-    const SAMPLE_INPUT: &'static str = include_str!("sample.txt");
+    const INPUT: Option<&'static str> = embedded_input!(day4);
+    const ANSWERS: Answers = embedded_answers!(day4);
 
     type Shared = Vec<Card>;
     type Answer = usize;
 
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 13;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 30;
-
     fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
-        input
-            .lines()
-            .map(|line| card.parse(line).map_err(anyhow::Error::msg))
-            .collect()
+        input.lines().map(|line| card.parse_all(line)).collect()
     }
 
     fn part_a(cards: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
@@ -41,15 +40,44 @@ impl BasicSolution for Day {
     }
 
     fn part_b(cards: Self::Shared) -> anyhow::Result<Self::Answer> {
-        let mut card_copies = vec![1; cards.len()];
-        cards.iter().enumerate().for_each(|(i, card)| {
+        let too_many = || anyhow!("too many copies to count");
+        let mut card_copies = vec![1usize; cards.len()];
+        for (i, card) in cards.iter().enumerate() {
             let copies_of_cur = card_copies[i];
-            card_copies[i + 1..]
-                .iter_mut()
-                .take(card.matches)
-                .for_each(|c| *c += copies_of_cur);
-        });
-        Ok(card_copies.into_iter().sum())
+            for c in card_copies[i + 1..].iter_mut().take(card.matches) {
+                *c = c.checked_add(copies_of_cur).ok_or_else(too_many)?;
+            }
+        }
+        card_copies
+            .into_iter()
+            .try_fold(0usize, usize::checked_add)
+            .ok_or_else(too_many)
+    }
+
+    /// `size` times 10 cards of 5 winning numbers and 8 of mine.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let (lines, cards): (Vec<_>, _) = (1..=size * 10)
+            .map(|id| {
+                let mut numbers = (1..100).collect_vec();
+                rng.shuffle(&mut numbers);
+                let winning = &numbers[..5];
+                let mut mine = numbers[5..]
+                    .iter()
+                    .take(8 - rng.usize(0..=5))
+                    .copied()
+                    .collect_vec();
+                mine.extend(winning.iter().take(8 - mine.len()));
+                rng.shuffle(&mut mine);
+                let matches = mine.iter().filter(|n| winning.contains(n)).count();
+                let [winning, mine] = [winning, &mine]
+                    .map(|numbers| numbers.iter().map(|n| format!("{n:>2}")).join(" "));
+                (
+                    format!("Card {id:>3}: {winning} | {mine}\n"),
+                    Card { matches },
+                )
+            })
+            .unzip();
+        Some(Generated::round_trip(lines.concat(), cards))
     }
 }
 
@@ -67,6 +95,8 @@ fn main() -> anyhow::Result<()> {
     Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -80,4 +110,11 @@ mod tests {
     fn b() -> anyhow::Result<()> {
         Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2023::sample_tests!(Day, day4);
 }
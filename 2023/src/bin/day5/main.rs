@@ -1,11 +1,16 @@
 #![warn(clippy::pedantic)]
-use std::{borrow::Cow, ops::Range};
+use std::{borrow::Cow, fmt::Write, ops::Range};
 
-use advent_2023::{BasicSolution, Solution};
+use advent_2023::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    ranges::IntervalSet,
+    register, Answers, BasicSolution, ParserExt, Solution,
+};
 use itertools::Itertools;
 use winnow::{
-    ascii::{dec_uint, line_ending},
-    combinator::{not, opt, preceded, separated},
+    ascii::{dec_uint, line_ending, till_line_ending},
+    combinator::{opt, preceded, separated},
     seq, Parser,
 };
 
@@ -23,25 +28,15 @@ struct Almanac {
     all_mappings: Vec<Vec<Mapping>>,
 }
 
-#[derive(Debug, Clone)]
-struct MappedRange {
-    before: Option<Range<u64>>,
-    overlap: Option<Range<u64>>,
-    after: Option<Range<u64>>,
-}
-
 impl BasicSolution for Day {
-    const INPUT: &'static str = include_str!("data.txt");
-    const SAMPLE_INPUT: &'static str = include_str!("sample.txt");
+    const INPUT: Option<&'static str> = embedded_input!(day5);
+    const ANSWERS: Answers = embedded_answers!(day5);
 
     type Shared = Almanac;
     type Answer = u64;
 
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 35;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 46;
-
     fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
-        almanac.parse(input).map_err(anyhow::Error::msg)
+        almanac.parse_all(input)
     }
 
     fn part_a(almanac: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
@@ -64,62 +59,80 @@ impl BasicSolution for Day {
 
     fn part_b(almanac: Self::Shared) -> anyhow::Result<Self::Answer> {
         let seed_ranges = almanac.seeds.into_iter().tuples().map(|(a, b)| a..a + b);
-        seed_ranges
-            .flat_map(|seed_range| {
-                almanac
-                    .all_mappings
-                    .iter()
-                    .fold(vec![seed_range], |acc, mappings| {
-                        acc.into_iter()
-                            .flat_map(|input_range| multi_map_range(input_range, mappings))
-                            .collect()
-                    })
-            })
-            .map(|location_range| location_range.start)
-            .min()
+        let locations = almanac.all_mappings.iter().fold(
+            seed_ranges.collect::<IntervalSet<_>>(),
+            |acc, mappings| {
+                acc.map_linear(
+                    mappings
+                        .iter()
+                        .map(|mapping| (mapping.source.clone(), mapping.destination_start)),
+                )
+            },
+        );
+        locations
+            .first()
             .ok_or_else(|| anyhow::Error::msg("no location ranges"))
     }
-}
 
-fn multi_map_range(
-    input_range: Range<u64>,
-    mappings: &[Mapping],
-) -> impl Iterator<Item = Range<u64>> {
-    let mut overlaps = vec![];
-    let mut leftovers = vec![input_range];
-    let mut leftovers_queue = vec![];
-    for mapping in mappings {
-        for input_range in leftovers.drain(..) {
-            let mapped = mapping.map_range(input_range);
-            overlaps.extend(mapped.overlap);
-            leftovers_queue.extend(mapped.before);
-            leftovers_queue.extend(mapped.after);
+    /// `size` seed ranges, and maps of up to `size` times 3 source ranges
+    /// that cover the numbers up to 2^32 with a few gaps.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        const NAMES: [&str; 8] = [
+            "seed",
+            "soil",
+            "fertilizer",
+            "water",
+            "light",
+            "temperature",
+            "humidity",
+            "location",
+        ];
+        let seeds = (0..size)
+            .flat_map(|_| [rng.u64(0..1 << 32), rng.u64(1..1 << 28)])
+            .collect_vec();
+        let all_mappings = (0..7)
+            .map(|_| {
+                let mut cuts = (1..size * 3).map(|_| rng.u64(0..1 << 32)).collect_vec();
+                cuts.extend([0, 1 << 32]);
+                cuts.sort_unstable();
+                let mut mappings = Vec::new();
+                for (start, end) in cuts.into_iter().tuple_windows() {
+                    // Leave gaps, which map to themselves, but never only gaps
+                    if start < end && (start == 0 || rng.u8(0..4) != 0) {
+                        mappings.push(Mapping {
+                            source: start..end,
+                            destination_start: rng.u64(0..1 << 32),
+                        });
+                    }
+                }
+                rng.shuffle(&mut mappings);
+                mappings
+            })
+            .collect_vec();
+        let mut input = format!("seeds: {}\n", seeds.iter().join(" "));
+        for ((source, destination), mappings) in NAMES.iter().tuple_windows().zip(&all_mappings) {
+            write!(input, "\n{source}-to-{destination} map:\n").ok()?;
+            for Mapping {
+                source,
+                destination_start,
+            } in mappings
+            {
+                let len = source.end - source.start;
+                writeln!(input, "{destination_start} {} {len}", source.start).ok()?;
+            }
         }
-        std::mem::swap(&mut leftovers, &mut leftovers_queue);
+        let almanac = Almanac {
+            seeds,
+            all_mappings,
+        };
+        Some(Generated::round_trip(input, almanac))
     }
-    overlaps.into_iter().chain(leftovers)
 }
 
 impl Mapping {
     fn offset(&self, value: u64) -> u64 {
         value + self.destination_start - self.source.start
     }
-
-    fn map_range(&self, range: Range<u64>) -> MappedRange {
-        let opt_range = |start, end| Some(start..end).filter(|r| !r.is_empty());
-        let before = opt_range(range.start, self.source.start.min(range.end));
-        let after = opt_range(self.source.end.max(range.start), range.end);
-        let overlap = opt_range(
-            range.start.max(self.source.start),
-            range.end.min(self.source.end),
-        )
-        .map(|r| self.offset(r.start)..self.offset(r.end));
-        MappedRange {
-            before,
-            overlap,
-            after,
-        }
-    }
 }
 
 fn almanac(input: &mut &'static str) -> winnow::Result<Almanac> {
@@ -137,7 +150,11 @@ fn seeds(input: &mut &str) -> winnow::Result<Vec<u64>> {
 }
 
 fn mappings(input: &mut &str) -> winnow::Result<Vec<Mapping>> {
-    preceded((not(line_ending), "\n"), separated(1.., mapping, "\n")).parse_next(input)
+    preceded(
+        (till_line_ending, line_ending),
+        separated(1.., mapping, "\n"),
+    )
+    .parse_next(input)
 }
 
 fn mapping(input: &mut &str) -> winnow::Result<Mapping> {
@@ -157,6 +174,8 @@ fn main() -> anyhow::Result<()> {
     Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -170,4 +189,11 @@ mod tests {
     fn b() -> anyhow::Result<()> {
         Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2023::sample_tests!(Day, day5);
 }
@@ -1,8 +1,13 @@
 #![warn(clippy::pedantic)]
 use std::borrow::Cow;
 
-use advent_2023::{BasicSolution, Solution};
+use advent_2023::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, ParserExt, Solution,
+};
 use anyhow::bail;
+use itertools::Itertools;
 use winnow::{
     ascii::{dec_uint, space1},
     combinator::{opt, separated},
@@ -18,17 +23,14 @@ struct Records {
 }
 
 impl BasicSolution for Day {
-    const INPUT: &'static str = include_str!("data.txt");
-    const SAMPLE_INPUT: &'static str = include_str!("sample.txt");
+    const INPUT: Option<&'static str> = embedded_input!(day6);
+    const ANSWERS: Answers = embedded_answers!(day6);
 
     type Shared = Records;
     type Answer = u64;
 
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 288;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 71503;
-
     fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
-        records.parse(input).map_err(anyhow::Error::msg)
+        records.parse_all(input)
     }
 
     fn part_a(records: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
@@ -41,13 +43,16 @@ distances={:?}",
                 records.distances
             );
         }
-        records
-            .times
-            .iter()
-            .zip(&records.distances)
-            .map(|(&time, &distance)| possible_ways_to_win(time, distance))
-            .product::<Option<u64>>()
-            .ok_or_else(|| anyhow::anyhow!("A cast failed"))
+        records.times.iter().zip(&records.distances).try_fold(
+            1u64,
+            |product, (&time, &distance)| {
+                let ways = possible_ways_to_win(time, distance)
+                    .ok_or_else(|| anyhow::anyhow!("A cast failed"))?;
+                product
+                    .checked_mul(ways)
+                    .ok_or_else(|| anyhow::anyhow!("too many ways to win to multiply"))
+            },
+        )
     }
 
     fn part_b(Records { times, distances }: Self::Shared) -> anyhow::Result<Self::Answer> {
@@ -55,6 +60,22 @@ distances={:?}",
         let distance = join_numbers(&distances)?;
         possible_ways_to_win(time, distance).ok_or_else(|| anyhow::anyhow!("A cast failed"))
     }
+
+    /// `size` races, each with a record that can be beaten.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let times = (0..size).map(|_| rng.u64(2..100)).collect_vec();
+        let distances = times
+            .iter()
+            .map(|&time| {
+                let hold = rng.u64(1..time);
+                rng.u64(0..hold * (time - hold))
+            })
+            .collect_vec();
+        let [times_line, distances_line] =
+            [&times, &distances].map(|numbers| numbers.iter().map(|n| format!("{n:>6}")).join(""));
+        let input = format!("Time:    {times_line}\nDistance:{distances_line}\n");
+        Some(Generated::round_trip(input, Records { times, distances }))
+    }
 }
 
 fn possible_ways_to_win(time: u64, record_distance: u64) -> Option<u64> {
@@ -73,7 +94,11 @@ fn possible_ways_to_win(time: u64, record_distance: u64) -> Option<u64> {
     let largest_int_hold_time_to_beet_record: u64 =
         num::cast(largest_hold_time_to_match_record.next_down().floor())?;
 
-    Some(largest_int_hold_time_to_beet_record - smallest_int_hold_time_to_beet_record + 1)
+    // When no hold time beats the record, the largest is below the smallest
+    Some(
+        (largest_int_hold_time_to_beet_record + 1)
+            .saturating_sub(smallest_int_hold_time_to_beet_record),
+    )
 }
 
 fn join_numbers(distances: &[u64]) -> Result<u64, std::num::ParseIntError> {
@@ -103,6 +128,8 @@ fn main() -> anyhow::Result<()> {
     Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -116,4 +143,11 @@ mod tests {
     fn b() -> anyhow::Result<()> {
         Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2023::sample_tests!(Day, day6);
 }
@@ -1,7 +1,11 @@
 #![warn(clippy::pedantic)]
-use std::{borrow::Cow, cmp::Reverse};
+use std::{borrow::Cow, cmp::Reverse, fmt::Write};
 
-use advent_2023::{BasicSolution, Solution};
+use advent_2023::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, ParserExt, Solution,
+};
 use itertools::Itertools;
 use winnow::{ascii::dec_uint, seq, token::any, Parser};
 
@@ -17,20 +21,14 @@ struct Bid {
 type HandType = [u8; 2];
 
 impl BasicSolution for Day {
-    const INPUT: &'static str = include_str!("data.txt");
-    const SAMPLE_INPUT: &'static str = include_str!("sample.txt");
+    const INPUT: Option<&'static str> = embedded_input!(day7);
+    const ANSWERS: Answers = embedded_answers!(day7);
 
     type Shared = Vec<Bid>;
     type Answer = u32;
 
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 6440;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 5905;
-
     fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
-        input
-            .lines()
-            .map(|line| bid.parse(line).map_err(anyhow::Error::msg))
-            .collect()
+        input.lines().map(|line| bid.parse_all(line)).collect()
     }
 
     fn part_a(bids: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
@@ -45,6 +43,23 @@ impl BasicSolution for Day {
             (hand_type_part_b(bid.hand), bid.hand, bid.amount)
         })))
     }
+
+    /// `size` times 10 random hands.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        const CARDS: &[u8; 13] = b"23456789TJQKA";
+        let bids = (0..size * 10)
+            .map(|_| Bid {
+                hand: [(); 5].map(|()| rng.u8(0..13)),
+                amount: rng.u16(1..=1000),
+            })
+            .collect_vec();
+        let mut input = String::new();
+        for bid in &bids {
+            input.extend(bid.hand.map(|card| char::from(CARDS[card as usize])));
+            writeln!(input, " {}", bid.amount).ok()?;
+        }
+        Some(Generated::round_trip(input, bids))
+    }
 }
 
 fn sum_sortable_bids(bid_amounts: impl Iterator<Item = (HandType, [u8; 5], u16)>) -> u32 {
@@ -112,6 +127,8 @@ fn main() -> anyhow::Result<()> {
     Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -125,4 +142,11 @@ mod tests {
     fn b() -> anyhow::Result<()> {
         Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2023::sample_tests!(Day, day7);
 }
@@ -1,9 +1,13 @@
 #![warn(clippy::pedantic)]
 use std::{borrow::Cow, collections::HashMap};
 
-use advent_2023::{BasicSolution, Solution};
+use advent_2023::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, ParserExt, Solution,
+};
 use anyhow::anyhow;
-use itertools::process_results;
+use itertools::{process_results, Itertools};
 use num::Integer;
 use winnow::{
     ascii::alphanumeric1,
@@ -26,18 +30,14 @@ struct Maps<'a> {
 }
 
 impl BasicSolution for Day {
-    const INPUT: &'static str = include_str!("data.txt");
-    const SAMPLE_INPUT: &'static str = include_str!("sample_a.txt");
-    const SAMPLE_INPUT_B: &'static str = include_str!("sample_b.txt");
+    const INPUT: Option<&'static str> = embedded_input!(day8);
+    const ANSWERS: Answers = embedded_answers!(day8);
 
     type Shared = Maps<'static>;
     type Answer = usize;
 
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 2;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 6;
-
     fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
-        maps.parse(input).map_err(anyhow::Error::msg)
+        maps.parse_all(input)
     }
 
     fn part_a(maps: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
@@ -56,6 +56,58 @@ impl BasicSolution for Day {
             },
         )?
     }
+
+    /// Up to 6 ghosts whose paths loop back to their Z node every few times
+    /// through the instructions, which are up to `size` times 3 long. The
+    /// turns not taken lead to other nodes on the same loop. Only checked to
+    /// parse, since the network is a `HashMap`.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        // Node names don't end with A or Z
+        const LETTERS: &[u8; 24] = b"BCDEFGHIJKLMNOPQRSTUVWXY";
+        let instructions = (0..rng.usize(size..=size * 3))
+            .map(|_| rng.bool())
+            .collect_vec();
+        let mut loop_counts = [2, 3, 5, 7, 11, 13, 17, 19, 23];
+        rng.shuffle(&mut loop_counts);
+        let mut node_id = 0;
+        let mut lines = Vec::new();
+        for (ghost, loop_count) in loop_counts.into_iter().take(size.min(6)).enumerate() {
+            let steps = instructions.len() * loop_count;
+            let (start, end) = match ghost {
+                0 => ("AAA".to_string(), "ZZZ".to_string()),
+                _ => (format!("{ghost}{ghost}A"), format!("{ghost}{ghost}Z")),
+            };
+            let mut names = vec![start];
+            names.extend((1..steps).map(|_| {
+                node_id += 1;
+                [node_id / 576, node_id / 24 % 24, node_id % 24]
+                    .map(|i| char::from(LETTERS[i]))
+                    .into_iter()
+                    .collect()
+            }));
+            names.push(end);
+            // The end node turns the same way as the start, back onto the loop
+            for (step, name) in names.iter().enumerate() {
+                let next = &names[step % steps + 1];
+                let other = &names[rng.usize(0..=steps)];
+                let (left, right) = if instructions[step % instructions.len()] {
+                    (other, next)
+                } else {
+                    (next, other)
+                };
+                lines.push(format!("{name} = ({left}, {right})\n"));
+            }
+        }
+        rng.shuffle(&mut lines);
+        let instructions = instructions
+            .iter()
+            .map(|&right| if right { 'R' } else { 'L' })
+            .collect::<String>();
+        Some(Generated::new(format!(
+            "{instructions}\n\n{}",
+            lines.concat()
+        )))
+    }
 }
 
 impl Maps<'_> {
@@ -113,6 +165,8 @@ fn main() -> anyhow::Result<()> {
     Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -126,4 +180,11 @@ mod tests {
     fn b() -> anyhow::Result<()> {
         Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2023::sample_tests!(Day, day8);
 }
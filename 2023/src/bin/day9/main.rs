@@ -1,7 +1,11 @@
 #![warn(clippy::pedantic)]
 use std::borrow::Cow;
 
-use advent_2023::{BasicSolution, Solution};
+use advent_2023::{
+    embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register, Answers, BasicSolution, ParserExt, Solution,
+};
 use anyhow::anyhow;
 use itertools::{iterate, Itertools};
 use winnow::{ascii::dec_int, combinator::separated, Parser};
@@ -9,24 +13,16 @@ use winnow::{ascii::dec_int, combinator::separated, Parser};
 struct Day;
 
 impl BasicSolution for Day {
-    const INPUT: &'static str = include_str!("data.txt");
-    const SAMPLE_INPUT: &'static str = include_str!("sample.txt");
+    const INPUT: Option<&'static str> = embedded_input!(day9);
+    const ANSWERS: Answers = embedded_answers!(day9);
 
     type Shared = Vec<(i32, i32)>;
     type Answer = i32;
 
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 114;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 2;
-
     fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
         input
             .lines()
-            .map(|line| {
-                history
-                    .parse(line)
-                    .map_err(anyhow::Error::msg)
-                    .and_then(extrapolate)
-            })
+            .map(|line| history.parse_all(line).and_then(extrapolate))
             .collect()
     }
 
@@ -37,6 +33,24 @@ impl BasicSolution for Day {
     fn part_b(extrapolations: Self::Shared) -> anyhow::Result<Self::Answer> {
         Ok(extrapolations.iter().map(|(front, _)| front).sum())
     }
+
+    /// `size` times 10 histories of 21 values of polynomials up to degree 5,
+    /// which extrapolate to their values before and after.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let mut input = String::new();
+        let extrapolations = (0..size * 10)
+            .map(|_| {
+                let coefficients = (0..=rng.usize(0..=5))
+                    .map(|_| rng.i32(-5..=5))
+                    .collect_vec();
+                let value = |x: i32| coefficients.iter().fold(0, |value, c| value * x + c);
+                input.push_str(&(0..21).map(value).join(" "));
+                input.push('\n');
+                (value(-1), value(21))
+            })
+            .collect();
+        Some(Generated::round_trip(input, extrapolations))
+    }
 }
 
 fn extrapolate(history: Vec<i32>) -> anyhow::Result<(i32, i32)> {
@@ -66,6 +80,8 @@ fn main() -> anyhow::Result<()> {
     Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -79,4 +95,11 @@ mod tests {
     fn b() -> anyhow::Result<()> {
         Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2023::sample_tests!(Day, day9);
 }
@@ -1,24 +1,23 @@
 use std::borrow::Cow;
 
-use advent_2023::{BasicSolution, Solution};
+use advent_2023::{
+    embedded_answers, embedded_input, register, Answers, BasicSolution, ParserExt, Solution,
+};
 use winnow::{token::rest, Parser};
 
 struct Day;
 
 impl BasicSolution for Day {
-    const INPUT: &'static str = include_str!("data.txt");
-    const SAMPLE_INPUT: &'static str = include_str!("sample.txt");
+    const INPUT: Option<&'static str> = embedded_input!(template);
+    const ANSWERS: Answers = embedded_answers!(template);
 
     type Shared = Vec<&'static str>;
     type Answer = u32;
 
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 0;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 0;
-
     fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
         input
             .lines()
-            .map(|line| line_parser.parse(line).map_err(anyhow::Error::msg))
+            .map(|line| line_parser.parse_all(line))
             .collect()
     }
 
@@ -39,6 +38,8 @@ fn main() -> anyhow::Result<()> {
     Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -52,4 +53,6 @@ mod tests {
     fn b() -> anyhow::Result<()> {
         Day::test_part_b()
     }
+
+    advent_2023::sample_tests!(Day, template);
 }
@@ -1,25 +1,26 @@
-#![feature(iter_map_windows)]
 use std::borrow::Cow;
 
-use advent_2024::{BasicSolution, Solution};
+use advent_2024::{
+    Answers, BasicSolution, ParserExt, Solution, embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register,
+};
+use itertools::Itertools;
 use winnow::{Parser, ascii::dec_int, combinator::separated};
 
 struct Day;
 
 impl BasicSolution for Day {
-    const INPUT: &'static str = include_str!("data.txt");
-    const SAMPLE_INPUT: &'static str = include_str!("sample.txt");
+    const INPUT: Option<&'static str> = embedded_input!(day2);
+    const ANSWERS: Answers = embedded_answers!(day2);
 
     type Shared = Vec<Vec<i8>>;
     type Answer = usize;
 
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 2;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 4;
-
     fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
         input
             .lines()
-            .map(|line| line_parser.parse(line).map_err(anyhow::Error::msg))
+            .map(|line| line_parser.parse_all(line))
             .collect()
     }
 
@@ -33,6 +34,32 @@ impl BasicSolution for Day {
             .filter(|line| is_safe_report_dampened(line))
             .count())
     }
+
+    /// `size` times 10 reports of 5 to 8 levels, that mostly change by
+    /// safe amounts in one direction.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let reports = (0..size * 10)
+            .map(|_| {
+                let direction = if rng.bool() { 1 } else { -1 };
+                let mut level = rng.i8(20..80);
+                (0..rng.usize(5..=8))
+                    .map(|_| {
+                        let change = match rng.u8(0..10) {
+                            0 => rng.i8(-4..=4),
+                            _ => rng.i8(1..=3) * direction,
+                        };
+                        level = (level + change).clamp(1, 99);
+                        level
+                    })
+                    .collect_vec()
+            })
+            .collect_vec();
+        let input = reports
+            .iter()
+            .map(|report| report.iter().join(" ") + "\n")
+            .collect();
+        Some(Generated::round_trip(input, reports))
+    }
 }
 
 fn is_safe_report_dampened(levels: &[i8]) -> bool {
@@ -51,7 +78,8 @@ fn is_safe_report<'a>(levels: impl IntoIterator<Item = &'a i8>) -> bool {
     };
     levels
         .into_iter()
-        .map_windows(|&[a, b]| b - a)
+        .tuple_windows()
+        .map(|(a, b)| b - a)
         .all(is_safe_diff)
 }
 
@@ -63,6 +91,8 @@ fn main() -> anyhow::Result<()> {
     Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -76,4 +106,11 @@ mod tests {
     fn b() -> anyhow::Result<()> {
         Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2024::sample_tests!(Day, day2);
 }
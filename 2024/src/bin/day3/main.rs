@@ -1,6 +1,10 @@
 use std::borrow::Cow;
 
-use advent_2024::{BasicSolution, Solution};
+use advent_2024::{
+    Answers, BasicSolution, Solution, embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register,
+};
 use winnow::{
     Parser,
     ascii::dec_uint,
@@ -11,18 +15,12 @@ use winnow::{
 struct Day;
 
 impl BasicSolution for Day {
-    const INPUT: &'static str = include_str!("data.txt");
-    const SAMPLE_INPUT: &'static str =
-        "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
-    const SAMPLE_INPUT_B: &'static str =
-        "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+    const INPUT: Option<&'static str> = embedded_input!(day3);
+    const ANSWERS: Answers = embedded_answers!(day3);
 
     type Shared = &'static str;
     type Answer = u32;
 
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 161;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 48;
-
     fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
         Ok(input)
     }
@@ -53,6 +51,29 @@ impl BasicSolution for Day {
             })
             .sum())
     }
+
+    /// `size` times 6 lines of corrupted memory, with instructions among
+    /// random characters, some of which almost make up instructions.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        const JUNK: &[u8] = b"mul(,)don't[]{}<> #@!%^&*-+'?:;select from why what who 0123456789";
+        let mut input = String::new();
+        for _ in 0..size * 6 {
+            for _ in 0..rng.usize(20..60) {
+                match rng.u8(0..10) {
+                    0 => input.push_str(if rng.bool() { "do()" } else { "don't()" }),
+                    1..=3 => {
+                        input.push_str(&format!("mul({},{})", rng.u16(1..1000), rng.u16(1..1000)))
+                    }
+                    _ => input.extend(
+                        (0..rng.usize(1..8)).map(|_| char::from(JUNK[rng.usize(0..JUNK.len())])),
+                    ),
+                }
+            }
+            input.push('\n');
+        }
+        let shared = input.clone().leak();
+        Some(Generated::round_trip(input, shared))
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -70,6 +91,8 @@ fn main() -> anyhow::Result<()> {
     Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -83,4 +106,11 @@ mod tests {
     fn b() -> anyhow::Result<()> {
         Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2024::sample_tests!(Day, day3);
 }
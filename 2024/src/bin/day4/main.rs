@@ -1,21 +1,21 @@
-#![feature(array_windows)]
 use std::borrow::Cow;
 
-use advent_2024::{BasicSolution, Solution};
+use advent_2024::{
+    Answers, BasicSolution, Solution, embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register,
+};
 use itertools::izip;
 
 struct Day;
 
 impl BasicSolution for Day {
-    const INPUT: &'static str = include_str!("data.txt");
-    const SAMPLE_INPUT: &'static str = include_str!("sample.txt");
+    const INPUT: Option<&'static str> = embedded_input!(day4);
+    const ANSWERS: Answers = embedded_answers!(day4);
 
     type Shared = Vec<&'static [u8]>;
     type Answer = usize;
 
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 18;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 9;
-
     fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
         Ok(input.lines().map(str::as_bytes).collect())
     }
@@ -59,12 +59,31 @@ impl BasicSolution for Day {
             })
             .count())
     }
+
+    /// A square word search `size` times 10 letters across, of only the
+    /// letters in XMAS.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let side = size * 10;
+        let mut input = String::new();
+        let mut rows = Vec::new();
+        for _ in 0..side {
+            let row = (0..side)
+                .map(|_| ['X', 'M', 'A', 'S'][rng.usize(0..4)])
+                .collect::<String>();
+            input.push_str(&row);
+            input.push('\n');
+            rows.push(row.leak().as_bytes());
+        }
+        Some(Generated::round_trip(input, rows))
+    }
 }
 
 fn main() -> anyhow::Result<()> {
     Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -78,4 +97,11 @@ mod tests {
     fn b() -> anyhow::Result<()> {
         Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2024::sample_tests!(Day, day4);
 }
@@ -1,7 +1,12 @@
 use std::{borrow::Cow, cmp::Ordering};
 
-use advent_2024::{BasicSolution, Solution};
+use advent_2024::{
+    Answers, BasicSolution, ParserExt, Solution, embedded_answers, embedded_input,
+    generate::{Generated, Rng},
+    register,
+};
 use fxhash::FxHashSet;
+use itertools::Itertools;
 use winnow::{
     Parser,
     ascii::dec_uint,
@@ -11,17 +16,14 @@ use winnow::{
 struct Day;
 
 impl BasicSolution for Day {
-    const INPUT: &'static str = include_str!("data.txt");
-    const SAMPLE_INPUT: &'static str = include_str!("sample.txt");
+    const INPUT: Option<&'static str> = embedded_input!(day5);
+    const ANSWERS: Answers = embedded_answers!(day5);
 
     type Shared = SafetyManual;
     type Answer = u32;
 
-    const SAMPLE_ANSWER_A: Self::TestAnswer = 143;
-    const SAMPLE_ANSWER_B: Self::TestAnswer = 123;
-
     fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
-        safety_manual.parse(input).map_err(anyhow::Error::msg)
+        safety_manual.parse_all(input)
     }
 
     fn part_a(safety_manual: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
@@ -54,6 +56,32 @@ impl BasicSolution for Day {
             })
             .sum())
     }
+
+    /// Rules that order every pair of up to `size` times 5 pages, and
+    /// `size` times 10 updates, about half of which are in order. Only
+    /// checked to parse, since the rules are kept in a hash set.
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        let mut pages = (10..100).collect_vec();
+        rng.shuffle(&mut pages);
+        pages.truncate((size * 5).max(5));
+        let mut rules = pages
+            .iter()
+            .tuple_combinations()
+            .map(|(before, after)| format!("{before}|{after}\n"))
+            .collect_vec();
+        rng.shuffle(&mut rules);
+        let updates = (0..size * 10).map(|_| {
+            let mut update = pages.clone();
+            rng.shuffle(&mut update);
+            update.truncate(rng.usize(1..=pages.len().min(23)) | 1);
+            if rng.bool() {
+                update.sort_unstable_by_key(|page| pages.iter().position(|p| p == page));
+            }
+            update.iter().join(",") + "\n"
+        });
+        let input = format!("{}\n{}", rules.concat(), updates.collect::<String>());
+        Some(Generated::new(input))
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -84,6 +112,8 @@ fn main() -> anyhow::Result<()> {
     Day::main()
 }
 
+register!(Day);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -97,4 +127,11 @@ mod tests {
     fn b() -> anyhow::Result<()> {
         Day::test_part_b()
     }
+
+    #[test]
+    fn generated() -> anyhow::Result<()> {
+        Day::test_generated()
+    }
+
+    advent_2024::sample_tests!(Day, day5);
 }
@@ -0,0 +1,23 @@
+//! Every day's binary compiled into the library, so the workspace runner can
+//! call them in-process.
+//!
+//! Each binary's `main` and crate-level attributes only apply when it's built
+//! on its own, and lint levels follow the binary's rather than the library's.
+#![allow(dead_code, unused_attributes, clippy::pedantic)]
+use crate::Registered;
+
+#[path = "bin/day2/main.rs"]
+mod day2;
+#[path = "bin/day3/main.rs"]
+mod day3;
+#[path = "bin/day4/main.rs"]
+mod day4;
+#[path = "bin/day5/main.rs"]
+mod day5;
+
+pub const DAYS: &[(u8, Registered)] = &[
+    (2, day2::REGISTERED),
+    (3, day3::REGISTERED),
+    (4, day4::REGISTERED),
+    (5, day5::REGISTERED),
+];
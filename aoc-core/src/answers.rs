@@ -0,0 +1,60 @@
+//! Each day's real-input answers, recorded in an `answers.toml` next to its
+//! `data.txt` so the tests can tell when a refactor changes them.
+use std::fmt::Display;
+
+use anyhow::Context;
+
+/// A day's `answers.toml`, see [`embedded_answers!`](crate::embedded_answers).
+#[derive(Debug, Clone, Copy)]
+pub struct Answers {
+    /// Where `--record` writes the answers
+    pub path: &'static str,
+    /// The file's contents at build time, if it existed
+    pub recorded: Option<&'static str>,
+}
+
+impl Answers {
+    /// The recorded answer to `part`, `"a"` or `"b"`.
+    pub fn get(&self, part: &str) -> anyhow::Result<Option<String>> {
+        let Some(recorded) = self.recorded else {
+            return Ok(None);
+        };
+        let table: toml::Table = recorded
+            .parse()
+            .with_context(|| format!("failed to parse {}", self.path))?;
+        Ok(table.get(part).map(|answer| match answer {
+            toml::Value::String(answer) => answer.clone(),
+            answer => answer.to_string(),
+        }))
+    }
+
+    /// Checks `answer` against the recorded answer to `part`.
+    ///
+    /// # Panics
+    ///
+    /// If they differ, the same way the sample answers are asserted.
+    pub fn check(&self, part: &str, answer: &impl Display) -> anyhow::Result<()> {
+        if let Some(expected) = self.get(part)? {
+            assert_eq!(
+                answer.to_string(),
+                expected,
+                "part {part} no longer matches the answer recorded in {}",
+                self.path
+            );
+        }
+        Ok(())
+    }
+
+    /// Writes the answers to `answers.toml`, keeping the recorded answer to
+    /// a part that wasn't run.
+    pub fn record(&self, a: Option<String>, b: Option<String>) -> anyhow::Result<()> {
+        let mut table = toml::Table::new();
+        for (part, answer) in [("a", a), ("b", b)] {
+            if let Some(answer) = answer.map_or_else(|| self.get(part), |a| Ok(Some(a)))? {
+                table.insert(part.to_owned(), toml::Value::String(answer));
+            }
+        }
+        std::fs::write(self.path, table.to_string())
+            .with_context(|| format!("failed to write {}", self.path))
+    }
+}
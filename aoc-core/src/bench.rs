@@ -0,0 +1,260 @@
+//! The `bench` subcommand: runs a day repeatedly after a warm-up and reports
+//! statistics for `shared` and each part separately.
+//!
+//! The rows it saves to `timings.csv` are timed differently, by running the
+//! day's binary as a fresh process, like hyperfine did for the first
+//! baselines. That way saved rows and the runner's `perf` check stay
+//! comparable with the ones that were already there.
+use std::{
+    borrow::Cow,
+    fmt::Write,
+    path::Path,
+    process::{Command, Stdio},
+    time::Instant,
+};
+
+use anyhow::{bail, Context};
+
+use crate::Solution;
+
+#[derive(Debug)]
+pub(crate) struct BenchArgs {
+    pub(crate) runs: usize,
+    pub(crate) warmup: usize,
+    /// The year's `timings.csv`, to record the results in
+    pub(crate) save: Option<String>,
+}
+
+impl Default for BenchArgs {
+    fn default() -> Self {
+        Self {
+            runs: 10,
+            warmup: 3,
+            save: None,
+        }
+    }
+}
+
+/// Summary statistics of a step's timings, in milliseconds.
+#[derive(Debug, Clone, Copy)]
+struct Stats {
+    min: f64,
+    median: f64,
+    mean: f64,
+    stddev: f64,
+}
+
+impl Stats {
+    #[allow(clippy::cast_precision_loss)]
+    fn new(samples: &[f64]) -> Self {
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let len = sorted.len() as f64;
+        let mean = sorted.iter().sum::<f64>() / len;
+        let variance = sorted.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / len;
+        Self {
+            min: sorted[0],
+            median: median(samples),
+            mean,
+            stddev: variance.sqrt(),
+        }
+    }
+}
+
+/// The median of some timings.
+///
+/// # Panics
+///
+/// If there are none.
+#[must_use]
+pub fn median(samples: &[f64]) -> f64 {
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        f64::midpoint(sorted[mid - 1], sorted[mid])
+    } else {
+        sorted[mid]
+    }
+}
+
+/// Times `runs` runs of `command` after `warmup` runs, in milliseconds,
+/// starting a fresh process for each. Startup counts, as it did in the
+/// hyperfine measurements the `timings.csv` baselines started out as.
+///
+/// # Errors
+///
+/// If the command can't be started or doesn't succeed.
+pub fn time_process(command: &mut Command, runs: usize, warmup: usize) -> anyhow::Result<Vec<f64>> {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    let program = command.get_program().to_owned();
+    let mut times = Vec::with_capacity(runs);
+    for i in 0..warmup + runs {
+        let start = Instant::now();
+        let status = command
+            .status()
+            .with_context(|| format!("failed to start {}", program.display()))?;
+        let elapsed = elapsed_ms(start);
+        if !status.success() {
+            bail!(
+                "{} failed with {status}, run it directly to see why",
+                program.display()
+            );
+        }
+        if i >= warmup {
+            times.push(elapsed);
+        }
+    }
+    Ok(times)
+}
+
+/// Timings of each run, in milliseconds.
+#[derive(Debug, Default)]
+struct Samples {
+    shared: Vec<f64>,
+    a: Vec<f64>,
+    b: Vec<f64>,
+}
+
+pub(crate) fn bench<T: Solution + ?Sized>(
+    input: &'static str,
+    part: Option<&str>,
+    args: &BenchArgs,
+) -> anyhow::Result<()> {
+    if args.runs == 0 {
+        bail!("--runs should be at least 1");
+    }
+    for _ in 0..args.warmup {
+        run_once::<T>(input, part, &mut Samples::default())?;
+    }
+    let mut samples = Samples::default();
+    for _ in 0..args.runs {
+        run_once::<T>(input, part, &mut samples)?;
+    }
+    println!(
+        "{} runs after {} warm-up runs, in ms:",
+        args.runs, args.warmup
+    );
+    println!(
+        "{:8}{:>10}{:>10}{:>10}{:>10}",
+        "", "min", "median", "mean", "stddev"
+    );
+    for (name, times) in [
+        ("shared", &samples.shared),
+        ("a", &samples.a),
+        ("b", &samples.b),
+    ] {
+        if times.is_empty() {
+            continue;
+        }
+        let Stats {
+            min,
+            median,
+            mean,
+            stddev,
+        } = Stats::new(times);
+        println!("{name:8}{min:>10.3}{median:>10.3}{mean:>10.3}{stddev:>10.3}");
+    }
+    if let Some(path) = &args.save {
+        save(Path::new(path), part, args)?;
+        println!("saved to {path}");
+    }
+    Ok(())
+}
+
+fn run_once<T: Solution + ?Sized>(
+    input: &'static str,
+    part: Option<&str>,
+    samples: &mut Samples,
+) -> anyhow::Result<()> {
+    let start = Instant::now();
+    let shared = T::shared(input)?;
+    samples.shared.push(elapsed_ms(start));
+    if part != Some("b") {
+        let start = Instant::now();
+        T::part_a(Cow::Borrowed(&shared))?;
+        samples.a.push(elapsed_ms(start));
+    }
+    if part != Some("a") {
+        let start = Instant::now();
+        T::part_b(shared)?;
+        samples.b.push(elapsed_ms(start));
+    }
+    Ok(())
+}
+
+fn elapsed_ms(start: Instant) -> f64 {
+    start.elapsed().as_secs_f64() * 1000.
+}
+
+/// Times the day's binary with [`time_process`] and writes its rows into
+/// `timings.csv`, replacing the day's previous rows if it has any.
+///
+/// Both existing layouts are supported: `day,time_ms` with the whole day's
+/// median rounded up to a millisecond, and the hyperfine-style
+/// `problem,mean,stddev,median` with a row per part.
+fn save(path: &Path, part: Option<&str>, args: &BenchArgs) -> anyhow::Result<()> {
+    let day = day_number()?;
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => "day,time_ms\n".to_owned(),
+        Err(e) => return Err(e).with_context(|| format!("failed to read {}", path.display())),
+    };
+    let mut lines = contents.lines();
+    let header = lines.next().unwrap_or("day,time_ms");
+    let exe = std::env::current_exe().context("can't find the day's binary")?;
+    let time =
+        |parts: &[&str]| time_process(Command::new(&exe).args(parts), args.runs, args.warmup);
+    let mut new_rows = String::new();
+    match header {
+        "day,time_ms" => {
+            if part.is_some() {
+                bail!("{} times whole days, leave out the part", path.display());
+            }
+            let total = median(&time(&[])?);
+            writeln!(new_rows, "{day:02},{:03.0}", total.ceil())?;
+        }
+        "problem,mean,stddev,median" => {
+            for part in ["a", "b"]
+                .into_iter()
+                .filter(|&p| part.is_none_or(|part| part == p))
+            {
+                let Stats {
+                    median,
+                    mean,
+                    stddev,
+                    ..
+                } = Stats::new(&time(&[part])?);
+                writeln!(
+                    new_rows,
+                    "{day:02}-{part},{mean:.2},{stddev:.3},{median:.2}"
+                )?;
+            }
+        }
+        _ => bail!("{} has an unknown header {header:?}", path.display()),
+    }
+    let replaced: Vec<_> = new_rows
+        .lines()
+        .filter_map(|row| row.split(',').next())
+        .collect();
+    let mut rows: Vec<_> = lines
+        .filter(|row| !replaced.contains(&row.split(',').next().unwrap_or_default()))
+        .map(|row| format!("{row}\n"))
+        .collect();
+    rows.push(new_rows);
+    rows.sort();
+    std::fs::write(path, format!("{header}\n{}", rows.concat()))
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// Days are built as binaries named `day<n>`.
+fn day_number() -> anyhow::Result<u8> {
+    let exe = std::env::args().next().unwrap_or_default();
+    Path::new(&exe)
+        .file_stem()
+        .and_then(|stem| stem.to_str()?.strip_prefix("day")?.parse().ok())
+        .with_context(|| format!("can't tell which day {exe:?} is"))
+}
@@ -0,0 +1,471 @@
+//! Branch and bound: searching a tree of partial solutions for the one with
+//! the highest score, skipping the subtrees whose bound says they can't beat
+//! the best solution found so far.
+//!
+//! A day describes its tree with [`Problem`], and [`solve`] or
+//! [`solve_parallel`] search it.
+use std::{
+    collections::{hash_map::Entry, BinaryHeap, VecDeque},
+    hash::Hash,
+    num::NonZero,
+    ops::AddAssign,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+use fxhash::FxHashMap;
+
+/// A tree of partial solutions to search.
+pub trait Problem {
+    type Node;
+    type Score: Copy + Ord;
+    /// Identifies the nodes [`Problem::dominance`] compares, `()` if it isn't
+    /// used.
+    type Key: Eq + Hash;
+
+    /// The score of `node`, if it's a solution.
+    fn score(&self, node: &Self::Node) -> Option<Self::Score>;
+
+    /// At least the score of every solution in `node`'s subtree.
+    fn bound(&self, node: &Self::Node) -> Self::Score;
+
+    /// `node`'s children.
+    fn branch(&self, node: &Self::Node) -> impl IntoIterator<Item = Self::Node>;
+
+    /// Whether a subtree with `bound` is worth searching when the best score
+    /// so far is `best`. Anything but the default of whether it could beat
+    /// `best` may miss the best solution, and should only get more likely to
+    /// hold as `bound` grows.
+    fn promising(&self, bound: Self::Score, best: Self::Score) -> bool {
+        bound > best
+    }
+
+    /// A key and a value for `node`, when the nodes with the same key have
+    /// the same subtrees apart from what they carry over from their value.
+    /// Only the first node with each key is searched, unless a later one has
+    /// a higher value.
+    fn dominance(&self, _node: &Self::Node) -> Option<(Self::Key, Self::Score)> {
+        None
+    }
+}
+
+/// The order [`solve`] searches the nodes in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    /// Each child's subtree in turn, starting with the highest bound. It
+    /// only remembers the nodes on the way down and their siblings.
+    DepthFirst,
+    /// The node with the highest bound of all the ones found so far. It
+    /// explores the fewest nodes, but may remember a lot of them.
+    BestFirst,
+}
+
+/// How many nodes a search went through.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Scored and branched
+    pub explored: u64,
+    /// Skipped because their bound wasn't promising
+    pub pruned: u64,
+    /// Skipped because a node with the same key had a value at least as high
+    pub dominated: u64,
+}
+
+impl AddAssign for Stats {
+    fn add_assign(&mut self, rhs: Self) {
+        self.explored += rhs.explored;
+        self.pruned += rhs.pruned;
+        self.dominated += rhs.dominated;
+    }
+}
+
+/// What a search found.
+#[derive(Debug, Clone)]
+pub struct Outcome<S, N> {
+    /// The best score and a node with it, if any node was a solution
+    pub best: Option<(S, N)>,
+    pub stats: Stats,
+}
+
+impl<S: Copy, N> Outcome<S, N> {
+    #[must_use]
+    pub fn score(&self) -> Option<S> {
+        self.best.as_ref().map(|&(score, _)| score)
+    }
+}
+
+/// Searches the tree under `root` in `order`.
+pub fn solve<P: Problem>(problem: &P, root: P::Node, order: Order) -> Outcome<P::Score, P::Node> {
+    let mut searcher = Searcher::new(problem);
+    let root = (problem.bound(&root), root);
+    match order {
+        Order::DepthFirst => searcher.depth_first(root),
+        Order::BestFirst => searcher.best_first(root),
+    }
+    searcher.outcome()
+}
+
+/// Searches the tree under `root` depth first, with the subtrees near the
+/// root shared out between a thread per core. The threads start from the
+/// best score found on the way to those subtrees, tell each other about
+/// better ones as soon as they find them, and have separate dominance keys.
+///
+/// # Panics
+///
+/// If `problem` panics in one of the threads.
+pub fn solve_parallel<P>(problem: &P, root: P::Node) -> Outcome<P::Score, P::Node>
+where
+    P: Problem + Sync,
+    P::Node: Send,
+    P::Score: Send,
+    P::Key: Send,
+{
+    let threads = thread::available_parallelism().map_or(1, NonZero::get);
+    let mut searcher = Searcher::new(problem);
+    // Enough subtrees that a thread finishing early can pick up another
+    let mut frontier = VecDeque::from([(problem.bound(&root), root)]);
+    while frontier.len() < threads * 8 {
+        let Some((bound, node)) = frontier.pop_front() else {
+            break;
+        };
+        if searcher.promising(bound) {
+            frontier.extend(searcher.expand(node));
+        } else {
+            searcher.stats.pruned += 1;
+        }
+    }
+    let mut frontier = Vec::from(frontier);
+    // Popped from the end, so the highest bounds are searched first
+    frontier.sort_unstable_by_key(|&(bound, _)| bound);
+    let frontier = &Mutex::new(frontier);
+    let seed = searcher.threshold;
+    let shared_best = &SharedBest::new(seed);
+    let searchers: Vec<_> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(move || {
+                    let mut searcher = Searcher::new(problem);
+                    searcher.threshold = seed;
+                    searcher.shared_best = Some(shared_best);
+                    while let Some(subtree) = frontier.lock().expect("not poisoned").pop() {
+                        searcher.depth_first(subtree);
+                    }
+                    searcher
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("the search doesn't panic"))
+            .collect()
+    });
+    for other in searchers {
+        searcher.stats += other.stats;
+        if let Some((score, node)) = other.best {
+            searcher.improve(score, node);
+        }
+    }
+    searcher.outcome()
+}
+
+/// The best score any of [`solve_parallel`]'s threads found.
+struct SharedBest<S> {
+    score: Mutex<Option<S>>,
+    /// Bumped whenever `score` goes up, so the threads only need to lock it
+    /// when there's something new
+    version: AtomicU64,
+}
+
+impl<S: Copy + Ord> SharedBest<S> {
+    fn new(score: Option<S>) -> Self {
+        Self {
+            score: Mutex::new(score),
+            version: AtomicU64::new(0),
+        }
+    }
+
+    fn publish(&self, score: S) {
+        let mut best = self.score.lock().expect("not poisoned");
+        if best.is_none_or(|best| score > best) {
+            *best = Some(score);
+            self.version.fetch_add(1, Ordering::Release);
+        }
+    }
+
+    /// The best score, if there is one and it changed since `seen`, which
+    /// is updated.
+    fn changed_since(&self, seen: &mut u64) -> Option<S> {
+        let version = self.version.load(Ordering::Acquire);
+        if version == *seen {
+            return None;
+        }
+        *seen = version;
+        *self.score.lock().expect("not poisoned")
+    }
+}
+
+struct Searcher<'a, P: Problem> {
+    problem: &'a P,
+    best: Option<(P::Score, P::Node)>,
+    /// The best score found, which may be another thread's
+    threshold: Option<P::Score>,
+    /// Where the threads of [`solve_parallel`] swap best scores
+    shared_best: Option<&'a SharedBest<P::Score>>,
+    /// The version of `shared_best` that `threshold` has caught up with
+    seen: u64,
+    values: FxHashMap<P::Key, P::Score>,
+    stats: Stats,
+}
+
+impl<'a, P: Problem> Searcher<'a, P> {
+    fn new(problem: &'a P) -> Self {
+        Self {
+            problem,
+            best: None,
+            threshold: None,
+            shared_best: None,
+            seen: 0,
+            values: FxHashMap::default(),
+            stats: Stats::default(),
+        }
+    }
+
+    fn outcome(self) -> Outcome<P::Score, P::Node> {
+        Outcome {
+            best: self.best,
+            stats: self.stats,
+        }
+    }
+
+    fn promising(&self, bound: P::Score) -> bool {
+        self.threshold
+            .is_none_or(|best| self.problem.promising(bound, best))
+    }
+
+    fn improve(&mut self, score: P::Score, node: P::Node) {
+        if self.best.as_ref().is_none_or(|&(best, _)| score > best) {
+            self.best = Some((score, node));
+        }
+        if self.threshold.is_none_or(|best| score > best) {
+            self.threshold = Some(score);
+            if let Some(shared_best) = self.shared_best {
+                shared_best.publish(score);
+            }
+        }
+    }
+
+    /// Scores `node`, and returns its children that are worth searching with
+    /// their bounds.
+    fn expand(&mut self, node: P::Node) -> Vec<(P::Score, P::Node)> {
+        self.stats.explored += 1;
+        let mut children = Vec::new();
+        for child in self.problem.branch(&node) {
+            if let Some((key, value)) = self.problem.dominance(&child) {
+                match self.values.entry(key) {
+                    Entry::Occupied(entry) if *entry.get() >= value => {
+                        self.stats.dominated += 1;
+                        continue;
+                    }
+                    Entry::Occupied(mut entry) => {
+                        entry.insert(value);
+                    }
+                    Entry::Vacant(entry) => {
+                        entry.insert(value);
+                    }
+                }
+            }
+            let bound = self.problem.bound(&child);
+            if self.promising(bound) {
+                children.push((bound, child));
+            } else {
+                self.stats.pruned += 1;
+            }
+        }
+        if let Some(score) = self.problem.score(&node) {
+            self.improve(score, node);
+        }
+        children
+    }
+
+    /// Searches `root`'s subtree, catching up with the other threads' best
+    /// score whenever it goes up if there are any.
+    fn depth_first(&mut self, root: (P::Score, P::Node)) {
+        let mut stack = vec![root];
+        while let Some((bound, node)) = stack.pop() {
+            if let Some(shared) = self
+                .shared_best
+                .and_then(|shared_best| shared_best.changed_since(&mut self.seen))
+            {
+                self.threshold = self.threshold.max(Some(shared));
+            }
+            // The best score may have gone up since it was pushed
+            if !self.promising(bound) {
+                self.stats.pruned += 1;
+                continue;
+            }
+            let mut children = self.expand(node);
+            // Popped from the end, so the highest bound is searched first
+            children.sort_unstable_by_key(|&(bound, _)| bound);
+            stack.extend(children);
+        }
+    }
+
+    fn best_first(&mut self, root: (P::Score, P::Node)) {
+        let mut queue = BinaryHeap::from([ByBound(root)]);
+        while let Some(ByBound((bound, node))) = queue.pop() {
+            if !self.promising(bound) {
+                // Neither is anything left in the queue
+                self.stats.pruned += queue.len() as u64 + 1;
+                break;
+            }
+            queue.extend(self.expand(node).into_iter().map(ByBound));
+        }
+    }
+}
+
+/// Orders nodes by their bound alone.
+struct ByBound<S, N>((S, N));
+
+impl<S: Ord, N> PartialEq for ByBound<S, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 .0 == other.0 .0
+    }
+}
+
+impl<S: Ord, N> Eq for ByBound<S, N> {}
+
+impl<S: Ord, N> PartialOrd for ByBound<S, N> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S: Ord, N> Ord for ByBound<S, N> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0 .0.cmp(&other.0 .0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Picking items to fit in a knapsack, as the index of the next item to
+    /// decide on, the weight left and the value so far.
+    struct Knapsack {
+        /// Weights and values, by decreasing value per weight
+        items: Vec<(u32, u32)>,
+        prune: bool,
+        dominance: bool,
+    }
+
+    type Node = (usize, u32, u32);
+
+    impl Problem for Knapsack {
+        type Node = Node;
+        type Score = u32;
+        type Key = (usize, u32);
+
+        fn score(&self, &(_, _, value): &Node) -> Option<u32> {
+            Some(value)
+        }
+
+        /// Filling the room left with fractions of the items
+        fn bound(&self, &(next, mut room, value): &Node) -> u32 {
+            let mut bound = value;
+            for &(weight, item_value) in &self.items[next..] {
+                let taken = weight.min(room);
+                bound += (item_value * taken).div_ceil(weight);
+                room -= taken;
+            }
+            bound
+        }
+
+        fn branch(&self, &(next, room, value): &Node) -> impl IntoIterator<Item = Node> {
+            let skip = (next < self.items.len()).then_some((next + 1, room, value));
+            let take = self.items.get(next).and_then(|&(weight, item_value)| {
+                Some((next + 1, room.checked_sub(weight)?, value + item_value))
+            });
+            take.into_iter().chain(skip)
+        }
+
+        fn promising(&self, bound: u32, best: u32) -> bool {
+            !self.prune || bound > best
+        }
+
+        fn dominance(&self, &(next, room, value): &Node) -> Option<((usize, u32), u32)> {
+            self.dominance.then_some(((next, room), value))
+        }
+    }
+
+    fn knapsack(prune: bool, dominance: bool) -> Knapsack {
+        let mut items: Vec<_> = (1..=16).map(|i| (i * 7 % 11 + 1, i * 5 % 13 + 1)).collect();
+        items.sort_by(|a, b| (b.1 * a.0).cmp(&(a.1 * b.0)));
+        Knapsack {
+            items,
+            prune,
+            dominance,
+        }
+    }
+
+    fn exhaustive(items: &[(u32, u32)], room: u32) -> u32 {
+        match items {
+            [] => 0,
+            [(weight, value), rest @ ..] => {
+                let skip = exhaustive(rest, room);
+                match room.checked_sub(*weight) {
+                    Some(room) => skip.max(value + exhaustive(rest, room)),
+                    None => skip,
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn orders_agree() {
+        let problem = knapsack(true, false);
+        let expected = exhaustive(&problem.items, 30);
+        let depth_first = solve(&problem, (0, 30, 0), Order::DepthFirst);
+        let best_first = solve(&problem, (0, 30, 0), Order::BestFirst);
+        let parallel = solve_parallel(&problem, (0, 30, 0));
+        for outcome in [&depth_first, &best_first, &parallel] {
+            assert_eq!(outcome.score(), Some(expected));
+            let (_, node) = outcome.best.unwrap();
+            assert_eq!(problem.score(&node), Some(expected));
+        }
+        // Bounding does cut the tree down from its 2^16 leaves
+        assert!(
+            depth_first.stats.explored < 1 << 12,
+            "{:?}",
+            depth_first.stats
+        );
+        assert!(best_first.stats.explored <= depth_first.stats.explored);
+        assert_eq!(depth_first.stats.dominated, 0);
+    }
+
+    #[test]
+    fn shared_best_only_goes_up() {
+        let shared_best = SharedBest::new(Some(5));
+        let mut seen = 0;
+        assert_eq!(shared_best.changed_since(&mut seen), None);
+        shared_best.publish(3);
+        assert_eq!(shared_best.changed_since(&mut seen), None);
+        shared_best.publish(8);
+        assert_eq!(shared_best.changed_since(&mut seen), Some(8));
+        assert_eq!(shared_best.changed_since(&mut seen), None);
+    }
+
+    #[test]
+    fn dominance() {
+        let expected = exhaustive(&knapsack(false, false).items, 30);
+        let everything = solve(&knapsack(false, false), (0, 30, 0), Order::DepthFirst);
+        assert_eq!(everything.score(), Some(expected));
+        let outcome = solve(&knapsack(false, true), (0, 30, 0), Order::DepthFirst);
+        assert_eq!(outcome.score(), Some(expected));
+        assert!(outcome.stats.dominated > 0);
+        assert!(outcome.stats.explored < everything.stats.explored / 4);
+    }
+}
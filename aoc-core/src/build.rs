@@ -0,0 +1,111 @@
+//! The year crates' build script.
+//!
+//! Copies each day's `data.txt` and `answers.toml` into `OUT_DIR` so
+//! `embedded_input!` and `embedded_answers!` can include them, writing empty
+//! placeholders for days without them. That way the crate builds on a fresh
+//! checkout, where no puzzle inputs are committed.
+//!
+//! Also generates the tests that `sample_tests!` includes, from each day's
+//! `sample*.txt` files and `samples.toml`.
+use std::{
+    env,
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Runs the build script, from the year crate's `build.rs`.
+///
+/// # Panics
+///
+/// If `src/bin` can't be read, `OUT_DIR` can't be written, or a day's
+/// `samples.toml` is invalid.
+pub fn main() {
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").expect("cargo sets OUT_DIR"));
+    let manifest_dir =
+        PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").expect("cargo sets CARGO_MANIFEST_DIR"));
+    for dir in ["inputs", "answers", "samples"] {
+        fs::create_dir_all(out_dir.join(dir)).expect("OUT_DIR should be writable");
+    }
+    println!("cargo::rerun-if-changed=src/bin");
+    for entry in fs::read_dir("src/bin").expect("src/bin should exist") {
+        let day_dir = entry.expect("src/bin should be readable").path();
+        let Some(name) = day_dir.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if !day_dir.is_dir() {
+            continue;
+        }
+        for (file, embedded) in [
+            ("data.txt", format!("inputs/{name}.txt")),
+            ("answers.toml", format!("answers/{name}.toml")),
+        ] {
+            let contents = fs::read_to_string(day_dir.join(file)).unwrap_or_default();
+            fs::write(out_dir.join(embedded), contents).expect("OUT_DIR should be writable");
+        }
+        let tests = sample_tests(&manifest_dir.join(&day_dir));
+        fs::write(out_dir.join(format!("samples/{name}.rs")), tests)
+            .expect("OUT_DIR should be writable");
+    }
+}
+
+/// A test per part of each sample with an expected answer.
+fn sample_tests(day_dir: &Path) -> String {
+    let samples_toml = day_dir.join("samples.toml");
+    let expected: toml::Table = match fs::read_to_string(&samples_toml) {
+        Ok(contents) => contents
+            .parse()
+            .unwrap_or_else(|e| panic!("{} is invalid: {e}", samples_toml.display())),
+        Err(_) => toml::Table::new(),
+    };
+    let mut samples: Vec<_> = fs::read_dir(day_dir)
+        .expect("the day's directory should be readable")
+        .filter_map(|entry| {
+            let file_name = entry.ok()?.file_name().into_string().ok()?;
+            let stem = file_name.strip_suffix(".txt")?;
+            stem.starts_with("sample").then(|| stem.to_owned())
+        })
+        .collect();
+    samples.sort();
+    for stem in expected.keys() {
+        assert!(
+            samples.contains(stem),
+            "{} has answers for {stem}.txt, which doesn't exist",
+            samples_toml.display()
+        );
+    }
+    let mut tests = String::new();
+    for stem in samples {
+        let Some(answers) = expected.get(&stem) else {
+            println!(
+                "cargo::warning={} has no answers for {stem}.txt",
+                samples_toml.display()
+            );
+            continue;
+        };
+        let path = day_dir.join(format!("{stem}.txt"));
+        let path = path.to_str().expect("sample paths should be UTF-8");
+        let ident: String = stem
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        for part in ["a", "b"] {
+            let Some(answer) = answers.get(part) else {
+                continue;
+            };
+            let answer = match answer {
+                toml::Value::String(answer) => answer.clone(),
+                answer => answer.to_string(),
+            };
+            writeln!(
+                tests,
+                "#[test]\n\
+                 fn {part}_{ident}() -> SampleResult {{\n    \
+                     test_sample::<SampleSolution>({part:?}, include_str!({path:?}), {answer:?})\n\
+                 }}\n"
+            )
+            .expect("writing to a String can't fail");
+        }
+    }
+    tests
+}
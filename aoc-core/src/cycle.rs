@@ -0,0 +1,118 @@
+//! Simulations that settle into a loop, so the state after far more steps
+//! than could be simulated can be worked out from the first time around.
+use std::{
+    collections::hash_map::Entry,
+    hash::Hash,
+    ops::{Add, Mul, Sub},
+};
+
+use fxhash::FxHashMap;
+
+/// The states repeat every `length` steps, starting after `start` steps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<M> {
+    pub start: usize,
+    pub length: usize,
+    /// The metric after each step up to going around once, both included
+    metrics: Vec<M>,
+}
+
+/// Steps `state` until it has the same key as an earlier state, keeping
+/// track of `metric` on the way. The key should tell apart any states whose
+/// next steps differ. Gives up after `max_steps`.
+pub fn detect<S, K: Eq + Hash, M>(
+    mut state: S,
+    mut step: impl FnMut(&mut S),
+    mut key: impl FnMut(&S) -> K,
+    mut metric: impl FnMut(&S) -> M,
+    max_steps: usize,
+) -> Option<Cycle<M>> {
+    let mut seen = FxHashMap::default();
+    let mut metrics = Vec::new();
+    for steps in 0..=max_steps {
+        metrics.push(metric(&state));
+        match seen.entry(key(&state)) {
+            Entry::Occupied(entry) => {
+                let start = *entry.get();
+                return Some(Cycle {
+                    start,
+                    length: steps - start,
+                    metrics,
+                });
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(steps);
+            }
+        }
+        if steps < max_steps {
+            step(&mut state);
+        }
+    }
+    None
+}
+
+impl<M> Cycle<M> {
+    /// The step before the first time around the cycle ends that's in the
+    /// same state as after `steps` steps.
+    #[must_use]
+    pub fn equivalent_step(&self, steps: usize) -> usize {
+        if steps < self.start {
+            steps
+        } else {
+            self.start + (steps - self.start) % self.length
+        }
+    }
+
+    /// The metric after `steps`, assuming it changes by the same amount each
+    /// time around the cycle. That's `None` if the number of times around
+    /// doesn't fit in `M`.
+    #[must_use]
+    pub fn extrapolate(&self, steps: usize) -> Option<M>
+    where
+        M: Copy + Add<Output = M> + Sub<Output = M> + Mul<Output = M> + TryFrom<usize>,
+    {
+        if steps < self.metrics.len() {
+            return Some(self.metrics[steps]);
+        }
+        let times_around = M::try_from((steps - self.start) / self.length).ok()?;
+        let per_cycle = self.metrics[self.start + self.length] - self.metrics[self.start];
+        Some(self.metrics[self.equivalent_step(steps)] + per_cycle * times_around)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Squaring modulo 1000 with an offset, and the running total.
+    fn step((x, total): &mut (i64, i64)) {
+        *x = (*x * *x + 7) % 1000;
+        *total += *x - 400;
+    }
+
+    #[test]
+    fn extrapolates() {
+        let cycle = detect((3, 0), step, |&(x, _)| x, |&(_, total)| total, 1000).unwrap();
+        assert!(cycle.start > 0 && cycle.length > 1, "{cycle:?}");
+        let mut state = (3, 0);
+        for steps in 0..3000 {
+            assert_eq!(
+                cycle.extrapolate(steps),
+                Some(state.1),
+                "after {steps} steps"
+            );
+            let mut equivalent = (3, 0);
+            (0..cycle.equivalent_step(steps)).for_each(|_| step(&mut equivalent));
+            assert_eq!(equivalent.0, state.0);
+            step(&mut state);
+        }
+    }
+
+    #[test]
+    fn gives_up() {
+        assert_eq!(detect(0_u32, |x| *x += 1, |&x| x, |&x| x, 100), None);
+        let cycle = detect(0_u32, |x| *x = (*x + 1) % 101, |&x| x, |&x| x, 101).unwrap();
+        assert_eq!((cycle.start, cycle.length), (0, 101));
+        assert_eq!(cycle.extrapolate(1_000_000), Some(1_000_000 % 101));
+    }
+}
@@ -0,0 +1,200 @@
+//! Parse errors that point at where in the day's input parsing stopped.
+use std::fmt::{self, Display};
+
+/// A parse failure, shown as its position in the input and the offending line
+/// with a caret under the column:
+///
+/// ```text
+/// failed to parse day7's input at line 3, column 7: expected a digit
+///   |
+/// 3 | 32T3K x65
+///   |       ^
+/// ```
+///
+/// Parsers are often given a single line of the input, so until the error is
+/// [`locate`]d in the whole input its position is relative to that line.
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    /// The input the parser was given
+    fragment: &'static str,
+    /// Where parsing stopped, as a byte offset into `fragment`
+    offset: usize,
+    message: String,
+    /// The whole input, once `fragment` is known to be a part of it
+    whole: Option<&'static str>,
+    /// The day's binary, like `day7`, if known
+    day: Option<String>,
+}
+
+impl ParseError {
+    pub fn new(fragment: &'static str, offset: usize, message: impl Display) -> Self {
+        Self {
+            fragment,
+            offset: offset.min(fragment.len()),
+            message: message.to_string(),
+            whole: None,
+            day: None,
+        }
+    }
+
+    /// The day's binary, like `day7`, if known.
+    #[must_use]
+    pub fn day(&self) -> Option<&str> {
+        self.day.as_deref()
+    }
+
+    /// The 1-based line parsing stopped on.
+    #[must_use]
+    pub fn line(&self) -> usize {
+        let (input, offset) = self.position();
+        input[..offset].matches('\n').count() + 1
+    }
+
+    /// The 1-based column, in characters, parsing stopped on.
+    #[must_use]
+    pub fn column(&self) -> usize {
+        let (input, offset) = self.position();
+        let start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+        input[start..offset].chars().count() + 1
+    }
+
+    /// The input to report the position in, and the offset into it.
+    fn position(&self) -> (&'static str, usize) {
+        let fragment_start = self.fragment.as_ptr() as usize;
+        if let Some(whole) = self.whole {
+            let whole_start = whole.as_ptr() as usize;
+            let whole_end = whole_start + whole.len();
+            if (whole_start..=whole_end).contains(&fragment_start)
+                && fragment_start + self.fragment.len() <= whole_end
+            {
+                return (whole, fragment_start - whole_start + self.offset);
+            }
+        }
+        (self.fragment, self.offset)
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (input, offset) = self.position();
+        let (line, column) = (self.line(), self.column());
+        let start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+        let source_line = input[start..end].trim_end_matches('\r');
+        match &self.day {
+            Some(day) => write!(f, "failed to parse {day}'s input")?,
+            None => write!(f, "failed to parse the input")?,
+        }
+        writeln!(f, " at line {line}, column {column}: {}", self.message)?;
+        let gutter = " ".repeat(line.to_string().len());
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{line} | {source_line}")?;
+        write!(f, "{gutter} | {}^", " ".repeat(column - 1))
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Fills in the whole input and the day of a [`ParseError`] in `error`'s
+/// chain, so its position is reported relative to the whole input.
+#[must_use]
+pub fn locate(mut error: anyhow::Error, whole: &'static str, day: Option<&str>) -> anyhow::Error {
+    if let Some(parse_error) = error.downcast_mut::<ParseError>() {
+        parse_error.whole = Some(whole);
+        if let Some(day) = day {
+            parse_error.day = Some(day.to_owned());
+        }
+    }
+    error
+}
+
+#[cfg(feature = "winnow")]
+impl From<winnow::error::ParseError<&'static str, winnow::error::ContextError>> for ParseError {
+    fn from(error: winnow::error::ParseError<&'static str, winnow::error::ContextError>) -> Self {
+        let message = match error.inner().to_string().trim() {
+            "" => match error.input()[error.offset()..].chars().next() {
+                Some(c) => format!("unexpected {c:?}"),
+                None => "unexpected end of input".to_owned(),
+            },
+            message => message.replace('\n', ", "),
+        };
+        Self::new(error.input(), error.offset(), message)
+    }
+}
+
+#[cfg(feature = "nom")]
+impl ParseError {
+    /// Converts nom's error, whose input is where parsing stopped: the rest
+    /// of `input`, or of a part of it that was parsed separately.
+    #[must_use]
+    pub fn from_nom(input: &'static str, error: &nom::error::Error<&'static str>) -> Self {
+        let message = match error.code {
+            nom::error::ErrorKind::Eof => "expected the end of the input".to_owned(),
+            nom::error::ErrorKind::Verify => "unexpected value".to_owned(),
+            code => format!("expected {}", code.description().to_lowercase()),
+        };
+        Self {
+            whole: Some(input),
+            ..Self::new(error.input, 0, message)
+        }
+    }
+}
+
+/// Lets nom parsers use parsers that return a [`ParseError`], failing where
+/// they did. The message is lost, since nom's errors don't have one.
+#[cfg(feature = "nom")]
+impl From<ParseError> for nom::Err<nom::error::Error<&'static str>> {
+    fn from(error: ParseError) -> Self {
+        let rest = &error.fragment[error.offset..];
+        nom::Err::Failure(nom::error::Error::new(rest, nom::error::ErrorKind::Verify))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "32T3K 765\nT55J5 684\nKK677 x28\n";
+
+    #[test]
+    fn position_in_a_line() {
+        let line = INPUT.lines().nth(2).unwrap();
+        let error = ParseError::new(line, 6, "expected a digit");
+        assert_eq!((error.line(), error.column()), (1, 7));
+        let error = locate(error.into(), INPUT, Some("day7"));
+        let error = error.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((error.line(), error.column()), (3, 7));
+        assert_eq!(error.day(), Some("day7"));
+    }
+
+    #[test]
+    fn caret_snippet() {
+        let error = ParseError::new(INPUT, 26, "expected a digit");
+        assert_eq!(
+            error.to_string(),
+            "failed to parse the input at line 3, column 7: expected a digit\n  \
+             |\n\
+             3 | KK677 x28\n  \
+             |       ^"
+        );
+    }
+
+    #[test]
+    fn unrelated_whole_input() {
+        let error = ParseError::new(INPUT, 10, "expected a card");
+        let error = locate(error.into(), "something else", None);
+        let error = error.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((error.line(), error.column()), (2, 1));
+    }
+
+    #[cfg(feature = "nom")]
+    #[test]
+    fn nom_error_in_a_piece() {
+        let piece = &INPUT.lines().nth(2).unwrap()[6..];
+        let error = nom::error::Error::new(piece, nom::error::ErrorKind::Digit);
+        let error = ParseError::from_nom(INPUT, &error);
+        assert_eq!((error.line(), error.column()), (3, 7));
+    }
+}
@@ -0,0 +1,77 @@
+//! Differential tests for heavily optimized days: the day's parts are run
+//! next to a straightforward reference implementation on the sample, the
+//! real input and random inputs, and the first input they disagree on is
+//! reported.
+use std::{borrow::Cow, fmt::Display};
+
+use anyhow::{bail, Context};
+
+use crate::{generate::Rng, Solution};
+
+/// The straightforward implementation of a day's parts, parsing the input
+/// itself.
+#[derive(Debug, Clone, Copy)]
+pub struct Reference<A> {
+    pub a: fn(input: &'static str) -> anyhow::Result<A>,
+    pub b: fn(input: &'static str) -> anyhow::Result<A>,
+    /// The largest size of generated input the reference keeps up with
+    pub max_size: usize,
+    /// How many generated inputs to compare on
+    pub cases: u64,
+}
+
+/// Compares `T` with `reference` on `sample` through the test variants, then
+/// on the real input if there is one and on inputs from `T`'s generator of
+/// up to the reference's `max_size`, seeded with 0, 1, 2 and so on.
+///
+/// Both implementations failing on an input counts as agreeing, so inputs
+/// without an answer can be generated too.
+pub fn check<T: Solution + ?Sized>(
+    reference: &Reference<T::Answer>,
+    sample: &'static str,
+) -> anyhow::Result<()> {
+    let test_a = |input| T::part_a_test(T::shared_test(input)?);
+    let test_b = |input| T::part_b_test(T::shared_test(input)?);
+    compare("a", "the sample", sample, test_a, reference.a)?;
+    compare("b", "the sample", sample, test_b, reference.b)?;
+    let a = |input| T::part_a(Cow::Owned(T::shared(input)?));
+    let b = |input| T::part_b(T::shared(input)?);
+    if let Some(input) = T::INPUT {
+        compare("a", "the real input", input, a, reference.a)?;
+        compare("b", "the real input", input, b, reference.b)?;
+    }
+    for seed in 0..reference.cases {
+        let rng = &mut Rng::with_seed(seed);
+        let size = rng.usize(1..=reference.max_size);
+        let generated = T::generate(rng, size).context("the day has no input generator")?;
+        let input: &'static str = generated.input.leak();
+        let name = format!("the input generated with seed {seed}:\n{input}");
+        compare("a", &name, input, a, reference.a)?;
+        compare("b", &name, input, b, reference.b)?;
+    }
+    Ok(())
+}
+
+fn compare<A: Display>(
+    part: &str,
+    name: &str,
+    input: &'static str,
+    optimized: impl Fn(&'static str) -> anyhow::Result<A>,
+    reference: fn(&'static str) -> anyhow::Result<A>,
+) -> anyhow::Result<()> {
+    let outcome = |answer: anyhow::Result<A>| match answer {
+        Ok(answer) => Ok(answer.to_string()),
+        Err(e) => Err(format!("{e:#}")),
+    };
+    match (outcome(optimized(input)), outcome(reference(input))) {
+        (Ok(optimized), Ok(reference)) if optimized == reference => Ok(()),
+        (Err(_), Err(_)) => Ok(()),
+        (optimized, reference) => bail!(
+            "part {part} diverges on {name}\n\
+             optimized: {}\n\
+             reference: {}",
+            optimized.unwrap_or_else(|e| format!("error: {e}")),
+            reference.unwrap_or_else(|e| format!("error: {e}")),
+        ),
+    }
+}
@@ -0,0 +1,111 @@
+//! Random puzzle inputs, from a size and a seed: property tests check that
+//! the parsers read them back and that the parts don't panic on them, and
+//! `--generate` runs or benches a day on one to see how it scales.
+use std::{
+    borrow::Cow,
+    panic::{self, AssertUnwindSafe},
+};
+
+use anyhow::{bail, Context};
+pub use fastrand::Rng;
+
+use crate::{diagnostic, Solution};
+
+/// The sizes [`check`] generates inputs of.
+pub const SIZES: &[usize] = &[1, 2, 5, 10, 20];
+
+/// How many inputs of each size [`check`] generates.
+pub const CASES: u64 = 8;
+
+/// A generated input, and what `shared` should make of it when that's
+/// simple to say.
+#[derive(Debug, Clone)]
+pub struct Generated<T> {
+    pub input: String,
+    pub shared: Option<T>,
+}
+
+impl<T> Generated<T> {
+    /// An input that's only checked to parse.
+    #[must_use]
+    pub fn new(input: String) -> Self {
+        Self {
+            input,
+            shared: None,
+        }
+    }
+
+    /// An input that should parse to `shared`.
+    #[must_use]
+    pub fn round_trip(input: String, shared: T) -> Self {
+        Self {
+            input,
+            shared: Some(shared),
+        }
+    }
+}
+
+/// Generates [`CASES`] inputs of each of the [`SIZES`], and checks that `T`
+/// parses each of them to what was generated, and that neither part panics
+/// on them. Parts may return errors, since some inputs have no answer.
+///
+/// Days without a generator pass.
+pub fn check<T: Solution + ?Sized>() -> anyhow::Result<()> {
+    check_with::<T>(SIZES, CASES)
+}
+
+/// [`check`] with fewer sizes or cases, for days that take a while on each
+/// input.
+pub fn check_with<T: Solution + ?Sized>(sizes: &[usize], cases: u64) -> anyhow::Result<()> {
+    for &size in sizes {
+        for seed in 0..cases {
+            let Some(generated) = T::generate(&mut Rng::with_seed(seed), size) else {
+                return Ok(());
+            };
+            let input: &'static str = generated.input.leak();
+            let describe = || format!("the input of size {size} and seed {seed}:\n{}", head(input));
+            let shared = T::shared(input)
+                .map_err(|e| diagnostic::locate(e, input, None))
+                .with_context(|| format!("failed to parse {}", describe()))?;
+            if let Some(expected) = &generated.shared {
+                let (parsed, expected) = (format!("{shared:?}"), format!("{expected:?}"));
+                if parsed != expected {
+                    bail!(
+                        "{} parsed to\n{parsed}\nrather than\n{expected}",
+                        describe()
+                    );
+                }
+            }
+            let a = panic::catch_unwind(AssertUnwindSafe(|| {
+                T::part_a(Cow::Borrowed(&shared)).map(drop)
+            }));
+            let b = panic::catch_unwind(AssertUnwindSafe(|| T::part_b(shared).map(drop)));
+            for (part, outcome) in [("a", a), ("b", b)] {
+                if let Err(payload) = outcome {
+                    let message = payload
+                        .downcast_ref::<&str>()
+                        .map(ToString::to_string)
+                        .or_else(|| payload.downcast_ref::<String>().cloned())
+                        .unwrap_or_default();
+                    bail!("part {part} panicked with {message:?} on {}", describe());
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The first lines of `input`, enough to recognize it by.
+fn head(input: &str) -> String {
+    const LINES: usize = 20;
+    let head = input
+        .lines()
+        .take(LINES)
+        .fold(String::new(), |head, line| head + line + "\n");
+    let more = input.lines().count().saturating_sub(LINES);
+    if more > 0 {
+        format!("{head}... and {more} more lines\n")
+    } else {
+        head
+    }
+}
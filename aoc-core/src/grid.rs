@@ -0,0 +1,425 @@
+//! A rectangular grid of tiles, for the days whose input is a map drawn with
+//! one byte per tile.
+//!
+//! Positions are `(row, column)` pairs counted from the top left, and
+//! directions are signed `(row, column)` offsets like those in
+//! [`ORTHOGONAL`] and [`ADJACENT`].
+use std::{
+    fmt::{self, Display},
+    ops::{Index, IndexMut},
+};
+
+use crate::ParseError;
+
+pub type Pos = (usize, usize);
+pub type Direction = (isize, isize);
+
+/// Up, right, down and left.
+pub const ORTHOGONAL: [Direction; 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+/// The 8 directions around a tile, row by row.
+pub const ADJACENT: [Direction; 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    /// Row by row
+    tiles: Vec<T>,
+    width: usize,
+}
+
+impl Grid<u8> {
+    /// The bytes of `input`'s lines, which should all be as long.
+    pub fn parse(input: &'static str) -> Result<Self, ParseError> {
+        Self::parse_with(input, Some)
+    }
+}
+
+impl<T> Grid<T> {
+    /// A grid of `width` by `height` copies of `tile`.
+    ///
+    /// # Panics
+    ///
+    /// If `width` or `height` is 0, like [`Grid::from_vec`] refuses.
+    #[must_use]
+    pub fn new(width: usize, height: usize, tile: T) -> Self
+    where
+        T: Clone,
+    {
+        assert!(width > 0, "a grid needs to be at least one tile wide");
+        assert!(height > 0, "a grid needs to be at least one tile high");
+        Self {
+            tiles: vec![tile; width * height],
+            width,
+        }
+    }
+
+    /// A grid of `tiles` laid out row by row, or `None` if they don't fill
+    /// whole rows of `width`, or there are none.
+    #[must_use]
+    pub fn from_vec(width: usize, tiles: Vec<T>) -> Option<Self> {
+        (width > 0 && !tiles.is_empty() && tiles.len().is_multiple_of(width))
+            .then_some(Self { tiles, width })
+    }
+
+    /// Parses each byte of `input`'s lines into a tile, failing on the first
+    /// byte `tile` returns `None` for, or on a line that isn't as long as
+    /// the first.
+    pub fn parse_with(
+        input: &'static str,
+        mut tile: impl FnMut(u8) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let width = input.lines().next().map_or(0, str::len);
+        if width == 0 {
+            return Err(ParseError::new(input, 0, "expected a row of tiles"));
+        }
+        let mut tiles = Vec::with_capacity(input.len());
+        for line in input.lines() {
+            let start = line.as_ptr() as usize - input.as_ptr() as usize;
+            if line.len() != width {
+                let mut end = line.len().min(width);
+                while !line.is_char_boundary(end) {
+                    end -= 1;
+                }
+                let message = format!("expected {width} tiles, like the first row");
+                return Err(ParseError::new(input, start + end, message));
+            }
+            for (col, byte) in line.bytes().enumerate() {
+                let Some(tile) = tile(byte) else {
+                    let c = line[col..].chars().next().unwrap_or_default();
+                    return Err(ParseError::new(
+                        input,
+                        start + col,
+                        format!("unexpected {c:?}"),
+                    ));
+                };
+                tiles.push(tile);
+            }
+        }
+        Ok(Self { tiles, width })
+    }
+
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.tiles.len() / self.width
+    }
+
+    #[must_use]
+    pub fn contains(&self, (row, col): Pos) -> bool {
+        row < self.height() && col < self.width
+    }
+
+    #[must_use]
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.contains(pos)
+            .then(|| &self.tiles[pos.0 * self.width + pos.1])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.contains(pos)
+            .then(|| &mut self.tiles[pos.0 * self.width + pos.1])
+    }
+
+    /// The position `direction` away from `pos`, if it's in the grid.
+    #[must_use]
+    pub fn offset(&self, (row, col): Pos, (drow, dcol): Direction) -> Option<Pos> {
+        let pos = (row.checked_add_signed(drow)?, col.checked_add_signed(dcol)?);
+        self.contains(pos).then_some(pos)
+    }
+
+    /// The position `direction` away from `pos`, wrapping around the edges.
+    #[must_use]
+    pub fn wrapping_offset(&self, (row, col): Pos, (drow, dcol): Direction) -> Pos {
+        let wrap = |i: usize, di: isize, len: usize| {
+            let di = di.rem_euclid(len.cast_signed()).cast_unsigned();
+            (i % len + di) % len
+        };
+        (wrap(row, drow, self.height()), wrap(col, dcol, self.width))
+    }
+
+    /// The positions in each of `directions` from `pos` that are in the grid.
+    pub fn neighbors<'a>(
+        &'a self,
+        pos: Pos,
+        directions: &'a [Direction],
+    ) -> impl Iterator<Item = Pos> + 'a {
+        directions
+            .iter()
+            .filter_map(move |&direction| self.offset(pos, direction))
+    }
+
+    /// The positions in each of `directions` from `pos`, wrapping around the
+    /// edges.
+    pub fn wrapping_neighbors<'a>(
+        &'a self,
+        pos: Pos,
+        directions: &'a [Direction],
+    ) -> impl Iterator<Item = Pos> + 'a {
+        directions
+            .iter()
+            .map(move |&direction| self.wrapping_offset(pos, direction))
+    }
+
+    /// The positions from `pos` onwards in `direction`, until the edge.
+    pub fn ray(&self, pos: Pos, direction: Direction) -> impl Iterator<Item = Pos> + '_ {
+        std::iter::successors(self.contains(pos).then_some(pos), move |&pos| {
+            self.offset(pos, direction)
+        })
+    }
+
+    #[must_use]
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.tiles[row * self.width..(row + 1) * self.width]
+    }
+
+    #[must_use]
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> {
+        self.tiles.chunks_exact(self.width)
+    }
+
+    /// # Panics
+    ///
+    /// If `col` is outside the grid.
+    #[must_use]
+    pub fn column(&self, col: usize) -> impl DoubleEndedIterator<Item = &T> {
+        assert!(
+            col < self.width,
+            "column {col} is outside a grid {} wide",
+            self.width
+        );
+        self.tiles[col..].iter().step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl DoubleEndedIterator<Item = &T>> {
+        (0..self.width).map(|col| self.column(col))
+    }
+
+    /// The diagonals going down and to the right, starting from the bottom
+    /// left corner.
+    pub fn diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        let starts = (0..self.height())
+            .rev()
+            .map(|row| (row, 0))
+            .chain((1..self.width).map(|col| (0, col)));
+        starts.map(|start| self.ray(start, (1, 1)).map(|pos| &self[pos]))
+    }
+
+    /// The diagonals going down and to the left, starting from the top left
+    /// corner.
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        let starts = (0..self.width)
+            .map(|col| (0, col))
+            .chain((1..self.height()).map(|row| (row, self.width - 1)));
+        starts.map(|start| self.ray(start, (1, -1)).map(|pos| &self[pos]))
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.tiles.len()).map(move |i| (i / width, i % width))
+    }
+
+    /// Every tile with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(&self.tiles)
+    }
+
+    /// The first position of `tile`, row by row.
+    #[must_use]
+    pub fn find(&self, tile: &T) -> Option<Pos>
+    where
+        T: PartialEq,
+    {
+        self.find_all(tile).next()
+    }
+
+    /// Every position of `tile`, row by row.
+    pub fn find_all<'a>(&'a self, tile: &'a T) -> impl Iterator<Item = Pos> + 'a
+    where
+        T: PartialEq,
+    {
+        self.iter()
+            .filter(move |&(_, t)| t == tile)
+            .map(|(pos, _)| pos)
+    }
+
+    #[must_use]
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            tiles: self.tiles.iter().map(f).collect(),
+            width: self.width,
+        }
+    }
+
+    /// Flipped over the diagonal from the top left corner, so rows become
+    /// columns.
+    #[must_use]
+    pub fn transposed(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            tiles: self.columns().flatten().cloned().collect(),
+            width: self.height(),
+        }
+    }
+
+    #[must_use]
+    pub fn rotated_clockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            tiles: self.columns().flat_map(Iterator::rev).cloned().collect(),
+            width: self.height(),
+        }
+    }
+
+    #[must_use]
+    pub fn rotated_counterclockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            tiles: (0..self.width)
+                .rev()
+                .flat_map(|col| self.column(col))
+                .cloned()
+                .collect(),
+            width: self.height(),
+        }
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): Pos) -> &T {
+        assert!(col < self.width, "column {col} is out of the grid");
+        &self.tiles[row * self.width + col]
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, (row, col): Pos) -> &mut T {
+        assert!(col < self.width, "column {col} is out of the grid");
+        &mut self.tiles[row * self.width + col]
+    }
+}
+
+/// Draws the grid the way it's parsed, a line per row.
+impl<T: Copy + Into<char>> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            let line: String = row.iter().map(|&tile| tile.into()).collect();
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "abc\ndef\n";
+
+    #[test]
+    fn parse_and_display() -> anyhow::Result<()> {
+        let grid = Grid::parse(INPUT)?;
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 2)], b'f');
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.find(&b'e'), Some((1, 1)));
+        assert_eq!(grid.to_string(), INPUT);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = Grid::parse("abc\nde\n").unwrap_err();
+        assert_eq!((error.line(), error.column()), (2, 3));
+        let error = Grid::parse_with(INPUT, |b| (b != b'e').then_some(b)).unwrap_err();
+        assert_eq!((error.line(), error.column()), (2, 2));
+        assert!(error.to_string().contains("unexpected 'e'"), "{error}");
+    }
+
+    #[test]
+    fn neighbors() -> anyhow::Result<()> {
+        let grid = Grid::parse(INPUT)?;
+        let neighbors: Vec<_> = grid.neighbors((0, 0), &ORTHOGONAL).collect();
+        assert_eq!(neighbors, [(0, 1), (1, 0)]);
+        assert_eq!(grid.neighbors((1, 1), &ADJACENT).count(), 5);
+        let wrapped: Vec<_> = grid.wrapping_neighbors((0, 0), &ORTHOGONAL).collect();
+        assert_eq!(wrapped, [(1, 0), (0, 1), (1, 0), (0, 2)]);
+        assert_eq!(grid.wrapping_offset((1, 2), (-3, 7)), (0, 0));
+        Ok(())
+    }
+
+    #[test]
+    fn views() -> anyhow::Result<()> {
+        let grid = Grid::parse(INPUT)?;
+        let to_string = |tiles: &mut dyn Iterator<Item = &u8>| -> String {
+            tiles.map(|&b| char::from(b)).collect()
+        };
+        assert_eq!(grid.row(1), b"def");
+        assert_eq!(to_string(&mut grid.column(1)), "be");
+        let diagonals: Vec<_> = grid.diagonals().map(|mut d| to_string(&mut d)).collect();
+        assert_eq!(diagonals, ["d", "ae", "bf", "c"]);
+        let anti: Vec<_> = grid
+            .anti_diagonals()
+            .map(|mut d| to_string(&mut d))
+            .collect();
+        assert_eq!(anti, ["a", "bd", "ce", "f"]);
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "outside a grid 3 wide")]
+    fn column_out_of_bounds() {
+        let grid = Grid::new(3, 2, 0);
+        let _ = grid.column(3);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one tile wide")]
+    fn zero_width() {
+        let _ = Grid::new(0, 2, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one tile high")]
+    fn zero_height() {
+        let _ = Grid::new(3, 0, 0);
+    }
+
+    #[test]
+    fn empty_from_vec() {
+        assert_eq!(Grid::from_vec(0, vec![1]), None);
+        assert_eq!(Grid::<u8>::from_vec(3, vec![]), None);
+    }
+
+    #[test]
+    fn rotations() -> anyhow::Result<()> {
+        let grid = Grid::parse(INPUT)?;
+        assert_eq!(grid.transposed().to_string(), "ad\nbe\ncf\n");
+        assert_eq!(grid.rotated_clockwise().to_string(), "da\neb\nfc\n");
+        assert_eq!(grid.rotated_counterclockwise().to_string(), "cf\nbe\nad\n");
+        let back = grid.rotated_clockwise().rotated_counterclockwise();
+        assert_eq!(back, grid);
+        Ok(())
+    }
+}
@@ -0,0 +1,510 @@
+#![warn(clippy::pedantic)]
+#![allow(clippy::missing_errors_doc)]
+//! The `Solution` trait shared by every year, and the plumbing that runs a
+//! day on its own, in its tests, or from the workspace runner.
+//!
+//! Days write their parsing however they like inside `shared`; the `nom` and
+//! `winnow` features add adapters for those parser libraries.
+use std::{
+    borrow::Cow,
+    fmt::{Debug, Display},
+    io::{IsTerminal, Read},
+    time::{Duration, Instant},
+};
+
+pub use answers::Answers;
+use anyhow::{bail, Context};
+use bench::BenchArgs;
+pub use diagnostic::ParseError;
+use generate::{Generated, Rng};
+use memory::Allocs;
+use report::{Format, Report};
+
+mod answers;
+pub mod bench;
+pub mod branch_and_bound;
+pub mod build;
+pub mod cycle;
+pub mod diagnostic;
+pub mod differential;
+pub mod generate;
+pub mod grid;
+pub mod memory;
+#[cfg(feature = "nom")]
+pub mod nom;
+pub mod ranges;
+pub mod report;
+pub mod search;
+#[cfg(feature = "winnow")]
+pub mod winnow;
+
+// Re-exported for the macros, so year crates don't have to depend on it
+#[doc(hidden)]
+pub use anyhow;
+
+/// One timed step of an in-process run: `"shared"`, `"a"` or `"b"`, the
+/// answer (empty for `"shared"`) or the step's error, how long it took, and
+/// what it allocated if [`memory`] counting is enabled.
+pub type Step = (
+    &'static str,
+    anyhow::Result<String>,
+    Duration,
+    Option<Allocs>,
+);
+
+/// Runs a day in-process for the workspace runner, on the given input or
+/// else the embedded one. `part` is `"a"`, `"b"`, or `None` for both.
+///
+/// Only fails with [`NoInput`]. Each step records its own error instead, so
+/// the steps that succeeded can still be reported, and the parts don't run
+/// if `shared` failed.
+pub type RunFn = fn(input: Option<&'static str>, part: Option<&str>) -> anyhow::Result<Vec<Step>>;
+
+/// Checks the assumptions a day makes about its input, on the given input or
+/// else the embedded one, see [`Solution::validate`].
+pub type ValidateFn = fn(input: Option<&'static str>) -> anyhow::Result<()>;
+
+/// What a day exposes to the workspace runner, see [`register!`].
+#[derive(Debug, Clone, Copy)]
+pub struct Registered {
+    pub run: RunFn,
+    pub validate: ValidateFn,
+}
+
+/// The error a [`RunFn`] returns when there's no input to run on.
+#[derive(Debug, Clone, Copy)]
+pub struct NoInput;
+
+impl Display for NoInput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("no input")
+    }
+}
+
+impl std::error::Error for NoInput {}
+
+/// The day's `data.txt`, if it was present at build time.
+///
+/// Expands to an `Option<&'static str>`, so a checkout without puzzle inputs
+/// still builds and the input can be supplied at runtime instead.
+#[macro_export]
+macro_rules! embedded_input {
+    ($day:ident) => {
+        $crate::non_empty(include_str!(concat!(
+            env!("OUT_DIR"),
+            "/inputs/",
+            stringify!($day),
+            ".txt"
+        )))
+    };
+}
+
+/// The day's `answers.toml`, recorded by running it with `--record`.
+///
+/// Expands to an [`Answers`], pointing at the file in the day's directory.
+#[macro_export]
+macro_rules! embedded_answers {
+    ($day:ident) => {
+        $crate::Answers {
+            path: concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/src/bin/",
+                stringify!($day),
+                "/answers.toml"
+            ),
+            recorded: $crate::non_empty(include_str!(concat!(
+                env!("OUT_DIR"),
+                "/answers/",
+                stringify!($day),
+                ".toml"
+            ))),
+        }
+    };
+}
+
+/// Generates a test per part of each `sample*.txt` in the day's directory
+/// that has an expected answer in its `samples.toml`, like
+///
+/// ```toml
+/// [sample]
+/// a = 142
+/// b = 281
+/// ```
+///
+/// The tests go through `shared_test` and `part_*_test`. Invoke it inside
+/// the day's test module, `$name` being the day's directory under `src/bin`.
+#[macro_export]
+macro_rules! sample_tests {
+    ($day:ty, $name:ident) => {
+        type SampleSolution = $day;
+        type SampleResult = $crate::anyhow::Result<()>;
+        use $crate::test_sample;
+        include!(concat!(
+            env!("OUT_DIR"),
+            "/samples/",
+            stringify!($name),
+            ".rs"
+        ));
+    };
+}
+
+/// Exposes a day to the workspace runner when it's compiled into the library.
+#[macro_export]
+macro_rules! register {
+    ($day:ty) => {
+        #[allow(dead_code)]
+        pub(crate) const REGISTERED: $crate::Registered = $crate::Registered {
+            run: $crate::run::<$day>,
+            validate: $crate::validate::<$day>,
+        };
+    };
+}
+
+#[doc(hidden)]
+#[must_use]
+pub const fn non_empty(input: &'static str) -> Option<&'static str> {
+    if input.is_empty() {
+        None
+    } else {
+        Some(input)
+    }
+}
+
+pub trait BasicSolution {
+    type Shared: Debug + Clone;
+    type Answer: Debug + Display;
+    const INPUT: Option<&'static str>;
+    const ANSWERS: Answers;
+
+    fn shared(input: &'static str) -> anyhow::Result<Self::Shared>;
+    fn part_a(shared: Cow<Self::Shared>) -> anyhow::Result<Self::Answer>;
+    fn part_b(shared: Self::Shared) -> anyhow::Result<Self::Answer>;
+
+    /// See [`Solution::validate`].
+    fn validate(_shared: &Self::Shared) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// See [`Solution::generate`].
+    fn generate(_rng: &mut Rng, _size: usize) -> Option<Generated<Self::Shared>> {
+        None
+    }
+}
+
+impl<T: BasicSolution> Solution for T {
+    type Shared = <Self as BasicSolution>::Shared;
+    type SharedTest = Self::Shared;
+    type Answer = <Self as BasicSolution>::Answer;
+    const INPUT: Option<&'static str> = <Self as BasicSolution>::INPUT;
+    const ANSWERS: Answers = <Self as BasicSolution>::ANSWERS;
+
+    fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
+        <Self as BasicSolution>::shared(input)
+    }
+
+    fn part_a(data: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
+        <Self as BasicSolution>::part_a(data)
+    }
+
+    fn part_b(data: Self::Shared) -> anyhow::Result<Self::Answer> {
+        <Self as BasicSolution>::part_b(data)
+    }
+
+    fn validate(shared: &Self::Shared) -> anyhow::Result<()> {
+        <Self as BasicSolution>::validate(shared)
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        <Self as BasicSolution>::generate(rng, size)
+    }
+
+    fn shared_test(input: &'static str) -> anyhow::Result<Self::SharedTest> {
+        Self::shared(input)
+    }
+    fn part_a_test(data: Self::SharedTest) -> anyhow::Result<Self::Answer> {
+        Self::part_a(Cow::Owned(data))
+    }
+    fn part_b_test(data: Self::SharedTest) -> anyhow::Result<Self::Answer> {
+        Self::part_b(data)
+    }
+}
+
+pub trait Solution {
+    type Shared: Debug + Clone;
+    type SharedTest: Debug + Clone;
+    type Answer: Debug + Display;
+    const INPUT: Option<&'static str>;
+    const ANSWERS: Answers;
+
+    fn shared(input: &'static str) -> anyhow::Result<Self::Shared>;
+    fn part_a(shared: Cow<Self::Shared>) -> anyhow::Result<Self::Answer>;
+    fn part_b(shared: Self::Shared) -> anyhow::Result<Self::Answer>;
+    fn shared_test(input: &'static str) -> anyhow::Result<Self::SharedTest>;
+    fn part_a_test(shared: Self::SharedTest) -> anyhow::Result<Self::Answer>;
+    fn part_b_test(shared: Self::SharedTest) -> anyhow::Result<Self::Answer>;
+
+    /// Checks the assumptions the parts make about the real input beyond
+    /// what `shared` checks, like whether it fits the integer types they
+    /// pack it into, or whether a heuristic holds for it. Run by the
+    /// workspace runner's `validate` command, so it may take a while.
+    fn validate(_shared: &Self::Shared) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// A random valid input, growing with `size` in whatever way suits the
+    /// day, like its number of lines or the side of its grid. Checked by
+    /// [`generate::check`], and run with `--generate <size>`.
+    fn generate(_rng: &mut Rng, _size: usize) -> Option<Generated<Self::Shared>> {
+        None
+    }
+
+    /// Runs part a on the real input, if there is one, and checks it against
+    /// `answers.toml`. The samples are tested by [`sample_tests!`].
+    fn test_part_a() -> anyhow::Result<()> {
+        if let Some(input) = Self::INPUT {
+            let shared = Self::shared(input).map_err(|e| diagnostic::locate(e, input, None))?;
+            let shared = Cow::Owned(shared);
+            let a = Self::part_a(shared)?;
+            println!("a: {a}");
+            Self::ANSWERS.check("a", &a)?;
+        }
+        Ok(())
+    }
+
+    /// Runs part b on the real input, see [`Solution::test_part_a`].
+    fn test_part_b() -> anyhow::Result<()> {
+        if let Some(input) = Self::INPUT {
+            let shared = Self::shared(input).map_err(|e| diagnostic::locate(e, input, None))?;
+            let b = Self::part_b(shared)?;
+            println!("b: {b}");
+            Self::ANSWERS.check("b", &b)?;
+        }
+        Ok(())
+    }
+
+    /// Runs both parts on generated inputs, see [`generate::check`].
+    fn test_generated() -> anyhow::Result<()> {
+        generate::check::<Self>()
+    }
+
+    fn main() -> anyhow::Result<()> {
+        let args = Args::parse(std::env::args().skip(1))?;
+        let input = match args.generate {
+            Some(size) => Self::generate(&mut Rng::with_seed(args.seed), size)
+                .context("this day has no input generator")?
+                .input
+                .leak(),
+            None => load_input(args.input.as_deref(), Self::INPUT)?,
+        };
+        if let Some(bench) = &args.bench {
+            return bench::bench::<Self>(input, args.part.as_deref(), bench);
+        }
+        if args.format == Format::Json {
+            let (year, day) = report::year_and_day(&Self::ANSWERS);
+            let steps = run::<Self>(Some(input), args.part.as_deref());
+            for report in Report::from_run(year, day, args.part.as_deref(), &steps) {
+                report.print()?;
+            }
+            return match steps?
+                .into_iter()
+                .find_map(|(_, outcome, ..)| outcome.err())
+            {
+                Some(e) => Err(e),
+                None => Ok(()),
+            };
+        }
+        let shared = time("Shared", || Self::shared(input))
+            .map_err(|e| diagnostic::locate(e, input, day_name().as_deref()))?;
+        let (a, b) = match args.part.as_deref() {
+            Some("a") => (
+                Some(time("Part a", || Self::part_a(Cow::Owned(shared)))?),
+                None,
+            ),
+            Some("b") => (None, Some(time("Part b", || Self::part_b(shared))?)),
+            _ => {
+                let a = time("Part a", || Self::part_a(Cow::Borrowed(&shared)))?;
+                (Some(a), Some(time("Part b", || Self::part_b(shared))?))
+            }
+        };
+        if let Some(a) = &a {
+            println!("a: {a}");
+        }
+        if let Some(b) = &b {
+            println!("b: {b}");
+        }
+        if args.record {
+            let to_string = |answer: Self::Answer| answer.to_string();
+            Self::ANSWERS.record(a.map(to_string), b.map(to_string))?;
+            println!("recorded in {}", Self::ANSWERS.path);
+        }
+        Ok(())
+    }
+}
+
+#[doc(hidden)]
+pub fn test_sample<T: Solution + ?Sized>(
+    part: &str,
+    input: &'static str,
+    expected: &str,
+) -> anyhow::Result<()> {
+    let shared = T::shared_test(input).map_err(|e| diagnostic::locate(e, input, None))?;
+    let answer = match part {
+        "a" => T::part_a_test(shared)?,
+        _ => T::part_b_test(shared)?,
+    };
+    println!("{part}: {answer}");
+    assert_eq!(answer.to_string(), expected, "part {part}");
+    Ok(())
+}
+
+#[doc(hidden)]
+pub fn run<T: Solution + ?Sized>(
+    input: Option<&'static str>,
+    part: Option<&str>,
+) -> anyhow::Result<Vec<Step>> {
+    let input = input.or(T::INPUT).ok_or(NoInput)?;
+    let start = Instant::now();
+    let (shared, allocs) = memory::measure(|| T::shared(input));
+    let elapsed = start.elapsed();
+    let shared = match shared {
+        Ok(shared) => shared,
+        Err(e) => {
+            let e = diagnostic::locate(e, input, None);
+            return Ok(vec![("shared", Err(e), elapsed, allocs)]);
+        }
+    };
+    let mut steps = vec![("shared", Ok(String::new()), elapsed, allocs)];
+    if part != Some("b") {
+        let start = Instant::now();
+        let (a, allocs) = memory::measure(|| T::part_a(Cow::Borrowed(&shared)));
+        let elapsed = start.elapsed();
+        steps.push(("a", a.map(|a| a.to_string()), elapsed, allocs));
+    }
+    if part != Some("a") {
+        let start = Instant::now();
+        let (b, allocs) = memory::measure(|| T::part_b(shared));
+        let elapsed = start.elapsed();
+        steps.push(("b", b.map(|b| b.to_string()), elapsed, allocs));
+    }
+    Ok(steps)
+}
+
+#[doc(hidden)]
+pub fn validate<T: Solution + ?Sized>(input: Option<&'static str>) -> anyhow::Result<()> {
+    let input = input.or(T::INPUT).ok_or(NoInput)?;
+    let shared = T::shared(input).map_err(|e| diagnostic::locate(e, input, None))?;
+    T::validate(&shared)
+}
+
+const USAGE: &str = "usage: [bench] [a|b] [--input <path>|-] [--generate <size> [--seed <n>]] \
+    [--record] [--format text|json] [--runs <n>] [--warmup <n>] [--save <timings.csv>]";
+
+#[derive(Debug, Default)]
+struct Args {
+    part: Option<String>,
+    input: Option<String>,
+    /// Run on a generated input of this size instead
+    generate: Option<usize>,
+    seed: u64,
+    /// Write the answers to `answers.toml`
+    record: bool,
+    format: Format,
+    bench: Option<BenchArgs>,
+}
+
+impl Args {
+    fn parse(args: impl Iterator<Item = String>) -> anyhow::Result<Self> {
+        let mut args = args.peekable();
+        let mut parsed = Self::default();
+        if args.next_if_eq("bench").is_some() {
+            parsed.bench = Some(BenchArgs::default());
+        }
+        while let Some(arg) = args.next() {
+            if arg == "a" || arg == "b" {
+                parsed.part = Some(arg);
+                continue;
+            }
+            if arg == "--record" && parsed.bench.is_none() {
+                parsed.record = true;
+                continue;
+            }
+            let (flag, value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_owned(), Some(value.to_owned())),
+                None => (arg, None),
+            };
+            let value = || {
+                value
+                    .or_else(|| args.next())
+                    .with_context(|| format!("{flag} expects a value"))
+            };
+            match (flag.as_str(), &mut parsed.bench) {
+                ("--input", _) => parsed.input = Some(value()?),
+                ("--generate", _) => {
+                    parsed.generate = Some(value()?.parse().context("--generate")?);
+                }
+                ("--seed", _) => parsed.seed = value()?.parse().context("--seed")?,
+                ("--format", None) => parsed.format = value()?.parse()?,
+                ("--runs", Some(bench)) => bench.runs = value()?.parse().context("--runs")?,
+                ("--warmup", Some(bench)) => bench.warmup = value()?.parse().context("--warmup")?,
+                ("--save", Some(bench)) => bench.save = Some(value()?),
+                _ => bail!("unexpected argument {flag:?}, {USAGE}"),
+            }
+        }
+        if parsed.generate.is_some() && parsed.input.is_some() {
+            bail!("--generate and --input can't be combined");
+        }
+        let saving = parsed.bench.as_ref().is_some_and(|b| b.save.is_some());
+        if saving && (parsed.input.is_some() || parsed.generate.is_some()) {
+            bail!("--save only saves the timings for the embedded data.txt");
+        }
+        if parsed.record && (parsed.input.is_some() || parsed.generate.is_some()) {
+            bail!("--record only records the answers for the embedded data.txt");
+        }
+        if parsed.record && parsed.format == Format::Json {
+            bail!("--record can't be combined with --format json");
+        }
+        Ok(parsed)
+    }
+}
+
+/// Resolves the puzzle input: an explicit `--input` path (`-` for stdin),
+/// then the embedded `data.txt`, then piped stdin.
+///
+/// Inputs read at runtime are leaked, since solutions borrow from the input
+/// for the rest of the program.
+fn load_input(path: Option<&str>, embedded: Option<&'static str>) -> anyhow::Result<&'static str> {
+    let input = match (path, embedded) {
+        (Some("-"), _) => read_stdin()?,
+        (Some(path), _) => {
+            std::fs::read_to_string(path).with_context(|| format!("failed to read {path}"))?
+        }
+        (None, Some(embedded)) => return Ok(embedded),
+        (None, None) if !std::io::stdin().is_terminal() => read_stdin()?,
+        (None, None) => {
+            bail!("no data.txt was embedded at build time, pass --input <path> or pipe the input")
+        }
+    };
+    Ok(Box::leak(input.into_boxed_str()))
+}
+
+fn read_stdin() -> anyhow::Result<String> {
+    let mut input = String::new();
+    std::io::stdin()
+        .read_to_string(&mut input)
+        .context("failed to read input from stdin")?;
+    Ok(input)
+}
+
+/// The day's binary, like `day7`, from how it was invoked.
+fn day_name() -> Option<String> {
+    let exe = std::env::args().next()?;
+    let stem = std::path::Path::new(&exe).file_stem()?.to_str()?;
+    stem.starts_with("day").then(|| stem.to_owned())
+}
+
+fn time<T>(tag: &str, f: impl FnOnce() -> T) -> T {
+    let start = std::time::Instant::now();
+    let ans = f();
+    println!("{tag} took {:?}", start.elapsed());
+    ans
+}
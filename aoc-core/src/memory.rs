@@ -0,0 +1,159 @@
+//! An opt-in counting global allocator, for seeing how much each step of a
+//! run allocates. A binary installs [`Counting`] as its global allocator and
+//! calls [`enable`]; until then it just forwards to the system allocator.
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicBool, AtomicIsize, AtomicU64, Ordering::Relaxed},
+};
+
+use serde::Serialize;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static COUNT: AtomicU64 = AtomicU64::new(0);
+static BYTES: AtomicU64 = AtomicU64::new(0);
+/// Goes negative when memory allocated before counting was enabled is freed,
+/// so only differences are meaningful
+static LIVE: AtomicIsize = AtomicIsize::new(0);
+static PEAK: AtomicIsize = AtomicIsize::new(0);
+
+/// The system allocator, counting allocations once [`enable`] is called.
+#[derive(Debug, Clone, Copy)]
+pub struct Counting;
+
+// Layouts are at most `isize::MAX` bytes, so the casts don't wrap
+#[allow(clippy::cast_possible_wrap)]
+fn record(allocated: usize, freed: usize) {
+    if !ENABLED.load(Relaxed) {
+        return;
+    }
+    COUNT.fetch_add(1, Relaxed);
+    BYTES.fetch_add(allocated as u64, Relaxed);
+    let change = allocated as isize - freed as isize;
+    let live = LIVE.fetch_add(change, Relaxed) + change;
+    PEAK.fetch_max(live, Relaxed);
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        if ENABLED.load(Relaxed) {
+            LIVE.fetch_sub(layout.size() as isize, Relaxed);
+        }
+    }
+
+    /// Counts as an allocation of `new_size` bytes.
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            record(new_size, layout.size());
+        }
+        new_ptr
+    }
+}
+
+/// Starts counting, which does nothing unless [`Counting`] is the global
+/// allocator.
+pub fn enable() {
+    ENABLED.store(true, Relaxed);
+}
+
+/// What one step of a run allocated.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Allocs {
+    /// How many allocations it made, reallocations included
+    pub count: u64,
+    /// The sum of their sizes
+    pub bytes: u64,
+    /// The most it had allocated at once, on top of what was allocated when
+    /// it started
+    pub peak: u64,
+}
+
+/// Runs `f`, counting what it allocates if counting is enabled.
+///
+/// Allocations on other threads are counted too, so steps shouldn't run
+/// concurrently.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<Allocs>) {
+    if !ENABLED.load(Relaxed) {
+        return (f(), None);
+    }
+    let count = COUNT.load(Relaxed);
+    let bytes = BYTES.load(Relaxed);
+    let live = LIVE.load(Relaxed);
+    PEAK.store(live, Relaxed);
+    let out = f();
+    let allocs = Allocs {
+        count: COUNT.load(Relaxed) - count,
+        bytes: BYTES.load(Relaxed) - bytes,
+        peak: (PEAK.load(Relaxed) - live).max(0).unsigned_abs() as u64,
+    };
+    (out, Some(allocs))
+}
+
+/// Formats a number of bytes with a binary unit, like `1.5MiB`.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn format_bytes(bytes: u64) -> String {
+    let mut value = bytes as f64;
+    for unit in ["B", "KiB", "MiB"] {
+        if value < 1024. {
+            return if unit == "B" {
+                format!("{bytes}B")
+            } else {
+                format!("{value:.1}{unit}")
+            };
+        }
+        value /= 1024.;
+    }
+    format!("{value:.1}GiB")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[global_allocator]
+    static ALLOCATOR: Counting = Counting;
+
+    #[test]
+    fn counts_a_step() {
+        enable();
+        let (v, allocs) = measure(|| {
+            let mut v = vec![0_u8; 1000];
+            v.extend([1; 1000]);
+            drop(vec![0_u8; 500]);
+            v
+        });
+        let allocs = allocs.expect("counting is enabled");
+        // Other tests may allocate at the same time
+        assert!(allocs.count >= 3, "{allocs:?}");
+        assert!(allocs.bytes >= 1000 + 2000 + 500, "{allocs:?}");
+        assert!(allocs.peak >= 2000, "{allocs:?}");
+        assert_eq!(v.len(), 2000);
+    }
+
+    #[test]
+    fn formats_bytes() {
+        assert_eq!(format_bytes(512), "512B");
+        assert_eq!(format_bytes(1536), "1.5KiB");
+        assert_eq!(format_bytes(3 << 20), "3.0MiB");
+        assert_eq!(format_bytes(5 << 30), "5.0GiB");
+    }
+}
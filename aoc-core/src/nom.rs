@@ -0,0 +1,299 @@
+//! A front-end for days that parse with nom.
+//!
+//! Days implement [`BasicSolution`] (or [`Solution`] when the tests need a
+//! different parser), and [`boilerplate!`](crate::boilerplate) wraps them in
+//! [`Nom`] to get the shared [`crate::Solution`] behaviour.
+use std::{
+    borrow::Cow,
+    fmt::{self, Debug, Display},
+    marker::PhantomData,
+};
+
+use nom::character::complete::line_ending;
+use nom_supreme::{final_parser::final_parser, ParserExt};
+
+use crate::{
+    generate::{Generated, Rng},
+    ParseError,
+};
+
+pub type IResult<'a, T> = nom::IResult<&'a str, T>;
+
+/// The outcome of answering or validating a day's parsed input.
+pub type OutResult<T> = Result<T, SolveError>;
+
+/// Why a day couldn't answer for, or validate, an input that parsed.
+///
+/// Errors from helpers that return an [`anyhow::Error`], with whatever
+/// context they added, count as [`SolveError::Unsupported`].
+#[derive(Debug)]
+pub enum SolveError {
+    /// Part of the input is only parsed while solving, and it didn't parse
+    Parse(ParseError),
+    /// The input breaks an assumption the solution relies on
+    Unsupported(anyhow::Error),
+    /// The input fits the solution, but there's no answer for it
+    NoAnswer(anyhow::Error),
+}
+
+impl SolveError {
+    #[must_use]
+    pub fn unsupported(message: impl Display + Debug + Send + Sync + 'static) -> Self {
+        Self::Unsupported(anyhow::Error::msg(message))
+    }
+
+    #[must_use]
+    pub fn no_answer(message: impl Display + Debug + Send + Sync + 'static) -> Self {
+        Self::NoAnswer(anyhow::Error::msg(message))
+    }
+
+    /// Keeps a [`ParseError`] at the top of the chain, where
+    /// [`crate::diagnostic::locate`] looks for it.
+    fn into_anyhow(self) -> anyhow::Error {
+        match self {
+            Self::Parse(e) => e.into(),
+            e => e.into(),
+        }
+    }
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(e) => Display::fmt(e, f),
+            Self::Unsupported(e) | Self::NoAnswer(e) => Display::fmt(e, f),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Parse(_) => None,
+            Self::Unsupported(e) | Self::NoAnswer(e) => e.source(),
+        }
+    }
+}
+
+impl From<ParseError> for SolveError {
+    fn from(e: ParseError) -> Self {
+        Self::Parse(e)
+    }
+}
+
+impl From<anyhow::Error> for SolveError {
+    fn from(e: anyhow::Error) -> Self {
+        Self::Unsupported(e)
+    }
+}
+
+pub trait SolutionData {
+    /// The day's `data.txt`, if it was present at build time
+    const DATA: Option<&'static str>;
+    const ANSWERS: crate::Answers;
+}
+
+/// A day whose input doesn't fit its solution fails instead of panicking.
+/// Parsing fails with a [`ParseError`] that points at the offending spot
+/// in the input, and answering or validating with a [`SolveError`].
+pub trait BasicSolution: SolutionData {
+    type Parsed: Debug + Clone;
+    type Answer: Debug + Display;
+
+    fn parse(data: &'static str) -> IResult<'static, Self::Parsed>;
+    fn a(data: Self::Parsed) -> OutResult<Self::Answer>;
+    fn b(data: Self::Parsed) -> OutResult<Self::Answer>;
+
+    /// See [`crate::Solution::validate`].
+    fn validate(_data: &Self::Parsed) -> OutResult<()> {
+        Ok(())
+    }
+
+    /// See [`crate::Solution::generate`].
+    fn generate(_rng: &mut Rng, _size: usize) -> Option<Generated<Self::Parsed>> {
+        None
+    }
+}
+
+impl<T: BasicSolution> Solution for T {
+    type Parsed = <Self as BasicSolution>::Parsed;
+    type ParsedTest = Self::Parsed;
+    type Answer = <Self as BasicSolution>::Answer;
+
+    fn parse(data: &'static str) -> IResult<'static, Self::Parsed> {
+        <Self as BasicSolution>::parse(data)
+    }
+
+    fn a(data: Self::Parsed) -> OutResult<Self::Answer> {
+        <Self as BasicSolution>::a(data)
+    }
+
+    fn b(data: Self::Parsed) -> OutResult<Self::Answer> {
+        <Self as BasicSolution>::b(data)
+    }
+
+    fn validate(data: &Self::Parsed) -> OutResult<()> {
+        <Self as BasicSolution>::validate(data)
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Parsed>> {
+        <Self as BasicSolution>::generate(rng, size)
+    }
+
+    fn parse_test(data: &'static str) -> IResult<'static, Self::ParsedTest> {
+        Self::parse(data)
+    }
+    fn a_test(data: Self::ParsedTest) -> OutResult<Self::Answer> {
+        Self::a(data)
+    }
+    fn b_test(data: Self::ParsedTest) -> OutResult<Self::Answer> {
+        Self::b(data)
+    }
+}
+
+pub trait Solution: SolutionData {
+    type Parsed: Debug + Clone;
+    type ParsedTest: Debug + Clone;
+    type Answer: Debug + Display;
+
+    fn parse(data: &'static str) -> IResult<'static, Self::Parsed>;
+    fn a(data: Self::Parsed) -> OutResult<Self::Answer>;
+    fn b(data: Self::Parsed) -> OutResult<Self::Answer>;
+    fn parse_test(data: &'static str) -> IResult<'static, Self::ParsedTest>;
+    fn a_test(data: Self::ParsedTest) -> OutResult<Self::Answer>;
+    fn b_test(data: Self::ParsedTest) -> OutResult<Self::Answer>;
+
+    /// See [`crate::Solution::validate`].
+    fn validate(_data: &Self::Parsed) -> OutResult<()> {
+        Ok(())
+    }
+
+    /// See [`crate::Solution::generate`].
+    fn generate(_rng: &mut Rng, _size: usize) -> Option<Generated<Self::Parsed>> {
+        None
+    }
+
+    fn final_parse(data: &'static str) -> Result<Self::Parsed, ParseError> {
+        final_parser(Self::parse.terminated(line_ending.opt()))(data)
+            .map_err(|e| ParseError::from_nom(data, &e))
+    }
+
+    fn final_parse_test(data: &'static str) -> Result<Self::ParsedTest, ParseError> {
+        final_parser(Self::parse_test.terminated(line_ending.opt()))(data)
+            .map_err(|e| ParseError::from_nom(data, &e))
+    }
+}
+
+/// Adapts a nom [`Solution`] to the shared [`crate::Solution`].
+pub struct Nom<T>(PhantomData<T>);
+
+impl<T: Solution> crate::Solution for Nom<T> {
+    type Shared = T::Parsed;
+    type SharedTest = T::ParsedTest;
+    type Answer = T::Answer;
+    const INPUT: Option<&'static str> = T::DATA;
+    const ANSWERS: crate::Answers = T::ANSWERS;
+
+    fn shared(input: &'static str) -> anyhow::Result<Self::Shared> {
+        Ok(T::final_parse(input)?)
+    }
+
+    fn part_a(shared: Cow<Self::Shared>) -> anyhow::Result<Self::Answer> {
+        T::a(shared.into_owned()).map_err(SolveError::into_anyhow)
+    }
+
+    fn part_b(shared: Self::Shared) -> anyhow::Result<Self::Answer> {
+        T::b(shared).map_err(SolveError::into_anyhow)
+    }
+
+    fn validate(shared: &Self::Shared) -> anyhow::Result<()> {
+        T::validate(shared).map_err(SolveError::into_anyhow)
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated<Self::Shared>> {
+        T::generate(rng, size)
+    }
+
+    fn shared_test(input: &'static str) -> anyhow::Result<Self::SharedTest> {
+        Ok(T::final_parse_test(input)?)
+    }
+
+    fn part_a_test(shared: Self::SharedTest) -> anyhow::Result<Self::Answer> {
+        T::a_test(shared).map_err(SolveError::into_anyhow)
+    }
+
+    fn part_b_test(shared: Self::SharedTest) -> anyhow::Result<Self::Answer> {
+        T::b_test(shared).map_err(SolveError::into_anyhow)
+    }
+}
+
+/// Declares the day's unit struct, its data, tests and `main`.
+///
+/// `$name` is the day's directory under `src/bin`, used to find the
+/// `data.txt` embedded by the build script.
+#[macro_export]
+macro_rules! boilerplate {
+    ($day:ident, $name:ident) => {
+        struct $day;
+
+        impl $crate::nom::SolutionData for $day {
+            const DATA: Option<&'static str> = $crate::embedded_input!($name);
+            const ANSWERS: $crate::Answers = $crate::embedded_answers!($name);
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use $crate::Solution as _;
+
+            use super::*;
+
+            #[test]
+            fn a() -> $crate::anyhow::Result<()> {
+                $crate::nom::Nom::<$day>::test_part_a()
+            }
+
+            #[test]
+            fn b() -> $crate::anyhow::Result<()> {
+                $crate::nom::Nom::<$day>::test_part_b()
+            }
+
+            #[test]
+            fn generated() -> $crate::anyhow::Result<()> {
+                $crate::nom::Nom::<$day>::test_generated()
+            }
+
+            $crate::sample_tests!($crate::nom::Nom<$day>, $name);
+        }
+
+        fn main() -> $crate::anyhow::Result<()> {
+            <$crate::nom::Nom<$day> as $crate::Solution>::main()
+        }
+
+        $crate::register!($crate::nom::Nom<$day>);
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_error_while_solving_can_be_located() {
+        const INPUT: &str = "vJrwpWtwJgWrhcsFMMfFFhFp\nab#cd#\n";
+        let line = INPUT.lines().nth(1).unwrap();
+        let error = SolveError::from(ParseError::new(line, 2, "illegal character '#'"));
+        let error = crate::diagnostic::locate(error.into_anyhow(), INPUT, None);
+        let error = error.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((error.line(), error.column()), (2, 3));
+    }
+
+    #[test]
+    fn no_answer_keeps_its_message() {
+        let error = SolveError::no_answer("there's no path to E").into_anyhow();
+        assert_eq!(error.to_string(), "there's no path to E");
+        assert!(matches!(
+            error.downcast_ref::<SolveError>(),
+            Some(SolveError::NoAnswer(_))
+        ));
+    }
+}
@@ -0,0 +1,552 @@
+//! Sets of values made of whole ranges at a time, in one dimension with
+//! [`IntervalSet`] or several with [`BoxSet`], for days where there are far
+//! too many values to handle one by one.
+//!
+//! Ranges are half-open like `start..end`, so inclusive bounds from an input
+//! need one added to the end.
+use std::ops::{Add, Div, Range, Sub};
+
+/// Values covered by any number of ranges, kept sorted, disjoint and not
+/// touching each other.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self { ranges: Vec::new() }
+    }
+}
+
+impl<T: Copy + Ord> IntervalSet<T> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The ranges covered, lowest first.
+    #[must_use]
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The lowest value in the set.
+    #[must_use]
+    pub fn first(&self) -> Option<T> {
+        self.ranges.first().map(|range| range.start)
+    }
+
+    #[must_use]
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.ranges.partition_point(|range| range.end <= value);
+        self.ranges.get(i).is_some_and(|range| range.start <= value)
+    }
+
+    /// Adds `range`, merging it with the ranges it overlaps or touches.
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+        let first = self.ranges.partition_point(|r| r.end < range.start);
+        let last = self.ranges.partition_point(|r| r.start <= range.end);
+        let merged = match self.ranges.get(first..last) {
+            Some([lowest, .., highest] | [lowest @ highest]) => {
+                lowest.start.min(range.start)..highest.end.max(range.end)
+            }
+            _ => range,
+        };
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        self.ranges.iter().chain(&other.ranges).cloned().collect()
+    }
+
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (mut i, mut j) = (0, 0);
+        let mut ranges = Vec::new();
+        while let (Some(a), Some(b)) = (self.ranges.get(i), other.ranges.get(j)) {
+            let overlap = a.start.max(b.start)..a.end.min(b.end);
+            if !overlap.is_empty() {
+                ranges.push(overlap);
+            }
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { ranges }
+    }
+
+    /// The values in `self` that aren't in `other`.
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let mut j = 0;
+        for range in &self.ranges {
+            let mut start = range.start;
+            while other.ranges.get(j).is_some_and(|hole| hole.end <= start) {
+                j += 1;
+            }
+            for hole in other.ranges[j..]
+                .iter()
+                .take_while(|hole| hole.start < range.end)
+            {
+                if start < hole.start {
+                    ranges.push(start..hole.start);
+                }
+                start = start.max(hole.end);
+            }
+            if start < range.end {
+                ranges.push(start..range.end);
+            }
+        }
+        Self { ranges }
+    }
+
+    /// The values below `at`, and the rest.
+    #[must_use]
+    pub fn split_at(&self, at: T) -> (Self, Self) {
+        let (mut below, mut above) = (Self::new(), Self::new());
+        for range in &self.ranges {
+            if range.end <= at {
+                below.ranges.push(range.clone());
+            } else if range.start >= at {
+                above.ranges.push(range.clone());
+            } else {
+                below.ranges.push(range.start..at);
+                above.ranges.push(at..range.end);
+            }
+        }
+        (below, above)
+    }
+
+    /// The values `pred` holds for, and the rest. `pred` has to hold either
+    /// below some threshold or from it on, which is found by bisecting.
+    #[must_use]
+    pub fn split(&self, pred: impl Fn(T) -> bool) -> (Self, Self)
+    where
+        T: Add<Output = T> + Sub<Output = T> + Div<Output = T> + From<u8>,
+    {
+        let (Some(first), Some(last)) = (self.ranges.first(), self.ranges.last()) else {
+            return (Self::new(), Self::new());
+        };
+        let (below, above) = self.split_at(threshold(first.start..last.end, &pred));
+        if pred(first.start) {
+            (below, above)
+        } else {
+            (above, below)
+        }
+    }
+
+    /// Moves the values in each piece's source range to start at its
+    /// destination, keeping the values that aren't in any piece where they
+    /// are. A value in several pieces' sources goes with the first.
+    #[must_use]
+    pub fn map_linear(&self, pieces: impl IntoIterator<Item = (Range<T>, T)>) -> Self
+    where
+        T: Add<Output = T> + Sub<Output = T>,
+    {
+        let mut unmapped = self.clone();
+        let mut mapped = Vec::new();
+        for (source, destination) in pieces {
+            let offset = |value| destination + (value - source.start);
+            let source = Self::from(source.clone());
+            let moved = unmapped.intersection(&source);
+            unmapped = unmapped.difference(&source);
+            mapped.extend(moved.ranges.iter().map(|r| offset(r.start)..offset(r.end)));
+        }
+        mapped.into_iter().chain(unmapped.ranges).collect()
+    }
+
+    /// How many values there are in the set.
+    #[must_use]
+    pub fn len(&self) -> u128
+    where
+        T: Into<i128>,
+    {
+        self.ranges.iter().map(|range| width(range)).sum()
+    }
+}
+
+impl<T: Copy + Ord> From<Range<T>> for IntervalSet<T> {
+    fn from(range: Range<T>) -> Self {
+        Self::from_iter([range])
+    }
+}
+
+impl<T: Copy + Ord> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(ranges: I) -> Self {
+        let mut sorted: Vec<_> = ranges.into_iter().filter(|r| !r.is_empty()).collect();
+        sorted.sort_unstable_by_key(|range| range.start);
+        let mut ranges: Vec<Range<T>> = Vec::with_capacity(sorted.len());
+        for range in sorted {
+            match ranges.last_mut() {
+                Some(last) if last.end >= range.start => last.end = last.end.max(range.end),
+                _ => ranges.push(range),
+            }
+        }
+        Self { ranges }
+    }
+}
+
+/// A box in `N` dimensions, as its range along each axis.
+pub type Bounds<T, const N: usize> = [Range<T>; N];
+
+/// Values covered by any number of boxes in `N` dimensions, kept as boxes
+/// that don't overlap.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoxSet<T, const N: usize> {
+    boxes: Vec<Bounds<T, N>>,
+}
+
+impl<T, const N: usize> Default for BoxSet<T, N> {
+    fn default() -> Self {
+        Self { boxes: Vec::new() }
+    }
+}
+
+impl<T: Copy + Ord, const N: usize> BoxSet<T, N> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Boxes that don't overlap, and cover the set between them.
+    #[must_use]
+    pub fn boxes(&self) -> &[Bounds<T, N>] {
+        &self.boxes
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.boxes.is_empty()
+    }
+
+    #[must_use]
+    pub fn contains(&self, point: [T; N]) -> bool {
+        self.boxes.iter().any(|b| {
+            b.iter()
+                .zip(point)
+                .all(|(range, value)| range.contains(&value))
+        })
+    }
+
+    pub fn insert(&mut self, new: Bounds<T, N>) {
+        if new.iter().any(Range::is_empty) {
+            return;
+        }
+        self.remove(&new);
+        self.boxes.push(new);
+    }
+
+    pub fn remove(&mut self, hole: &Bounds<T, N>) {
+        self.boxes = self
+            .boxes
+            .iter()
+            .flat_map(|b| box_difference(b, hole))
+            .collect();
+    }
+
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for b in &other.boxes {
+            union.insert(b.clone());
+        }
+        union
+    }
+
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        let boxes = self
+            .boxes
+            .iter()
+            .flat_map(|a| other.boxes.iter().filter_map(|b| box_intersection(a, b)))
+            .collect();
+        Self { boxes }
+    }
+
+    /// The values in `self` that aren't in `other`.
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+        for b in &other.boxes {
+            difference.remove(b);
+        }
+        difference
+    }
+
+    /// The values below `at` along `axis`, and the rest.
+    #[must_use]
+    pub fn split_at(&self, axis: usize, at: T) -> (Self, Self) {
+        let (mut below, mut above) = (Self::new(), Self::new());
+        for b in &self.boxes {
+            let range = &b[axis];
+            if range.end <= at {
+                below.boxes.push(b.clone());
+            } else if range.start >= at {
+                above.boxes.push(b.clone());
+            } else {
+                let (mut lower, mut upper) = (b.clone(), b.clone());
+                lower[axis].end = at;
+                upper[axis].start = at;
+                below.boxes.push(lower);
+                above.boxes.push(upper);
+            }
+        }
+        (below, above)
+    }
+
+    /// The values `pred` holds for along `axis`, and the rest. `pred` has
+    /// to hold either below some threshold or from it on, which is found by
+    /// bisecting.
+    #[must_use]
+    pub fn split(&self, axis: usize, pred: impl Fn(T) -> bool) -> (Self, Self)
+    where
+        T: Add<Output = T> + Sub<Output = T> + Div<Output = T> + From<u8>,
+    {
+        let ranges = self.boxes.iter().map(|b| &b[axis]);
+        let (Some(start), Some(end)) = (
+            ranges.clone().map(|range| range.start).min(),
+            ranges.map(|range| range.end).max(),
+        ) else {
+            return (Self::new(), Self::new());
+        };
+        let (below, above) = self.split_at(axis, threshold(start..end, &pred));
+        if pred(start) {
+            (below, above)
+        } else {
+            (above, below)
+        }
+    }
+
+    /// How many points there are in the set.
+    #[must_use]
+    pub fn volume(&self) -> u128
+    where
+        T: Into<i128>,
+    {
+        self.boxes
+            .iter()
+            .map(|b| b.iter().map(width).product::<u128>())
+            .sum()
+    }
+}
+
+impl<T: Copy + Ord, const N: usize> From<Bounds<T, N>> for BoxSet<T, N> {
+    fn from(b: Bounds<T, N>) -> Self {
+        let mut set = Self::new();
+        set.insert(b);
+        set
+    }
+}
+
+impl<T: Copy + Ord, const N: usize> FromIterator<Bounds<T, N>> for BoxSet<T, N> {
+    fn from_iter<I: IntoIterator<Item = Bounds<T, N>>>(boxes: I) -> Self {
+        let mut set = Self::new();
+        for b in boxes {
+            set.insert(b);
+        }
+        set
+    }
+}
+
+/// The first value in `range` that `pred` disagrees with its start on, or
+/// the end if there's none, assuming `pred` changes at most once.
+fn threshold<T>(range: Range<T>, pred: impl Fn(T) -> bool) -> T
+where
+    T: Copy + Ord + Add<Output = T> + Sub<Output = T> + Div<Output = T> + From<u8>,
+{
+    let side = pred(range.start);
+    let (mut agrees, mut disagrees) = (range.start, range.end);
+    while disagrees - agrees > T::from(1) {
+        let mid = agrees + (disagrees - agrees) / T::from(2);
+        if pred(mid) == side {
+            agrees = mid;
+        } else {
+            disagrees = mid;
+        }
+    }
+    disagrees
+}
+
+fn width<T: Copy + Into<i128>>(range: &Range<T>) -> u128 {
+    (range.end.into() - range.start.into()).unsigned_abs()
+}
+
+fn box_intersection<T: Copy + Ord, const N: usize>(
+    a: &Bounds<T, N>,
+    b: &Bounds<T, N>,
+) -> Option<Bounds<T, N>> {
+    let mut overlap = a.clone();
+    for (range, other) in overlap.iter_mut().zip(b) {
+        *range = range.start.max(other.start)..range.end.min(other.end);
+        if range.is_empty() {
+            return None;
+        }
+    }
+    Some(overlap)
+}
+
+/// At most 2 boxes per axis, covering what's in `b` but not `hole`.
+fn box_difference<T: Copy + Ord, const N: usize>(
+    b: &Bounds<T, N>,
+    hole: &Bounds<T, N>,
+) -> Vec<Bounds<T, N>> {
+    if box_intersection(b, hole).is_none() {
+        return vec![b.clone()];
+    }
+    let mut pieces = Vec::new();
+    // Slices off whatever sticks out of the hole, one axis at a time
+    let mut rest = b.clone();
+    for axis in 0..N {
+        let (range, hole) = (rest[axis].clone(), &hole[axis]);
+        if range.start < hole.start {
+            let mut piece = rest.clone();
+            piece[axis] = range.start..hole.start;
+            pieces.push(piece);
+        }
+        if hole.end < range.end {
+            let mut piece = rest.clone();
+            piece[axis] = hole.end..range.end;
+            pieces.push(piece);
+        }
+        rest[axis] = range.start.max(hole.start)..range.end.min(hole.end);
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::generate::Rng;
+
+    fn random_set(rng: &mut Rng) -> IntervalSet<i32> {
+        (0..rng.usize(0..5))
+            .map(|_| {
+                let start = rng.i32(-20..20);
+                start..start + rng.i32(0..10)
+            })
+            .collect()
+    }
+
+    fn values(set: &IntervalSet<i32>) -> BTreeSet<i32> {
+        set.ranges().iter().flat_map(Clone::clone).collect()
+    }
+
+    #[test]
+    fn interval_set_algebra() {
+        let mut rng = Rng::with_seed(0);
+        for _ in 0..200 {
+            let (a, b) = (random_set(&mut rng), random_set(&mut rng));
+            for set in [&a, &b, &a.union(&b), &a.intersection(&b), &a.difference(&b)] {
+                let normalized = set.ranges().iter().cloned().collect::<IntervalSet<_>>();
+                assert_eq!(set, &normalized);
+            }
+            let (va, vb) = (values(&a), values(&b));
+            assert_eq!(values(&a.union(&b)), &va | &vb);
+            assert_eq!(values(&a.intersection(&b)), &va & &vb);
+            assert_eq!(values(&a.difference(&b)), &va - &vb);
+            assert_eq!(a.len(), va.len() as u128);
+            assert_eq!(a.first(), va.first().copied());
+            assert!((-25..35).all(|v| a.contains(v) == va.contains(&v)));
+            let (below, above) = a.split_at(3);
+            assert_eq!(
+                values(&below),
+                va.iter().copied().filter(|&v| v < 3).collect()
+            );
+            assert_eq!(
+                values(&above),
+                va.iter().copied().filter(|&v| v >= 3).collect()
+            );
+            let (pass, fail) = a.split(|v| v > -4);
+            assert_eq!(
+                values(&pass),
+                va.iter().copied().filter(|&v| v > -4).collect()
+            );
+            assert_eq!(
+                values(&fail),
+                va.iter().copied().filter(|&v| v <= -4).collect()
+            );
+            let mut inserted = a.clone();
+            for range in b.ranges() {
+                inserted.insert(range.clone());
+            }
+            assert_eq!(inserted, a.union(&b));
+        }
+    }
+
+    #[test]
+    fn map_linear() {
+        let set = IntervalSet::from(0_u32..20);
+        let mapped = set.map_linear([(5..10, 100), (8..12, 200), (30..40, 0)]);
+        let expected = IntervalSet::from_iter([0..5, 100..105, 202..204, 12..20]);
+        assert_eq!(mapped, expected);
+    }
+
+    fn points_in(b: &Bounds<i8, 3>) -> Vec<[i8; 3]> {
+        let [xs, ys, zs] = b.clone();
+        let mut points = Vec::new();
+        for x in xs {
+            for y in ys.clone() {
+                points.extend(zs.clone().map(|z| [x, y, z]));
+            }
+        }
+        points
+    }
+
+    #[test]
+    fn box_set_volume() {
+        let mut rng = Rng::with_seed(0);
+        let mut set = BoxSet::new();
+        let mut points = BTreeSet::new();
+        for _ in 0..30 {
+            let b: Bounds<i8, 3> = [(); 3].map(|()| {
+                let start = rng.i8(-5..5);
+                start..start + rng.i8(0..5)
+            });
+            let inside = points_in(&b);
+            if rng.bool() {
+                set.insert(b);
+                points.extend(inside);
+            } else {
+                set.remove(&b);
+                for point in inside {
+                    points.remove(&point);
+                }
+            }
+            assert_eq!(set.volume(), points.len() as u128);
+        }
+        for point in points_in(&[-6..10, -6..10, -6..10]) {
+            assert_eq!(set.contains(point), points.contains(&point));
+        }
+        let (below, above) = set.split_at(1, 0);
+        assert_eq!(below.volume() + above.volume(), set.volume());
+        assert!(below.boxes().iter().all(|b| b[1].end <= 0));
+        let cube = BoxSet::from([-2..2, -2..2, -2..2]);
+        let inside = points
+            .iter()
+            .filter(|p| p.iter().all(|v| (-2..2).contains(v)));
+        assert_eq!(set.intersection(&cube).volume(), inside.count() as u128);
+        assert_eq!(
+            set.union(&cube).volume() + set.intersection(&cube).volume(),
+            set.volume() + 64
+        );
+        assert_eq!(
+            set.difference(&cube).volume(),
+            set.volume() - set.intersection(&cube).volume()
+        );
+    }
+}
@@ -0,0 +1,142 @@
+//! The `--format json` output: a JSON object per line for each part that was
+//! run, shared by the days' binaries and the workspace runner.
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::{memory::Allocs, Answers, Step};
+
+/// How a day's results are printed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Timings and answers for people to read
+    #[default]
+    Text,
+    /// A [`Report`] per line
+    Json,
+}
+
+impl std::str::FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => anyhow::bail!("unknown format {s:?}, expected text or json"),
+        }
+    }
+}
+
+/// The result of running one part.
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub year: Option<u16>,
+    pub day: Option<u8>,
+    /// `"a"` or `"b"`
+    pub part: &'static str,
+    pub answer: Option<String>,
+    /// How long `shared` took
+    pub parse_ns: Option<u128>,
+    /// How long the part itself took
+    pub solve_ns: Option<u128>,
+    /// What `shared` allocated, when counting allocations
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parse_allocs: Option<Allocs>,
+    /// What the part itself allocated, when counting allocations
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub solve_allocs: Option<Allocs>,
+    /// The error's context chain, outermost first, if the run failed
+    pub error: Option<Vec<String>>,
+}
+
+impl Report {
+    /// A report per part in `part` (`None` for both), from the outcome of a
+    /// [`RunFn`](crate::RunFn). A part that failed gets its error, and when
+    /// `shared` or the whole run failed every part gets that error.
+    #[must_use]
+    pub fn from_run(
+        year: Option<u16>,
+        day: Option<u8>,
+        part: Option<&str>,
+        steps: &anyhow::Result<Vec<Step>>,
+    ) -> Vec<Self> {
+        let parts = match part {
+            Some("a") => &["a"][..],
+            Some("b") => &["b"],
+            _ => &["a", "b"],
+        };
+        let empty = |part| Self {
+            year,
+            day,
+            part,
+            answer: None,
+            parse_ns: None,
+            solve_ns: None,
+            parse_allocs: None,
+            solve_allocs: None,
+            error: None,
+        };
+        let chain = |e: &anyhow::Error| Some(e.chain().map(ToString::to_string).collect());
+        match steps {
+            Ok(steps) => {
+                let shared = steps.iter().find(|(step, ..)| *step == "shared");
+                let parse_ns = shared.map(|(_, _, elapsed, _)| elapsed.as_nanos());
+                let parse_allocs = shared.and_then(|&(.., allocs)| allocs);
+                parts
+                    .iter()
+                    .filter_map(|&part| {
+                        let parsed = Self {
+                            parse_ns,
+                            parse_allocs,
+                            ..empty(part)
+                        };
+                        if let Some((_, Err(e), ..)) = shared {
+                            return Some(Self {
+                                error: chain(e),
+                                ..parsed
+                            });
+                        }
+                        let (_, outcome, elapsed, allocs) =
+                            steps.iter().find(|(step, ..)| *step == part)?;
+                        Some(Self {
+                            answer: outcome.as_ref().ok().cloned(),
+                            solve_ns: Some(elapsed.as_nanos()),
+                            solve_allocs: *allocs,
+                            error: outcome.as_ref().err().and_then(chain),
+                            ..parsed
+                        })
+                    })
+                    .collect()
+            }
+            Err(e) => parts
+                .iter()
+                .map(|&part| Self {
+                    error: chain(e),
+                    ..empty(part)
+                })
+                .collect(),
+        }
+    }
+
+    /// Prints the report as a line of JSON.
+    pub fn print(&self) -> anyhow::Result<()> {
+        println!("{}", serde_json::to_string(self)?);
+        Ok(())
+    }
+}
+
+/// The year and day of the day whose `answers.toml` this is, from its
+/// `<year>/src/bin/day<n>` directory.
+pub(crate) fn year_and_day(answers: &Answers) -> (Option<u16>, Option<u8>) {
+    let mut names = Path::new(answers.path)
+        .ancestors()
+        .skip(1)
+        .map(|path| path.file_name().and_then(|name| name.to_str()));
+    let day = names
+        .next()
+        .flatten()
+        .and_then(|day| day.strip_prefix("day")?.parse().ok());
+    let year = names.nth(2).flatten().and_then(|year| year.parse().ok());
+    (year, day)
+}
@@ -0,0 +1,276 @@
+//! Shortest paths through a graph that's only known by each state's
+//! successors, from a start state to the first one `success` holds for.
+//!
+//! States are remembered in a hash map along with how they were reached, so
+//! they should be small and cheap to clone, like positions with a little
+//! extra state.
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+use fxhash::FxHashMap;
+
+/// A shortest path that was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<S, C> {
+    /// The sum of the steps' costs
+    pub cost: C,
+    /// From the start to the state `success` held for, both included
+    pub states: Vec<S>,
+}
+
+/// The states that were reached, with the cheapest known cost and the state
+/// it was reached from.
+struct Reached<S, C> {
+    nodes: Vec<(S, C, Option<usize>)>,
+    index: FxHashMap<S, usize>,
+}
+
+impl<S: Clone + Eq + Hash, C: Copy> Reached<S, C> {
+    fn new(start: S, cost: C) -> Self {
+        Self {
+            index: FxHashMap::from_iter([(start.clone(), 0)]),
+            nodes: vec![(start, cost, None)],
+        }
+    }
+
+    /// Follows the parents back from `node` to the start.
+    fn path(&self, node: usize) -> Path<S, C> {
+        let mut states = Vec::new();
+        let mut next = Some(node);
+        while let Some(node) = next {
+            let (state, _, parent) = &self.nodes[node];
+            states.push(state.clone());
+            next = *parent;
+        }
+        states.reverse();
+        Path {
+            cost: self.nodes[node].1,
+            states,
+        }
+    }
+}
+
+/// Dijkstra's algorithm. Step costs shouldn't be negative.
+pub fn dijkstra<S, C, I>(
+    start: S,
+    successors: impl FnMut(&S) -> I,
+    success: impl FnMut(&S) -> bool,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar(start, successors, |_| C::default(), success)
+}
+
+/// A*, searching states in order of their cost so far plus `heuristic`'s
+/// estimate of the cost left. The path is only the shortest if the estimate
+/// is never more than the actual cost left.
+pub fn astar<S, C, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut success: impl FnMut(&S) -> bool,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut queue = BinaryHeap::from([Reverse((heuristic(&start), C::default(), 0))]);
+    let mut reached = Reached::new(start, C::default());
+    while let Some(Reverse((_, cost, node))) = queue.pop() {
+        let (state, best_cost, _) = &reached.nodes[node];
+        if cost > *best_cost {
+            // Reached more cheaply since it was queued
+            continue;
+        }
+        if success(state) {
+            return Some(reached.path(node));
+        }
+        for (next, step) in successors(&state.clone()) {
+            let cost = cost + step;
+            let next_node = match reached.index.entry(next) {
+                Entry::Occupied(entry) => {
+                    let next_node = *entry.get();
+                    let (_, best_cost, parent) = &mut reached.nodes[next_node];
+                    if cost >= *best_cost {
+                        continue;
+                    }
+                    (*best_cost, *parent) = (cost, Some(node));
+                    next_node
+                }
+                Entry::Vacant(entry) => {
+                    let next_node = reached.nodes.len();
+                    reached.nodes.push((entry.key().clone(), cost, Some(node)));
+                    entry.insert(next_node);
+                    next_node
+                }
+            };
+            let estimate = cost + heuristic(&reached.nodes[next_node].0);
+            queue.push(Reverse((estimate, cost, next_node)));
+        }
+    }
+    None
+}
+
+/// Breadth-first search, for when every step costs the same. The cost is
+/// the number of steps.
+pub fn bfs<S, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut success: impl FnMut(&S) -> bool,
+) -> Option<Path<S, usize>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut queue = VecDeque::from([0]);
+    let mut reached = Reached::new(start, 0);
+    while let Some(node) = queue.pop_front() {
+        let (state, steps, _) = &reached.nodes[node];
+        if success(state) {
+            return Some(reached.path(node));
+        }
+        let steps = steps + 1;
+        for next in successors(&state.clone()) {
+            if let Entry::Vacant(entry) = reached.index.entry(next) {
+                queue.push_back(reached.nodes.len());
+                reached.nodes.push((entry.key().clone(), steps, Some(node)));
+                entry.insert(reached.nodes.len() - 1);
+            }
+        }
+    }
+    None
+}
+
+/// The cost of the shortest paths, like [`dijkstra`], and how many different
+/// paths have that cost. Step costs should be positive.
+pub fn count_shortest_paths<S, C, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut success: impl FnMut(&S) -> bool,
+) -> Option<(C, u64)>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut queue = BinaryHeap::from([Reverse((C::default(), 0))]);
+    let mut reached = Reached::new(start, C::default());
+    // How many paths reach each node at its best known cost
+    let mut counts = vec![1];
+    while let Some(Reverse((cost, node))) = queue.pop() {
+        let (state, best_cost, _) = &reached.nodes[node];
+        if cost > *best_cost {
+            continue;
+        }
+        if success(state) {
+            return Some((cost, counts[node]));
+        }
+        for (next, step) in successors(&state.clone()) {
+            let cost = cost + step;
+            match reached.index.entry(next) {
+                Entry::Occupied(entry) => {
+                    let next_node = *entry.get();
+                    let best_cost = &mut reached.nodes[next_node].1;
+                    if cost == *best_cost {
+                        counts[next_node] += counts[node];
+                    } else if cost < *best_cost {
+                        *best_cost = cost;
+                        counts[next_node] = counts[node];
+                        queue.push(Reverse((cost, next_node)));
+                    }
+                }
+                Entry::Vacant(entry) => {
+                    let next_node = reached.nodes.len();
+                    reached.nodes.push((entry.key().clone(), cost, Some(node)));
+                    entry.insert(next_node);
+                    counts.push(counts[node]);
+                    queue.push(Reverse((cost, next_node)));
+                }
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{Grid, Pos, ORTHOGONAL};
+
+    // The wall forces a detour, over the top or along the bottom
+    const MAZE: &str = "S...\n.##.\n...E\n";
+
+    fn maze() -> anyhow::Result<(Grid<u8>, Pos, Pos)> {
+        let grid = Grid::parse(MAZE)?;
+        let start = grid.find(&b'S').unwrap();
+        let end = grid.find(&b'E').unwrap();
+        Ok((grid, start, end))
+    }
+
+    fn open(grid: &Grid<u8>, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        grid.neighbors(pos, &ORTHOGONAL)
+            .filter(|&next| grid[next] != b'#')
+    }
+
+    #[test]
+    fn bfs_path() -> anyhow::Result<()> {
+        let (grid, start, end) = maze()?;
+        let path = bfs(start, |&pos| open(&grid, pos), |&pos| pos == end).unwrap();
+        assert_eq!(path.cost, 5);
+        assert_eq!(path.states.len(), 6);
+        assert_eq!(path.states.first(), Some(&start));
+        assert_eq!(path.states.last(), Some(&end));
+        for (a, b) in path.states.iter().zip(&path.states[1..]) {
+            assert_eq!(a.0.abs_diff(b.0) + a.1.abs_diff(b.1), 1);
+        }
+        assert_eq!(
+            bfs(start, |&pos| open(&grid, pos), |&pos| pos == (1, 1)),
+            None
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn weighted() -> anyhow::Result<()> {
+        let (grid, start, end) = maze()?;
+        // Going down costs 10, so the path goes along the top row first
+        let successors = |&(row, col): &Pos| {
+            open(&grid, (row, col))
+                .map(move |next| (next, if next.0 > row { 10 } else { 1 }))
+                .collect::<Vec<_>>()
+        };
+        let path = dijkstra(start, successors, |&pos| pos == end).unwrap();
+        assert_eq!(path.cost, 23);
+        assert_eq!(path.states[..4], [(0, 0), (0, 1), (0, 2), (0, 3)]);
+        let manhattan = |&(row, col): &Pos| end.0.abs_diff(row) + end.1.abs_diff(col);
+        let path = astar(start, successors, manhattan, |&pos| pos == end).unwrap();
+        assert_eq!(path.cost, 23);
+        Ok(())
+    }
+
+    #[test]
+    fn counting() -> anyhow::Result<()> {
+        let (grid, start, end) = maze()?;
+        let successors = |&pos: &Pos| open(&grid, pos).map(|next| (next, 1));
+        assert_eq!(
+            count_shortest_paths(start, successors, |&pos| pos == end),
+            Some((5, 2))
+        );
+        let open_grid = Grid::parse("...\n...\n...\n")?;
+        let successors = |&pos: &Pos| open(&open_grid, pos).map(|next| (next, 1));
+        // Any order of 2 rights and 2 downs
+        assert_eq!(
+            count_shortest_paths((0, 0), successors, |&pos| pos == (2, 2)),
+            Some((4, 6))
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,17 @@
+//! A front-end for days that parse with winnow.
+use winnow::{error::ContextError, Parser};
+
+use crate::ParseError;
+
+pub trait ParserExt<O>: Parser<&'static str, O, ContextError> {
+    /// Parses all of `input`, converting winnow's error into a
+    /// [`ParseError`] that shows where parsing stopped.
+    fn parse_all(&mut self, input: &'static str) -> anyhow::Result<O>
+    where
+        Self: Sized,
+    {
+        Ok(self.parse(input).map_err(ParseError::from)?)
+    }
+}
+
+impl<O, P: Parser<&'static str, O, ContextError>> ParserExt<O> for P {}
@@ -0,0 +1,320 @@
+//! Downloads puzzle inputs from adventofcode.com, or a stand-in for it.
+//!
+//! Inputs are cached on disk by year and day, so each is only downloaded
+//! once, and requests are spaced out so scripted runs stay polite.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, bail};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// The least time between two requests, across runs.
+const DEFAULT_THROTTLE: Duration = Duration::from_secs(5);
+
+const USER_AGENT: &str = "github.com/Crazytieguy/advent-of-code runner";
+
+pub struct Client {
+    agent: ureq::Agent,
+    base_url: String,
+    /// The `session` cookie of a logged in browser
+    session: Option<String>,
+    cache_dir: PathBuf,
+    throttle: Duration,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: Option<String>, cache_dir: PathBuf) -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new()
+                .user_agent(USER_AGENT)
+                .timeout(Duration::from_secs(30))
+                .build(),
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            session,
+            cache_dir,
+            throttle: DEFAULT_THROTTLE,
+        }
+    }
+
+    /// The client for the workspace: the base URL comes from `AOC_BASE_URL`,
+    /// and the session cookie from `AOC_SESSION` or else the `session` file.
+    pub fn for_workspace(workspace: &Path, base_url: Option<&str>) -> Self {
+        let base_url = base_url
+            .map(String::from)
+            .or_else(|| std::env::var("AOC_BASE_URL").ok())
+            .unwrap_or_else(|| DEFAULT_BASE_URL.to_owned());
+        let session = std::env::var("AOC_SESSION")
+            .ok()
+            .or_else(|| fs::read_to_string(workspace.join("session")).ok())
+            .map(|session| session.trim().to_owned())
+            .filter(|session| !session.is_empty());
+        Self::new(&base_url, session, workspace.join(".aoc-cache"))
+    }
+
+    /// The puzzle input, from the cache unless it's missing or `refresh`.
+    pub fn input(&self, year: u16, day: u8, refresh: bool) -> anyhow::Result<String> {
+        let cached = self.cache_dir.join(format!("{year}/day{day}.txt"));
+        if !refresh && let Ok(input) = fs::read_to_string(&cached) {
+            return Ok(input);
+        }
+        let input = self.get(&format!("/{year}/day/{day}/input"))?;
+        check_input(&input).with_context(|| format!("failed to download {year} day {day}"))?;
+        fs::create_dir_all(
+            cached
+                .parent()
+                .context("cached inputs are in a directory")?,
+        )?;
+        fs::write(&cached, &input)
+            .with_context(|| format!("failed to cache {}", cached.display()))?;
+        Ok(input)
+    }
+
+    /// Where inputs and submission histories are cached.
+    pub fn cache_dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
+    /// Requests `path` with the session cookie, waiting out the throttle.
+    fn get(&self, path: &str) -> anyhow::Result<String> {
+        self.request("GET", path, None)
+    }
+
+    /// Posts `form` to `path`, see [`Client::get`].
+    pub fn post(&self, path: &str, form: &[(&str, &str)]) -> anyhow::Result<String> {
+        self.request("POST", path, Some(form))
+    }
+
+    fn request(
+        &self,
+        method: &str,
+        path: &str,
+        form: Option<&[(&str, &str)]>,
+    ) -> anyhow::Result<String> {
+        let session = self
+            .session
+            .as_deref()
+            .context("no session cookie, put it in the `session` file or set AOC_SESSION")?;
+        self.wait_for_throttle()?;
+        let url = format!("{}{path}", self.base_url);
+        let request = self
+            .agent
+            .request(method, &url)
+            .set("Cookie", &format!("session={session}"));
+        let response = match form {
+            Some(form) => request.send_form(form),
+            None => request.call(),
+        };
+        match response {
+            Ok(response) => Ok(response.into_string()?),
+            Err(ureq::Error::Status(status, response)) => {
+                let body = response.into_string().unwrap_or_default();
+                Err(status_error(status, &body)).with_context(|| format!("{method} {url}"))
+            }
+            Err(e) => Err(e).with_context(|| format!("{method} {url}")),
+        }
+    }
+
+    /// Sleeps until `throttle` has passed since the last request, which is
+    /// recorded in the cache so separate runs are spaced out too.
+    fn wait_for_throttle(&self) -> anyhow::Result<()> {
+        let last_request = self.cache_dir.join("last-request");
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+        let last = fs::read_to_string(&last_request)
+            .ok()
+            .and_then(|nanos| nanos.trim().parse().ok())
+            .map(Duration::from_nanos);
+        if let Some(wait) = last.and_then(|last| (last + self.throttle).checked_sub(now)) {
+            thread::sleep(wait);
+        }
+        fs::create_dir_all(&self.cache_dir)
+            .with_context(|| format!("failed to create {}", self.cache_dir.display()))?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+        fs::write(&last_request, now.as_nanos().to_string())
+            .with_context(|| format!("failed to write {}", last_request.display()))
+    }
+}
+
+/// Explains the site's error responses.
+fn status_error(status: u16, body: &str) -> anyhow::Error {
+    if status == 400 && body.contains("log in") {
+        anyhow::anyhow!("the session cookie was rejected, log in again and update it")
+    } else if status == 404 && body.contains("before it unlocks") {
+        anyhow::anyhow!("the puzzle hasn't unlocked yet")
+    } else {
+        let first_line = body.lines().next().unwrap_or_default();
+        anyhow::anyhow!("the server answered {status}: {first_line}")
+    }
+}
+
+/// Rejects responses that came back successfully but aren't a puzzle input,
+/// like the page a logged out session gets.
+fn check_input(input: &str) -> anyhow::Result<()> {
+    let start = input.trim_start();
+    if start.is_empty() {
+        bail!("the input is empty");
+    }
+    if start.starts_with('<') {
+        bail!("got an HTML page instead of the input, is the session cookie still valid?");
+    }
+    if input.contains("Please log in") || input.contains("before it unlocks") {
+        bail!(
+            "got {:?} instead of the input",
+            input.lines().next().unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+/// A stand-in for the site to test against.
+#[cfg(test)]
+pub(crate) mod stand_in {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+    };
+
+    use super::*;
+
+    pub(crate) struct Request {
+        pub(crate) method: String,
+        pub(crate) path: String,
+        pub(crate) cookie: String,
+        pub(crate) body: String,
+    }
+
+    /// Serves each request with `respond`'s status and body. Returns a
+    /// client for it, caching in a fresh directory named after `test`, and a
+    /// count of the requests.
+    pub(crate) fn serve(
+        test: &str,
+        respond: impl Fn(&Request) -> (u16, String) + Send + 'static,
+    ) -> (Client, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&requests);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let request = read_request(&stream);
+                counter.fetch_add(1, Ordering::SeqCst);
+                let (status, body) = respond(&request);
+                write!(
+                    stream,
+                    "HTTP/1.1 {status} X\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+        });
+        let cache_dir =
+            std::env::temp_dir().join(format!("aoc-client-{test}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&cache_dir);
+        let mut client = Client::new(&base_url, Some("abc".into()), cache_dir);
+        client.throttle = Duration::ZERO;
+        (client, requests)
+    }
+
+    fn read_request(stream: &std::net::TcpStream) -> Request {
+        let mut reader = BufReader::new(stream);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+        let mut parts = request_line.split(' ');
+        let method = parts.next().unwrap_or_default().to_owned();
+        let path = parts.next().unwrap_or_default().to_owned();
+        let (mut cookie, mut length) = (String::new(), 0);
+        loop {
+            let mut header = String::new();
+            reader.read_line(&mut header).unwrap();
+            if header.trim().is_empty() {
+                break;
+            }
+            let (name, value) = header.split_once(':').unwrap_or_default();
+            match name.to_ascii_lowercase().as_str() {
+                "cookie" => value.trim().clone_into(&mut cookie),
+                "content-length" => length = value.trim().parse().unwrap(),
+                _ => {}
+            }
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+        Request {
+            method,
+            path,
+            cookie,
+            body: String::from_utf8(body).unwrap(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::atomic::Ordering, time::Instant};
+
+    use super::{stand_in::serve, *};
+
+    #[test]
+    fn downloads_once() -> anyhow::Result<()> {
+        let (client, requests) = serve("downloads_once", |request| {
+            match (request.path.as_str(), request.cookie.as_str()) {
+                ("/2022/day/1/input", "session=abc") => (200, "1000\n2000\n".into()),
+                _ => (500, "unexpected request".into()),
+            }
+        });
+        assert_eq!(client.input(2022, 1, false)?, "1000\n2000\n");
+        assert_eq!(client.input(2022, 1, false)?, "1000\n2000\n");
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        client.input(2022, 1, true)?;
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_html() {
+        let (client, _) = serve("rejects_html", |_| {
+            (200, "<!DOCTYPE html>\n<html></html>".into())
+        });
+        let error = client.input(2022, 2, false).unwrap_err();
+        assert!(format!("{error:#}").contains("HTML"), "{error:#}");
+        assert!(!client.cache_dir.join("2022/day2.txt").exists());
+    }
+
+    #[test]
+    fn explains_errors() {
+        let (client, _) = serve("explains_errors", |request| match request.path.as_str() {
+            "/2022/day/3/input" => (
+                400,
+                "Puzzle inputs differ by user.  Please log in to get your puzzle input.".into(),
+            ),
+            _ => (
+                404,
+                "Please don't repeatedly request this endpoint before it unlocks!".into(),
+            ),
+        });
+        let error = client.input(2022, 3, false).unwrap_err();
+        assert!(format!("{error:#}").contains("session cookie"), "{error:#}");
+        let error = client.input(2022, 25, false).unwrap_err();
+        assert!(format!("{error:#}").contains("unlocked"), "{error:#}");
+    }
+
+    #[test]
+    fn throttles() -> anyhow::Result<()> {
+        let (mut client, _) = serve("throttles", |_| (200, "1\n".into()));
+        let throttle = Duration::from_millis(300);
+        client.throttle = throttle;
+        let start = Instant::now();
+        client.input(2022, 4, false)?;
+        client.input(2022, 5, false)?;
+        assert!(start.elapsed() >= throttle);
+        Ok(())
+    }
+}
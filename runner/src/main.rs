@@ -0,0 +1,412 @@
+#![warn(clippy::pedantic)]
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::{Context, bail};
+use aoc_core::{
+    memory::{self, Counting},
+    report::Report,
+};
+use clap::{Parser, Subcommand, ValueEnum};
+use client::Client;
+use registry::{NoInput, Registered};
+use submit::Verdict;
+
+mod client;
+mod perf;
+mod registry;
+mod scaffold;
+mod submit;
+mod watch;
+
+/// Only counts allocations once `run --alloc` enables it.
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// Runs solutions from every year in-process.
+#[derive(Parser)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run a single day, or every day of a year with --all
+    Run {
+        year: u16,
+        day: Option<u8>,
+        part: Option<Part>,
+        /// Run every day of the year
+        #[arg(long, conflicts_with_all = ["day", "input"])]
+        all: bool,
+        /// Read the input from this file instead of the embedded data.txt
+        #[arg(long)]
+        input: Option<PathBuf>,
+        /// Print a table, or a JSON object per line for each part
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+        /// Also count how many allocations each step makes, how many bytes
+        /// they add up to, and the peak bytes allocated at once
+        #[arg(long)]
+        alloc: bool,
+    },
+    /// Re-measure every day of a year and compare against its timings.csv
+    Perf {
+        year: u16,
+        /// How much slower than the baseline a day may get, in percent
+        #[arg(long, default_value_t = 10.0)]
+        tolerance: f64,
+        /// How many runs to take the median of, after a warm-up run
+        #[arg(long, default_value_t = 10)]
+        runs: usize,
+        /// The baseline to compare against, the year's timings.csv by default
+        #[arg(long)]
+        timings: Option<PathBuf>,
+    },
+    /// Create a day from its year's template and register it with the runner
+    NewDay {
+        year: u16,
+        day: u8,
+        /// Import this file as the day's data.txt
+        #[arg(long, conflicts_with = "fetch")]
+        input: Option<PathBuf>,
+        /// Download the day's data.txt, see the fetch command
+        #[arg(long)]
+        fetch: bool,
+    },
+    /// Download a day's input into its data.txt, caching it in .aoc-cache
+    ///
+    /// The session cookie is read from `AOC_SESSION` or the `session` file.
+    Fetch {
+        year: u16,
+        day: u8,
+        /// Download the input again even if it's cached
+        #[arg(long)]
+        refresh: bool,
+        /// Where to download from, `AOC_BASE_URL` or adventofcode.com by default
+        #[arg(long)]
+        base_url: Option<String>,
+    },
+    /// Check that inputs fit the assumptions their days' solutions make
+    ///
+    /// Validates every day with an embedded data.txt, or those of a year, or a
+    /// single day's against --input.
+    Validate {
+        year: Option<u16>,
+        day: Option<u8>,
+        /// Validate this file instead of the embedded data.txt
+        #[arg(long, requires = "day")]
+        input: Option<PathBuf>,
+    },
+    /// Re-run a day's tests whenever its solution, samples or input change
+    Watch { year: u16, day: u8 },
+    /// Submit an answer, recording it in the day's answers.toml if it's right
+    ///
+    /// Answers that were rejected before, or are beyond ones that were too high
+    /// or too low, aren't submitted again.
+    Submit {
+        year: u16,
+        day: u8,
+        part: Part,
+        /// The answer, or else the day is run on its embedded data.txt
+        answer: Option<String>,
+        /// Where to submit to, `AOC_BASE_URL` or adventofcode.com by default
+        #[arg(long)]
+        base_url: Option<String>,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Part {
+    A,
+    B,
+}
+
+impl Part {
+    fn as_str(self) -> &'static str {
+        match self {
+            Part::A => "a",
+            Part::B => "b",
+        }
+    }
+}
+
+struct Row {
+    year: u16,
+    day: u8,
+    steps: anyhow::Result<Vec<registry::Step>>,
+}
+
+impl Row {
+    /// Whether one of the steps failed. A day without an input was skipped
+    /// rather than failed.
+    fn failed(&self) -> bool {
+        match &self.steps {
+            Ok(steps) => steps.iter().any(|(_, result, ..)| result.is_err()),
+            Err(e) => !e.is::<NoInput>(),
+        }
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    match Cli::parse().command {
+        Command::Run {
+            year,
+            day,
+            part,
+            all,
+            input,
+            format,
+            alloc,
+        } => {
+            if alloc {
+                memory::enable();
+            }
+            let part = part.map(Part::as_str);
+            let rows = match (day, all) {
+                (Some(day), false) => {
+                    let input = input.as_deref().map(read_input).transpose()?;
+                    let registered = registry::day(year, day)?;
+                    vec![Row {
+                        year,
+                        day,
+                        steps: (registered.run)(input, part),
+                    }]
+                }
+                (None, true) => registry::days(year)?
+                    .iter()
+                    .map(|&(day, registered)| Row {
+                        year,
+                        day,
+                        steps: (registered.run)(None, part),
+                    })
+                    .collect(),
+                _ => bail!("pass either a day or --all"),
+            };
+            match format {
+                Format::Text => print_table(&rows, alloc),
+                Format::Json => print_reports(&rows, part)?,
+            }
+            let failures = rows.iter().filter(|row| row.failed()).count();
+            if failures > 0 {
+                bail!("{failures} of the days failed");
+            }
+        }
+        Command::Perf {
+            year,
+            tolerance,
+            runs,
+            timings,
+        } => {
+            let timings =
+                timings.unwrap_or_else(|| workspace().join(format!("{year}/timings.csv")));
+            perf::check(&workspace(), year, &timings, tolerance, runs)?;
+        }
+        Command::NewDay {
+            year,
+            day,
+            input,
+            fetch,
+        } => {
+            let input = match input {
+                Some(path) => Some(read_input(&path)?.to_owned()),
+                None if fetch => {
+                    Some(Client::for_workspace(&workspace(), None).input(year, day, false)?)
+                }
+                None => None,
+            };
+            scaffold::new_day(&workspace(), year, day, input.as_deref())?;
+        }
+        Command::Fetch {
+            year,
+            day,
+            refresh,
+            base_url,
+        } => fetch(year, day, refresh, base_url.as_deref())?,
+        Command::Validate { year, day, input } => validate(year, day, input.as_deref())?,
+        Command::Watch { year, day } => watch::watch(&workspace(), year, day)?,
+        Command::Submit {
+            year,
+            day,
+            part,
+            answer,
+            base_url,
+        } => {
+            let part = part.as_str();
+            let answer = answer.map_or_else(|| submit::solve(year, day, part), Ok)?;
+            let client = Client::for_workspace(&workspace(), base_url.as_deref());
+            let answers_toml = workspace().join(format!("{year}/src/bin/day{day}/answers.toml"));
+            let verdict = submit::submit(&client, &answers_toml, year, day, part, &answer)?;
+            println!("{verdict}");
+            if verdict != Verdict::Right {
+                bail!("{answer} wasn't accepted");
+            }
+        }
+    }
+    Ok(())
+}
+
+fn workspace() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("the runner is in the workspace")
+        .to_owned()
+}
+
+/// Downloads the day's input into its `data.txt`.
+fn fetch(year: u16, day: u8, refresh: bool, base_url: Option<&str>) -> anyhow::Result<()> {
+    let day_dir = workspace().join(format!("{year}/src/bin/day{day}"));
+    if !day_dir.is_dir() {
+        bail!(
+            "{} doesn't exist, create it with new-day --fetch",
+            day_dir.display()
+        );
+    }
+    let input = Client::for_workspace(&workspace(), base_url).input(year, day, refresh)?;
+    let data = day_dir.join("data.txt");
+    std::fs::write(&data, input).with_context(|| format!("failed to write {}", data.display()))?;
+    println!("wrote {}", data.display());
+    Ok(())
+}
+
+/// Runs the days' `validate` hooks, printing a row per day.
+fn validate(year: Option<u16>, day: Option<u8>, input: Option<&Path>) -> anyhow::Result<()> {
+    let input = input.map(read_input).transpose()?;
+    let days: Vec<(u16, u8, Registered)> = match (year, day) {
+        (Some(year), Some(day)) => vec![(year, day, registry::day(year, day)?)],
+        (Some(year), None) => registry::days(year)?
+            .iter()
+            .map(|&(day, registered)| (year, day, registered))
+            .collect(),
+        (None, _) => registry::YEARS
+            .iter()
+            .flat_map(|&(year, days)| {
+                days.iter()
+                    .map(move |&(day, registered)| (year, day, registered))
+            })
+            .collect(),
+    };
+    let header = ["year", "day", ""].map(String::from);
+    let mut table = vec![header.to_vec()];
+    let mut failures = 0;
+    for (year, day, registered) in days {
+        let status = match (registered.validate)(input) {
+            Ok(()) => "ok".to_owned(),
+            Err(e) if e.is::<NoInput>() => "no input".to_owned(),
+            Err(e) => {
+                failures += 1;
+                let e = format!("{e:#}");
+                format!("error: {}", e.lines().next().unwrap_or_default())
+            }
+        };
+        table.push(vec![year.to_string(), day.to_string(), status]);
+    }
+    print_cells(&table);
+    if failures > 0 {
+        bail!("{failures} of the inputs don't fit their solutions");
+    }
+    Ok(())
+}
+
+/// Solutions borrow from their input for the rest of the program, so the
+/// buffer is leaked.
+fn read_input(path: &Path) -> anyhow::Result<&'static str> {
+    let input = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    Ok(Box::leak(input.into_boxed_str()))
+}
+
+fn print_reports(rows: &[Row], part: Option<&str>) -> anyhow::Result<()> {
+    for row in rows {
+        for report in Report::from_run(Some(row.year), Some(row.day), part, &row.steps) {
+            report.print()?;
+        }
+    }
+    Ok(())
+}
+
+/// With `alloc`, each step's time is followed by what it allocated.
+fn print_table(rows: &[Row], alloc: bool) {
+    let mut header = vec!["year", "day"];
+    for columns in [&["shared"][..], &["a", "time"], &["b", "time"]] {
+        header.extend(columns);
+        if alloc {
+            header.extend(["allocs", "bytes", "peak"]);
+        }
+    }
+    let mut table = vec![header.into_iter().map(String::from).collect()];
+    let mut total = Duration::ZERO;
+    for row in rows {
+        let mut cells = vec![row.year.to_string(), row.day.to_string()];
+        match &row.steps {
+            Ok(steps) => {
+                for name in ["shared", "a", "b"] {
+                    let step = steps.iter().find(|(step, ..)| *step == name);
+                    if let Some((_, Err(e), ..)) = step {
+                        cells.push(error_cell(e));
+                        break;
+                    }
+                    if name != "shared" {
+                        let answer = step.and_then(|(_, answer, ..)| answer.as_ref().ok());
+                        cells.push(answer.map_or("-".into(), Clone::clone));
+                    }
+                    let elapsed = step.map(|&(_, _, elapsed, _)| elapsed);
+                    cells.push(elapsed.map_or("-".into(), |elapsed| format!("{elapsed:.1?}")));
+                    total += elapsed.unwrap_or_default();
+                    if alloc {
+                        match step.and_then(|&(.., allocs)| allocs) {
+                            Some(allocs) => cells.extend([
+                                allocs.count.to_string(),
+                                memory::format_bytes(allocs.bytes),
+                                memory::format_bytes(allocs.peak),
+                            ]),
+                            None => cells.extend(["-", "-", "-"].map(String::from)),
+                        }
+                    }
+                }
+            }
+            Err(e) => cells.push(error_cell(e)),
+        }
+        table.push(cells);
+    }
+    print_cells(&table);
+    println!("total: {total:.1?}");
+}
+
+/// Parse errors go on to show the offending line, which won't fit.
+fn error_cell(e: &anyhow::Error) -> String {
+    let e = format!("{e:#}");
+    format!("error: {}", e.lines().next().unwrap_or_default())
+}
+
+/// Prints `table` with aligned columns, the first row being the header.
+///
+/// Rows shorter than the header span its remaining columns, so they don't
+/// count towards widths.
+fn print_cells(table: &[Vec<String>]) {
+    let columns = table.first().map_or(0, Vec::len);
+    let mut widths = vec![0; columns];
+    for cells in table.iter().filter(|cells| cells.len() == columns) {
+        for (width, cell) in widths.iter_mut().zip(cells) {
+            *width = (*width).max(cell.len());
+        }
+    }
+    for cells in table {
+        let line = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, &width)| format!("{cell:width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        println!("{}", line.trim_end());
+    }
+}
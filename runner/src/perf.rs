@@ -0,0 +1,164 @@
+//! Re-measures a year's days and compares them against its `timings.csv`.
+//!
+//! The baselines are wall-clock times of running each day's release binary,
+//! measured with hyperfine at first and by `bench --save` since, so startup
+//! is part of them. Days are timed the same way here rather than in-process,
+//! or every fast day would look like it got a lot faster.
+//!
+//! The `day,time_ms` baselines are rounded up to whole milliseconds, so a
+//! day is compared against an upper bound of its earlier time, and the
+//! tolerance applies to that. A day whose 1ms baseline was really 0.2ms
+//! only counts as slower past 1ms plus the tolerance, over five times as
+//! slow.
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::{Context, bail};
+use aoc_core::bench::{median, time_process};
+
+use crate::registry;
+
+/// One row of a `timings.csv`.
+struct Baseline {
+    day: u8,
+    /// `None` when the row times the whole day
+    part: Option<&'static str>,
+    ms: f64,
+}
+
+/// Reads either layout of `timings.csv`: `day,time_ms` for whole days, or
+/// hyperfine's `problem,mean,stddev,median` with a row per part, where the
+/// median is the baseline.
+fn read_baselines(path: &Path) -> anyhow::Result<Vec<Baseline>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let mut lines = contents.lines();
+    let header = lines.next().unwrap_or_default();
+    let ms_column = match header {
+        "day,time_ms" => 1,
+        "problem,mean,stddev,median" => 3,
+        _ => bail!("{} has an unknown header {header:?}", path.display()),
+    };
+    lines
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let columns: Vec<_> = line.split(',').collect();
+            let (day, part) = match columns[0].split_once('-') {
+                Some((day, "a")) => (day, Some("a")),
+                Some((day, "b")) => (day, Some("b")),
+                Some(_) => bail!("unexpected problem {:?}", columns[0]),
+                None => (columns[0], None),
+            };
+            let ms = columns
+                .get(ms_column)
+                .with_context(|| format!("missing a time in {line:?}"))?;
+            Ok(Baseline {
+                day: day
+                    .parse()
+                    .with_context(|| format!("bad day in {line:?}"))?,
+                part,
+                ms: ms
+                    .parse()
+                    .with_context(|| format!("bad time in {line:?}"))?,
+            })
+        })
+        .collect()
+}
+
+/// Builds the year's days in release mode, in a target directory of their
+/// own since every year has binaries with the same names.
+fn build(workspace: &Path, year: u16) -> anyhow::Result<PathBuf> {
+    let target = workspace.join(format!("target/perf/{year}"));
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".into());
+    let status = Command::new(cargo)
+        .current_dir(workspace)
+        .args([
+            "build",
+            "--release",
+            "--bins",
+            "-p",
+            &format!("advent-{year}"),
+        ])
+        .arg("--target-dir")
+        .arg(&target)
+        .status()?;
+    if !status.success() {
+        bail!("building {year}'s days failed");
+    }
+    Ok(target.join("release"))
+}
+
+/// The median wall-clock time of `runs` runs of the day's binary after a
+/// warm-up run, or `None` if the day has no input to run on.
+fn measure(
+    workspace: &Path,
+    bins: &Path,
+    year: u16,
+    baseline: &Baseline,
+    runs: usize,
+) -> anyhow::Result<Option<f64>> {
+    registry::day(year, baseline.day)?;
+    let name = format!("day{}", baseline.day);
+    // The same check the build script makes before embedding the input
+    let data = workspace.join(format!("{year}/src/bin/{name}/data.txt"));
+    if std::fs::read_to_string(data).unwrap_or_default().is_empty() {
+        return Ok(None);
+    }
+    let mut command = Command::new(bins.join(name));
+    command.args(baseline.part);
+    Ok(Some(median(&time_process(&mut command, runs, 1)?)))
+}
+
+/// Prints a row per baseline and fails if any day got slower than its
+/// baseline by more than `tolerance` percent.
+pub fn check(
+    workspace: &Path,
+    year: u16,
+    timings: &Path,
+    tolerance: f64,
+    runs: usize,
+) -> anyhow::Result<()> {
+    if runs == 0 {
+        bail!("--runs should be at least 1");
+    }
+    let bins = build(workspace, year)?;
+    let header = ["day", "baseline", "now", "change", ""].map(String::from);
+    let mut table = vec![header.to_vec()];
+    let mut regressions = 0;
+    for baseline in read_baselines(timings)? {
+        let name = match baseline.part {
+            Some(part) => format!("{:02}-{part}", baseline.day),
+            None => format!("{:02}", baseline.day),
+        };
+        let mut cells = vec![name, format!("{:.2}ms", baseline.ms)];
+        match measure(workspace, &bins, year, &baseline, runs) {
+            Ok(Some(ms)) => {
+                let change = (ms / baseline.ms - 1.) * 100.;
+                let status = if change > tolerance {
+                    regressions += 1;
+                    "slower"
+                } else {
+                    "ok"
+                };
+                cells.extend([
+                    format!("{ms:.2}ms"),
+                    format!("{change:+.0}%"),
+                    status.into(),
+                ]);
+            }
+            Ok(None) => cells.extend(["-".into(), "-".into(), "no input".into()]),
+            Err(e) => {
+                regressions += 1;
+                cells.extend(["-".into(), "-".into(), format!("error: {e:#}")]);
+            }
+        }
+        table.push(cells);
+    }
+    crate::print_cells(&table);
+    if regressions > 0 {
+        bail!("{regressions} of {year}'s timings regressed by more than {tolerance}%");
+    }
+    Ok(())
+}
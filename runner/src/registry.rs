@@ -0,0 +1,24 @@
+pub use aoc_core::{NoInput, Registered, Step};
+
+pub const YEARS: &[(u16, &[(u8, Registered)])] = &[
+    (2021, advent_2021::DAYS),
+    (2022, advent_2022::DAYS),
+    (2023, advent_2023::DAYS),
+    (2024, advent_2024::DAYS),
+];
+
+pub fn days(year: u16) -> anyhow::Result<&'static [(u8, Registered)]> {
+    YEARS
+        .iter()
+        .find(|&&(y, _)| y == year)
+        .map(|&(_, days)| days)
+        .ok_or_else(|| anyhow::anyhow!("no solutions for {year}"))
+}
+
+pub fn day(year: u16, day: u8) -> anyhow::Result<Registered> {
+    days(year)?
+        .iter()
+        .find(|&&(d, _)| d == day)
+        .map(|&(_, registered)| registered)
+        .ok_or_else(|| anyhow::anyhow!("no solution for {year} day {day}"))
+}
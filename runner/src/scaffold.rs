@@ -0,0 +1,167 @@
+//! The `new-day` command: copies a year's template into a new day and
+//! registers it with the runner.
+use std::{fs, path::Path};
+
+use anyhow::{Context, bail};
+
+/// Scaffolds `<year>/src/bin/day<day>` from the year's template, with an
+/// empty `answers.toml` for `--record` to fill in, and `input` as its
+/// `data.txt` if given.
+pub fn new_day(workspace: &Path, year: u16, day: u8, input: Option<&str>) -> anyhow::Result<()> {
+    if !(1..=25).contains(&day) {
+        bail!("there's no day {day}, days go from 1 to 25");
+    }
+    let bin = workspace.join(format!("{year}/src/bin"));
+    let template = bin.join("template");
+    if !template.is_dir() {
+        bail!("{year} has no template at {}", template.display());
+    }
+    let name = format!("day{day}");
+    let day_dir = bin.join(&name);
+    if day_dir.join("main.rs").exists() {
+        bail!("{} already exists", day_dir.display());
+    }
+    fs::create_dir_all(&day_dir)
+        .with_context(|| format!("failed to create {}", day_dir.display()))?;
+    for entry in fs::read_dir(&template)? {
+        let from = entry?.path();
+        let to = day_dir.join(from.file_name().context("template files have names")?);
+        if to.exists() {
+            continue;
+        }
+        let contents = fs::read_to_string(&from)
+            .with_context(|| format!("failed to read {}", from.display()))?;
+        // The template's stub answers would make the sample tests fail
+        let contents = match from.file_name().and_then(|name| name.to_str()) {
+            Some("main.rs") => contents.replace("template", &name),
+            Some("samples.toml") => "[sample]\n".to_owned(),
+            _ => contents,
+        };
+        fs::write(&to, contents).with_context(|| format!("failed to write {}", to.display()))?;
+    }
+    let answers = day_dir.join("answers.toml");
+    if !answers.exists() {
+        fs::write(&answers, "")
+            .with_context(|| format!("failed to write {}", answers.display()))?;
+    }
+    if let Some(input) = input {
+        let data = day_dir.join("data.txt");
+        fs::write(&data, input).with_context(|| format!("failed to write {}", data.display()))?;
+    }
+    register(&workspace.join(format!("{year}/src/days.rs")), day)?;
+    println!("created {}", day_dir.display());
+    println!("run its tests with `cargo test -p advent-{year} --bin {name}`");
+    Ok(())
+}
+
+/// Adds the day to the year's `days.rs`, keeping the modules in the order
+/// they're listed in and `DAYS` sorted by day.
+fn register(days_rs: &Path, day: u8) -> anyhow::Result<()> {
+    let contents = fs::read_to_string(days_rs)
+        .with_context(|| format!("failed to read {}", days_rs.display()))?;
+    let name = format!("day{day}");
+    let module = format!("#[path = \"bin/{name}/main.rs\"]\nmod {name};\n");
+    if contents.contains(&module) {
+        return Ok(());
+    }
+    let mut lines: Vec<String> = contents.lines().map(String::from).collect();
+    let module_at = lines
+        .iter()
+        .position(|line| {
+            line.strip_prefix("mod ")
+                .and_then(|module| module.strip_suffix(';'))
+                .is_some_and(|module| module > name.as_str())
+        })
+        .map(|i| i - 1)
+        .or_else(|| {
+            let last = lines.iter().rposition(|line| line.starts_with("mod "))?;
+            Some(last + 1)
+        })
+        .with_context(|| format!("{} has no day modules", days_rs.display()))?;
+    lines.splice(
+        module_at..module_at,
+        [
+            format!("#[path = \"bin/{name}/main.rs\"]"),
+            format!("mod {name};"),
+        ],
+    );
+    let days_start = lines
+        .iter()
+        .position(|line| line.starts_with("pub const DAYS"))
+        .with_context(|| format!("{} has no DAYS", days_rs.display()))?;
+    let entry_at = lines[days_start..]
+        .iter()
+        .position(|line| {
+            let line = line.trim();
+            line == "];"
+                || line
+                    .strip_prefix('(')
+                    .and_then(|entry| entry.split_once(','))
+                    .and_then(|(d, _)| d.parse::<u8>().ok())
+                    .is_some_and(|d| d > day)
+        })
+        .map(|i| days_start + i)
+        .with_context(|| format!("{}'s DAYS isn't closed", days_rs.display()))?;
+    lines.insert(entry_at, format!("    ({day}, {name}::REGISTERED),"));
+    fs::write(days_rs, lines.join("\n") + "\n")
+        .with_context(|| format!("failed to write {}", days_rs.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAYS_RS: &str = "use crate::Registered;
+
+#[path = \"bin/day1/main.rs\"]
+mod day1;
+#[path = \"bin/day5/main.rs\"]
+mod day5;
+
+pub const DAYS: &[(u8, Registered)] = &[
+    (1, day1::REGISTERED),
+    (5, day5::REGISTERED),
+];
+";
+
+    /// A workspace with a year that has a template and days 1 and 5.
+    fn workspace(test: &str) -> anyhow::Result<std::path::PathBuf> {
+        let workspace =
+            std::env::temp_dir().join(format!("aoc-scaffold-{test}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&workspace);
+        let template = workspace.join("2099/src/bin/template");
+        fs::create_dir_all(&template)?;
+        fs::write(template.join("main.rs"), "boilerplate!(Day, template);\n")?;
+        fs::write(template.join("samples.toml"), "[sample]\na = 0\nb = 0\n")?;
+        fs::write(template.join("sample.txt"), "")?;
+        fs::write(workspace.join("2099/src/days.rs"), DAYS_RS)?;
+        Ok(workspace)
+    }
+
+    #[test]
+    fn scaffolds_a_day() -> anyhow::Result<()> {
+        let workspace = workspace("scaffolds_a_day")?;
+        new_day(&workspace, 2099, 3, Some("1 2 3\n"))?;
+        let day = workspace.join("2099/src/bin/day3");
+        let read = |name| fs::read_to_string(day.join(name));
+        assert_eq!(read("main.rs")?, "boilerplate!(Day, day3);\n");
+        assert_eq!(read("samples.toml")?, "[sample]\n");
+        assert_eq!(read("answers.toml")?, "");
+        assert_eq!(read("sample.txt")?, "");
+        assert_eq!(read("data.txt")?, "1 2 3\n");
+        let days_rs = fs::read_to_string(workspace.join("2099/src/days.rs"))?;
+        assert!(
+            days_rs.contains(
+                "mod day1;\n#[path = \"bin/day3/main.rs\"]\nmod day3;\n#[path = \"bin/day5/main.rs\"]"
+            ),
+            "{days_rs}"
+        );
+        assert!(
+            days_rs.contains("(1, day1::REGISTERED),\n    (3, day3::REGISTERED),\n    (5,"),
+            "{days_rs}"
+        );
+        let error = new_day(&workspace, 2099, 3, None).unwrap_err();
+        assert!(error.to_string().contains("already exists"), "{error}");
+        Ok(())
+    }
+}
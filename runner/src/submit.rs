@@ -0,0 +1,311 @@
+//! The `submit` command: posts an answer and remembers the verdict, so an
+//! answer that was already rejected is never sent again.
+use std::{
+    fmt::{self, Display},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::{client::Client, registry};
+
+/// What the site made of an answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Right,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// Answered too recently, with how long is left to wait as the site
+    /// phrased it, like `"4m 32s"`
+    RateLimited(Option<String>),
+    /// The part was already solved, or part a hasn't been yet
+    WrongLevel,
+    /// Anything else, with the response's text
+    Unknown(String),
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Right => f.write_str("that's the right answer"),
+            Verdict::Wrong => f.write_str("that's not the right answer"),
+            Verdict::TooHigh => f.write_str("that's not the right answer, it's too high"),
+            Verdict::TooLow => f.write_str("that's not the right answer, it's too low"),
+            Verdict::RateLimited(Some(wait)) => {
+                write!(f, "answered too recently, {wait} left to wait")
+            }
+            Verdict::RateLimited(None) => f.write_str("answered too recently"),
+            Verdict::WrongLevel => {
+                f.write_str("this part can't be answered, is it already solved?")
+            }
+            Verdict::Unknown(text) => write!(f, "unexpected response: {text}"),
+        }
+    }
+}
+
+impl Verdict {
+    /// Reads the verdict from the `<article>` of the response page.
+    pub fn parse(page: &str) -> Self {
+        let text = article_text(page);
+        if text.contains("That's the right answer") {
+            Verdict::Right
+        } else if text.contains("That's not the right answer") {
+            if text.contains("too high") {
+                Verdict::TooHigh
+            } else if text.contains("too low") {
+                Verdict::TooLow
+            } else {
+                Verdict::Wrong
+            }
+        } else if text.contains("You gave an answer too recently") {
+            let wait = text
+                .split_once("You have ")
+                .and_then(|(_, rest)| rest.split_once(" left to wait"))
+                .map(|(wait, _)| wait.to_owned());
+            Verdict::RateLimited(wait)
+        } else if text.contains("You don't seem to be solving the right level") {
+            Verdict::WrongLevel
+        } else {
+            Verdict::Unknown(text)
+        }
+    }
+}
+
+/// The text of the page's `<article>`, or the whole page, without tags.
+fn article_text(page: &str) -> String {
+    let article = page
+        .split_once("<article")
+        .and_then(|(_, rest)| rest.split_once("</article>"))
+        .map_or(page, |(article, _)| article);
+    let mut text = String::new();
+    let mut in_tag = page.len() != article.len();
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// A part's submission history.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PartHistory {
+    accepted: Option<String>,
+    #[serde(default)]
+    rejected: Vec<String>,
+    /// The smallest answer that was too high
+    too_high: Option<i64>,
+    /// The largest answer that was too low
+    too_low: Option<i64>,
+}
+
+impl PartHistory {
+    /// Why `answer` is known to be wrong without asking, if it is.
+    fn known_wrong(&self, answer: &str) -> Option<String> {
+        if self.rejected.iter().any(|rejected| rejected == answer) {
+            return Some(format!("{answer} was already rejected"));
+        }
+        let number = answer.parse::<i64>().ok()?;
+        if let Some(too_high) = self.too_high.filter(|&too_high| number >= too_high) {
+            return Some(format!("{too_high} was already too high"));
+        }
+        if let Some(too_low) = self.too_low.filter(|&too_low| number <= too_low) {
+            return Some(format!("{too_low} was already too low"));
+        }
+        None
+    }
+
+    fn remember(&mut self, answer: &str, verdict: &Verdict) {
+        match verdict {
+            Verdict::Right => self.accepted = Some(answer.to_owned()),
+            Verdict::Wrong | Verdict::TooHigh | Verdict::TooLow => {
+                self.rejected.push(answer.to_owned());
+            }
+            _ => {}
+        }
+        let number = answer.parse::<i64>().ok();
+        match verdict {
+            Verdict::TooHigh => self.too_high = self.too_high.into_iter().chain(number).min(),
+            Verdict::TooLow => self.too_low = self.too_low.into_iter().chain(number).max(),
+            _ => {}
+        }
+    }
+}
+
+/// Every submission for a day, kept next to its cached input.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct History {
+    #[serde(default)]
+    a: PartHistory,
+    #[serde(default)]
+    b: PartHistory,
+}
+
+impl History {
+    fn path(cache_dir: &Path, year: u16, day: u8) -> PathBuf {
+        cache_dir.join(format!("{year}/day{day}-submissions.toml"))
+    }
+
+    fn load(path: &Path) -> anyhow::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(contents) => toml::from_str(&contents)
+                .with_context(|| format!("failed to parse {}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).with_context(|| format!("failed to read {}", path.display())),
+        }
+    }
+
+    fn save(&self, path: &Path) -> anyhow::Result<()> {
+        fs::create_dir_all(path.parent().context("histories are in a directory")?)?;
+        fs::write(path, toml::to_string(self)?)
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    fn part(&mut self, part: &str) -> &mut PartHistory {
+        if part == "a" {
+            &mut self.a
+        } else {
+            &mut self.b
+        }
+    }
+}
+
+/// Submits `answer` to `part` (`"a"` or `"b"`) unless it's known to be
+/// wrong, and records it in the day's `answers.toml` if it's right.
+pub fn submit(
+    client: &Client,
+    answers_toml: &Path,
+    year: u16,
+    day: u8,
+    part: &str,
+    answer: &str,
+) -> anyhow::Result<Verdict> {
+    let history_path = History::path(client.cache_dir(), year, day);
+    let mut history = History::load(&history_path)?;
+    let part_history = history.part(part);
+    match &part_history.accepted {
+        Some(accepted) if accepted == answer => bail!("{answer} was already accepted"),
+        Some(accepted) => bail!("part {part} was already solved with {accepted}"),
+        None => {}
+    }
+    if let Some(reason) = part_history.known_wrong(answer) {
+        bail!("not submitting {answer}: {reason}");
+    }
+    let level = if part == "a" { "1" } else { "2" };
+    let page = client.post(
+        &format!("/{year}/day/{day}/answer"),
+        &[("level", level), ("answer", answer)],
+    )?;
+    let verdict = Verdict::parse(&page);
+    part_history.remember(answer, &verdict);
+    history.save(&history_path)?;
+    if verdict == Verdict::Right {
+        record(answers_toml, part, answer)?;
+    }
+    Ok(verdict)
+}
+
+/// Runs the day on its embedded `data.txt` for the answer to `part`.
+pub fn solve(year: u16, day: u8, part: &str) -> anyhow::Result<String> {
+    let steps = (registry::day(year, day)?.run)(None, Some(part))?;
+    for (step, outcome, ..) in steps {
+        let answer = outcome?;
+        if step == part {
+            println!("{part}: {answer}");
+            return Ok(answer);
+        }
+    }
+    bail!("the run didn't answer the part")
+}
+
+/// Writes an accepted answer into `answers.toml`, the way `--record` does.
+fn record(answers_toml: &Path, part: &str, answer: &str) -> anyhow::Result<()> {
+    let mut table: toml::Table = match fs::read_to_string(answers_toml) {
+        Ok(contents) => contents
+            .parse()
+            .with_context(|| format!("failed to parse {}", answers_toml.display()))?,
+        Err(_) => toml::Table::new(),
+    };
+    table.insert(part.to_owned(), toml::Value::String(answer.to_owned()));
+    fs::write(answers_toml, table.to_string())
+        .with_context(|| format!("failed to write {}", answers_toml.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+
+    use super::*;
+    use crate::client::stand_in::serve;
+
+    fn page(article: &str) -> String {
+        format!("<html><main><article><p>{article}</p></article></main></html>")
+    }
+
+    #[test]
+    fn parses_verdicts() {
+        for (article, verdict) in [
+            (
+                "That's the right answer! You are <em>one gold star</em> closer.",
+                Verdict::Right,
+            ),
+            (
+                "That's not the right answer; your answer is too high. Please wait one minute.",
+                Verdict::TooHigh,
+            ),
+            (
+                "That's not the right answer; your answer is too low.",
+                Verdict::TooLow,
+            ),
+            (
+                "That's not the right answer. If you're stuck, make sure you're using the full input",
+                Verdict::Wrong,
+            ),
+            (
+                "You gave an answer too recently; you have to wait after submitting an answer \
+                 before trying again.  You have 4m 32s left to wait.",
+                Verdict::RateLimited(Some("4m 32s".into())),
+            ),
+            (
+                "You don't seem to be solving the right level.  Did you already complete it?",
+                Verdict::WrongLevel,
+            ),
+        ] {
+            assert_eq!(Verdict::parse(&page(article)), verdict, "{article}");
+        }
+    }
+
+    #[test]
+    fn remembers_verdicts() -> anyhow::Result<()> {
+        let (client, requests) = serve("remembers_verdicts", |request| {
+            assert_eq!(request.method, "POST");
+            assert_eq!(request.path, "/2022/day/7/answer");
+            let article = match request.body.as_str() {
+                "level=1&answer=100" => "That's not the right answer; your answer is too high.",
+                "level=1&answer=10" => "That's not the right answer; your answer is too low.",
+                "level=1&answer=42" => "That's the right answer!",
+                _ => "unexpected",
+            };
+            (200, page(article))
+        });
+        let answers_toml = client.cache_dir().join("answers.toml");
+        let submit = |answer| submit(&client, &answers_toml, 2022, 7, "a", answer);
+        assert_eq!(submit("100")?, Verdict::TooHigh);
+        assert_eq!(submit("10")?, Verdict::TooLow);
+        for known_wrong in ["100", "150", "5"] {
+            assert!(submit(known_wrong).is_err());
+        }
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+        assert_eq!(submit("42")?, Verdict::Right);
+        assert!(submit("42").is_err());
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+        assert_eq!(fs::read_to_string(&answers_toml)?, "a = \"42\"\n");
+        Ok(())
+    }
+}
@@ -0,0 +1,285 @@
+//! The `watch` command: re-runs a day's tests whenever its files change and
+//! shows how each sample and the real input did.
+use std::{
+    path::Path,
+    process::Command,
+    sync::mpsc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Context;
+use notify::{EventKind, RecursiveMode, Watcher};
+
+/// Clears the terminal and moves the cursor to the top.
+const CLEAR: &str = "\x1b[2J\x1b[H";
+
+/// How long to wait for an editor to finish writing before re-running.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+pub fn watch(workspace: &Path, year: u16, day: u8) -> anyhow::Result<()> {
+    let day_dir = workspace.join(format!("{year}/src/bin/day{day}"));
+    if !day_dir.is_dir() {
+        anyhow::bail!("{} doesn't exist", day_dir.display());
+    }
+    let (events, changes) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(events)?;
+    watcher
+        .watch(&day_dir, RecursiveMode::NonRecursive)
+        .with_context(|| format!("failed to watch {}", day_dir.display()))?;
+    loop {
+        let panel = match run_tests(workspace, year, day) {
+            Ok(TestRun::Built(outcomes)) => panel(&outcomes),
+            Ok(TestRun::BuildFailed(errors)) => errors,
+            Err(e) => format!("failed to run the tests: {e:#}"),
+        };
+        print!("{CLEAR}{year} day {day}, at {}\n\n{panel}", clock());
+        println!("\nwatching {} for changes", day_dir.display());
+        loop {
+            let event = changes.recv()??;
+            let relevant = event.paths.iter().any(|path| is_watched(path));
+            if relevant && !matches!(event.kind, EventKind::Access(_)) {
+                break;
+            }
+        }
+        while changes.recv_timeout(DEBOUNCE).is_ok() {}
+    }
+}
+
+/// The day's solution, its samples and answers, and its input.
+fn is_watched(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    matches!(
+        name,
+        "main.rs" | "data.txt" | "samples.toml" | "answers.toml"
+    ) || (name.starts_with("sample") && path.extension().is_some_and(|ext| ext == "txt"))
+}
+
+/// The time of day in UTC, as `hh:mm:ss`.
+fn clock() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| now.as_secs());
+    format!(
+        "{:02}:{:02}:{:02} UTC",
+        secs / 3600 % 24,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+enum TestRun {
+    Built(Vec<Outcome>),
+    /// The compiler's errors
+    BuildFailed(String),
+}
+
+/// How one test went.
+#[derive(Debug, PartialEq, Eq)]
+struct Outcome {
+    /// The test's name without the module, like `a_sample`
+    name: String,
+    passed: bool,
+    /// What the test printed, and why it failed
+    output: Vec<String>,
+}
+
+fn run_tests(workspace: &Path, year: u16, day: u8) -> anyhow::Result<TestRun> {
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".into());
+    let output = Command::new(cargo)
+        .current_dir(workspace)
+        .args(["test", "-p", &format!("advent-{year}")])
+        .args(["--bin", &format!("day{day}"), "--", "--show-output"])
+        .env("RUST_BACKTRACE", "0")
+        .output()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !stdout.contains("running ") {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let errors: Vec<_> = stderr
+            .lines()
+            .skip_while(|line| !line.starts_with("error"))
+            .take(30)
+            .collect();
+        return Ok(TestRun::BuildFailed(errors.join("\n") + "\n"));
+    }
+    Ok(TestRun::Built(parse_outcomes(&stdout)))
+}
+
+/// Reads libtest's output, as printed with `--show-output`.
+fn parse_outcomes(stdout: &str) -> Vec<Outcome> {
+    let mut outcomes: Vec<Outcome> = stdout
+        .lines()
+        .filter_map(|line| {
+            let (name, result) = line.strip_prefix("test ")?.split_once(" ... ")?;
+            let name = name.rsplit("::").next()?;
+            Some(Outcome {
+                name: name.to_owned(),
+                passed: result == "ok",
+                output: Vec::new(),
+            })
+        })
+        .collect();
+    let mut current = None;
+    for line in stdout.lines() {
+        if let Some(name) = line
+            .strip_prefix("---- ")
+            .and_then(|line| line.strip_suffix(" stdout ----"))
+        {
+            let name = name.rsplit("::").next().unwrap_or(name);
+            current = outcomes.iter().position(|outcome| outcome.name == name);
+        } else if matches!(line, "successes:" | "failures:") {
+            current = None;
+        } else if let Some(i) = current {
+            outcomes[i].output.push(line.to_owned());
+        }
+    }
+    for outcome in &mut outcomes {
+        while outcome
+            .output
+            .last()
+            .is_some_and(|line| line.trim().is_empty())
+        {
+            outcome.output.pop();
+        }
+    }
+    outcomes
+}
+
+/// A row per part's test: which input and part it ran, whether it passed,
+/// its answer, and for failures what was expected or what went wrong. The
+/// day's other tests share a row after them.
+fn panel(outcomes: &[Outcome]) -> String {
+    let mut rows = Vec::new();
+    let mut others = Vec::new();
+    for outcome in outcomes {
+        let position = part_and_input(&outcome.name);
+        let prefix = format!(
+            "{}: ",
+            position.map_or(outcome.name.as_str(), |(part, _)| part)
+        );
+        let mut answer: Vec<&str> = Vec::new();
+        let mut failure = None;
+        let mut lines = outcome.output.iter();
+        while let Some(line) = lines.next() {
+            if let Some(first) = line.strip_prefix(&prefix) {
+                answer.push(first);
+            } else if let Some(expected) = line.trim_start().strip_prefix("right: ") {
+                failure = Some(format!("expected {expected}"));
+            } else if line.starts_with("thread '") && failure.is_none() {
+                failure = lines.next().cloned();
+            } else if let Some(error) = line.strip_prefix("Error: ") {
+                failure = Some(error.to_owned());
+            } else if !answer.is_empty() && failure.is_none() && !line.is_empty() {
+                answer.push(line);
+            }
+        }
+        let status = if outcome.passed { "ok" } else { "FAILED" };
+        let failure = failure.map(|failure| format!("  ({failure})"));
+        let Some((part, input)) = position else {
+            others.push(format!(
+                "{} {status}{}",
+                outcome.name,
+                failure.unwrap_or_default()
+            ));
+            continue;
+        };
+        let answer = match answer.as_slice() {
+            [] if outcome.passed && input == "data.txt" => "no data.txt".to_owned(),
+            [] => "-".to_owned(),
+            [answer] => (*answer).to_owned(),
+            lines => format!("\n{}", lines.join("\n")),
+        };
+        rows.push(format!(
+            "  {input:<12}{part}  {status:<8}{answer}{}",
+            failure.unwrap_or_default()
+        ));
+    }
+    if !others.is_empty() {
+        rows.push(format!("  {:<12}{}", "other tests", others.join(", ")));
+    }
+    rows.join("\n") + "\n"
+}
+
+/// The part a test runs, and on which input: `a` and `b` run the real input
+/// and `<part>_<sample>` a sample. Other tests aren't about a part.
+fn part_and_input(name: &str) -> Option<(&str, &str)> {
+    match name.split_once('_') {
+        None if matches!(name, "a" | "b") => Some((name, "data.txt")),
+        Some((part @ ("a" | "b"), sample)) => Some((part, sample)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUTPUT: &str = "
+running 6 tests
+test tests::a ... ok
+test tests::a_sample ... ok
+test tests::b ... ok
+test tests::b_sample ... FAILED
+test tests::generated ... ok
+test tests::matches_reference ... ok
+
+successes:
+
+---- tests::a stdout ----
+
+---- tests::a_sample stdout ----
+a: 6440
+
+---- tests::generated stdout ----
+
+---- tests::matches_reference stdout ----
+
+
+successes:
+    tests::a
+    tests::a_sample
+    tests::b
+    tests::generated
+    tests::matches_reference
+
+failures:
+
+---- tests::b_sample stdout ----
+b: 5905
+
+thread 'tests::b_sample' (16744) panicked at aoc-core/src/lib.rs:273:5:
+assertion `left == right` failed: part b
+  left: \"5905\"
+ right: \"5904\"
+note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+
+
+failures:
+    tests::b_sample
+
+test result: FAILED. 5 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out
+";
+
+    #[test]
+    fn parses_outcomes() {
+        let outcomes = parse_outcomes(OUTPUT);
+        assert_eq!(outcomes.len(), 6);
+        assert_eq!(outcomes[1].output, ["a: 6440"]);
+        assert!(!outcomes[3].passed);
+        assert_eq!(outcomes[3].output[0], "b: 5905");
+    }
+
+    #[test]
+    fn shows_expected_answers() {
+        assert_eq!(
+            panel(&parse_outcomes(OUTPUT)),
+            "  data.txt    a  ok      no data.txt
+  sample      a  ok      6440
+  data.txt    b  ok      no data.txt
+  sample      b  FAILED  5905  (expected \"5904\")
+  other tests generated ok, matches_reference ok
+"
+        );
+    }
+}